@@ -14,11 +14,18 @@ pub enum TrySendError<T> {
 }
 
 /// Async and Sync MPMP Sender.
-#[derive(Clone)]
 pub struct Sender<T> {
     inner: async_channel::Sender<T>,
 }
 
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for Sender<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Sender").finish()
@@ -56,6 +63,12 @@ pin_project_lite::pin_project! {
     }
 }
 
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
 impl<T> Receiver<T> {
     /// Asynchronously receives an item
     ///
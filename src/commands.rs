@@ -4,6 +4,16 @@
 //!
 //! The [functions][functions] in this module produce borrowed types in the [`types`][types] module. You can store the [`types`][types] for multiple encodings.
 //!
+//! Encoding a command fails with an [`EncodeError`][encode_error] if its arguments would produce a malformed line,
+//! e.g. a message containing `\r\n` or a channel containing a space. Use [`sanitize`][sanitize] to clean up text
+//! you'd rather send anyway.
+//!
+//! Commands that take a duration, e.g. [`timeout`][timeout], take a [`ChatDuration`][chat_duration] and check it
+//! against the range Twitch allows for that command.
+//!
+//! Lines produced by these commands can be decoded again with [`ClientCommand`][client_command],
+//! and [`parse_input`][parse_input] turns a slash command a user typed, e.g. `/timeout foo 10m`, into one.
+//!
 //! ### Some provided encoders:
 //! * [`AsyncEncoder`](../struct.AsyncEncoder.html)
 //! * [`Encoder`](../struct.Encoder.html)
@@ -13,6 +23,12 @@
 //! [functions]: ./index.html#functions
 //! [types]: ./types/index.html
 //! [encodable]: ./trait.Encodable.html
+//! [encode_error]: ./enum.EncodeError.html
+//! [sanitize]: ./fn.sanitize.html
+//! [timeout]: ./fn.timeout.html
+//! [chat_duration]: ./struct.ChatDuration.html
+//! [client_command]: ./enum.ClientCommand.html
+//! [parse_input]: ./fn.parse_input.html
 pub(crate) use super::Encodable;
 
 macro_rules! write_cmd {
@@ -63,6 +79,8 @@ macro_rules! export_commands {
 
 export_commands! {
     ban             => Ban
+    cap_ls          => CapLs
+    cap_req         => CapReq
     clear           => Clear
     color           => Color
     command         => Command
@@ -106,6 +124,20 @@ export_commands! {
     whisper         => Whisper
 }
 
+pub use color::color_checked;
+
+pub(crate) mod check;
+pub use check::{sanitize, EncodeError};
+
+pub(crate) mod duration;
+pub use duration::{ChatDuration, DurationError};
+
+mod client_command;
+pub use client_command::ClientCommand;
+
+mod input;
+pub use input::{parse_input, InputError};
+
 macro_rules! serde_for_commands {
     (@one $($x:tt)*) => { () };
     (@len $($e:expr),*) => { <[()]>::len(&[$(serde_for_commands!(@one $e)),*]); };
@@ -141,6 +173,8 @@ macro_rules! serde_for_commands {
 
 serde_for_commands! {
     Ban { channel, username, reason };
+    CapLs { };
+    CapReq { capabilities };
     Clear { channel };
     Color { color };
     Command { channel, data };
@@ -228,11 +262,7 @@ pub(crate) struct Channel<'a>(pub &'a str);
 
 impl<'a> Display for Channel<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.0.starts_with('#') {
-            write!(f, "{}", self.0)
-        } else {
-            write!(f, "#{}", self.0)
-        }
+        crate::twitch::ChannelName::from_server(self.0).fmt(f)
     }
 }
 
@@ -243,6 +273,13 @@ fn test_encode(enc: impl Encodable, expected: impl for<'a> PartialEq<&'a str> +
     assert_eq!(expected, std::str::from_utf8(&data).unwrap());
 }
 
+#[cfg(test)]
+fn test_encode_err(enc: impl Encodable, expected: EncodeError) {
+    let err = enc.encode(&mut vec![]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(EncodeError::from_io(&err), Some(&expected));
+}
+
 #[cfg(all(test, feature = "serde"))]
 fn test_serde<'de, T>(enc: T, expected: impl for<'a> PartialEq<&'a str> + std::fmt::Debug)
 where
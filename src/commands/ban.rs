@@ -1,4 +1,4 @@
-use super::{Channel, Encodable, MaybeEmpty};
+use super::{check, Channel, Encodable, MaybeEmpty};
 use std::io::{Result, Write};
 
 /// Permanently prevent a user from chatting.
@@ -29,11 +29,19 @@ impl<'a> Encodable for Ban<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::name("username", self.username)?;
+        check::opt_text("reason", self.reason)?;
+
         write_cmd!(buf,
             Channel(self.channel) =>
             "/ban {}{}", self.username, MaybeEmpty(self.reason)
         )
     }
+
+    fn priority(&self) -> crate::writer::Priority {
+        crate::writer::Priority::Moderation
+    }
 }
 
 #[cfg(test)]
@@ -57,6 +65,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ban_injection() {
+        test_encode_err(
+            ban("#museun", "museun", "spam\n/mod museun"),
+            EncodeError::InvalidCharacter {
+                field: "reason",
+                character: '\n',
+            },
+        );
+
+        test_encode_err(
+            ban("#museun", ":museun", None),
+            EncodeError::InvalidName {
+                field: "username",
+                name: ":museun".to_string(),
+            },
+        );
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn ban_serde() {
@@ -0,0 +1,48 @@
+use super::Encodable;
+use std::io::{Result, Write};
+
+/// Ask the server which capabilities it supports.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct CapLs<'a> {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    marker: std::marker::PhantomData<&'a CapLs<'a>>,
+}
+
+/// Ask the server which capabilities it supports.
+///
+/// The server replies with a `CAP * LS` [Cap] message listing them.
+///
+/// [Cap]: ../messages/struct.Cap.html
+pub const fn cap_ls() -> CapLs<'static> {
+    CapLs {
+        marker: std::marker::PhantomData,
+    }
+}
+
+impl<'a> Encodable for CapLs<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        write!(buf, "CAP LS 302\r\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn cap_ls_encode() {
+        test_encode(cap_ls(), "CAP LS 302\r\n")
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn cap_ls_serde() {
+        test_serde(cap_ls(), "CAP LS 302\r\n")
+    }
+}
@@ -0,0 +1,97 @@
+use super::{check, Encodable, EncodeError};
+use crate::twitch::Capability;
+use std::io::{Result, Write};
+
+/// Request several capabilities at once.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct CapReq<'a> {
+    pub(crate) capabilities: Vec<Capability>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    marker: std::marker::PhantomData<&'a CapReq<'a>>,
+}
+
+/// Request several capabilities at once.
+///
+/// The server either acknowledges all of them with a `CAP * ACK`, or denies
+/// all of them with a `CAP * NAK`. See [Cap] for the reply.
+///
+/// [Cap]: ../messages/struct.Cap.html
+pub fn cap_req(capabilities: &[Capability]) -> CapReq<'static> {
+    CapReq {
+        capabilities: capabilities.to_vec(),
+        marker: std::marker::PhantomData,
+    }
+}
+
+impl<'a> Encodable for CapReq<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        if self.capabilities.is_empty() {
+            return Err(EncodeError::EmptyName {
+                field: "capabilities",
+            }
+            .into());
+        }
+        for cap in &self.capabilities {
+            check::name("capabilities", cap.as_str())?;
+        }
+
+        write!(buf, "CAP REQ :")?;
+        for (i, cap) in self.capabilities.iter().enumerate() {
+            if i > 0 {
+                write!(buf, " ")?;
+            }
+            write!(buf, "{}", cap)?;
+        }
+        write!(buf, "\r\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn cap_req_encode() {
+        test_encode(cap_req(&[Capability::Tags]), "CAP REQ :twitch.tv/tags\r\n");
+        test_encode(
+            cap_req(&[
+                Capability::Tags,
+                Capability::Commands,
+                Capability::Custom("twitch.tv/foo".into()),
+            ]),
+            "CAP REQ :twitch.tv/tags twitch.tv/commands twitch.tv/foo\r\n",
+        );
+    }
+
+    #[test]
+    fn cap_req_invalid() {
+        test_encode_err(
+            cap_req(&[]),
+            EncodeError::EmptyName {
+                field: "capabilities",
+            },
+        );
+        test_encode_err(
+            cap_req(&[Capability::Custom("twitch.tv/foo bar".into())]),
+            EncodeError::InvalidName {
+                field: "capabilities",
+                name: "twitch.tv/foo bar".into(),
+            },
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn cap_req_serde() {
+        test_serde(
+            cap_req(&[Capability::Tags, Capability::Commands]),
+            "CAP REQ :twitch.tv/tags twitch.tv/commands\r\n",
+        );
+    }
+}
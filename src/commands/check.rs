@@ -0,0 +1,270 @@
+use super::ChatDuration;
+use std::{borrow::Cow, ops::RangeInclusive};
+
+/// An error returned when a command's arguments would produce a malformed, or injected, IRC line
+///
+/// This is returned as the inner error of a `std::io::Error` of kind `InvalidInput`,
+/// use [EncodeError::from_io] to get it back.
+///
+/// [EncodeError::from_io]: ./enum.EncodeError.html#method.from_io
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeError {
+    /// A field contained a carriage return, line feed or NUL
+    InvalidCharacter {
+        /// The field that was invalid
+        field: &'static str,
+        /// The character that isn't allowed
+        character: char,
+    },
+    /// A channel or user name contained a space or a comma, or started with a colon
+    InvalidName {
+        /// The field that was invalid
+        field: &'static str,
+        /// The name that was provided
+        name: String,
+    },
+    /// A channel or user name was empty
+    EmptyName {
+        /// The field that was empty
+        field: &'static str,
+    },
+    /// A duration was outside of the range Twitch allows for the command
+    DurationOutOfRange {
+        /// The field that was invalid
+        field: &'static str,
+        /// The duration that was provided
+        duration: ChatDuration,
+        /// The shortest allowed duration
+        min: ChatDuration,
+        /// The longest allowed duration
+        max: ChatDuration,
+    },
+}
+
+impl EncodeError {
+    /// Get the `EncodeError` from an `std::io::Error` returned by [Encodable::encode], if it was one
+    ///
+    /// [Encodable::encode]: ../trait.Encodable.html#tymethod.encode
+    pub fn from_io(err: &std::io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|err| err.downcast_ref())
+    }
+}
+
+impl std::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCharacter { field, character } => write!(
+                f,
+                "'{}' cannot contain the character {:?}",
+                field, character
+            ),
+            Self::InvalidName { field, name } => write!(
+                f,
+                "'{}' is not a valid name: {:?}. it cannot contain spaces or commas, or start with a colon",
+                field, name
+            ),
+            Self::EmptyName { field } => write!(f, "'{}' cannot be empty", field),
+            Self::DurationOutOfRange {
+                field,
+                duration,
+                min,
+                max,
+            } => write!(
+                f,
+                "'{}' must be between {} and {}, got {}",
+                field, min, max, duration
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+impl From<EncodeError> for std::io::Error {
+    fn from(err: EncodeError) -> Self {
+        Self::new(std::io::ErrorKind::InvalidInput, err)
+    }
+}
+
+/// Replace any carriage returns, line feeds and NULs in `text` with spaces
+///
+/// The commands reject text containing these, as they would let the text
+/// inject extra lines. Use this when you'd rather send the text anyway.
+///
+/// # Example
+/// ```
+/// # use twitchchat::commands;
+/// let msg = commands::sanitize("hello\r\nJOIN #museun");
+/// assert_eq!(msg, "hello  JOIN #museun");
+///
+/// let msg = commands::sanitize("hello world");
+/// assert!(matches!(msg, std::borrow::Cow::Borrowed(..)));
+/// ```
+pub fn sanitize(text: &str) -> Cow<'_, str> {
+    if !text.contains(is_forbidden) {
+        return Cow::Borrowed(text);
+    }
+    text.chars()
+        .map(|c| if is_forbidden(c) { ' ' } else { c })
+        .collect::<String>()
+        .into()
+}
+
+fn is_forbidden(c: char) -> bool {
+    matches!(c, '\r' | '\n' | '\0')
+}
+
+/// Ensure `text` won't break out of its line
+pub(crate) fn text(field: &'static str, text: &str) -> Result<(), EncodeError> {
+    match text.chars().find(|&c| is_forbidden(c)) {
+        Some(character) => Err(EncodeError::InvalidCharacter { field, character }),
+        None => Ok(()),
+    }
+}
+
+/// Ensure the optional `text` won't break out of its line
+pub(crate) fn opt_text(field: &'static str, text: Option<&str>) -> Result<(), EncodeError> {
+    text.map_or(Ok(()), |data| self::text(field, data))
+}
+
+/// Ensure `name` is a single, non-empty, parameter
+pub(crate) fn name(field: &'static str, name: &str) -> Result<(), EncodeError> {
+    text(field, name)?;
+    if name.is_empty() {
+        return Err(EncodeError::EmptyName { field });
+    }
+    if name.starts_with(':') || name.contains(&[' ', ','][..]) {
+        return Err(EncodeError::InvalidName {
+            field,
+            name: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Ensure `channel` is a single, non-empty, parameter. The leading `#` is optional
+pub(crate) fn channel(field: &'static str, channel: &str) -> Result<(), EncodeError> {
+    let stripped = channel.strip_prefix('#').unwrap_or(channel);
+    match name(field, stripped) {
+        Err(EncodeError::InvalidName { field, .. }) => Err(EncodeError::InvalidName {
+            field,
+            name: channel.to_string(),
+        }),
+        res => res,
+    }
+}
+
+/// Ensure `duration` is within the `range` Twitch allows
+pub(crate) fn duration(
+    field: &'static str,
+    duration: ChatDuration,
+    range: &RangeInclusive<ChatDuration>,
+) -> Result<(), EncodeError> {
+    if range.contains(&duration) {
+        return Ok(());
+    }
+    Err(EncodeError::DurationOutOfRange {
+        field,
+        duration,
+        min: *range.start(),
+        max: *range.end(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text() {
+        assert!(super::text("msg", "hello world").is_ok());
+        for &(input, character) in &[("a\rb", '\r'), ("a\nb", '\n'), ("a\0b", '\0')] {
+            assert_eq!(
+                super::text("msg", input).unwrap_err(),
+                EncodeError::InvalidCharacter {
+                    field: "msg",
+                    character
+                }
+            );
+        }
+        assert!(opt_text("reason", None).is_ok());
+        assert!(opt_text("reason", Some("a\r\nb")).is_err());
+    }
+
+    #[test]
+    fn name() {
+        assert!(super::name("username", "museun").is_ok());
+        assert_eq!(
+            super::name("username", "").unwrap_err(),
+            EncodeError::EmptyName { field: "username" }
+        );
+        for &input in &["foo bar", ":foo", "foo,bar"] {
+            assert_eq!(
+                super::name("username", input).unwrap_err(),
+                EncodeError::InvalidName {
+                    field: "username",
+                    name: input.to_string()
+                }
+            );
+        }
+        assert!(matches!(
+            super::name("username", "foo\r\nbar").unwrap_err(),
+            EncodeError::InvalidCharacter { .. }
+        ));
+    }
+
+    #[test]
+    fn channel() {
+        assert!(super::channel("channel", "#museun").is_ok());
+        assert!(super::channel("channel", "museun").is_ok());
+        assert_eq!(
+            super::channel("channel", "#").unwrap_err(),
+            EncodeError::EmptyName { field: "channel" }
+        );
+        assert_eq!(
+            super::channel("channel", "#foo bar").unwrap_err(),
+            EncodeError::InvalidName {
+                field: "channel",
+                name: "#foo bar".to_string()
+            }
+        );
+        assert!(super::channel("channel", "#:foo").is_err());
+    }
+
+    #[test]
+    fn duration() {
+        let range = ChatDuration::seconds(1)..=ChatDuration::minutes(30);
+        assert!(super::duration("duration", ChatDuration::seconds(1), &range).is_ok());
+        assert!(super::duration("duration", ChatDuration::minutes(30), &range).is_ok());
+        assert_eq!(
+            super::duration("duration", ChatDuration::ZERO, &range).unwrap_err(),
+            EncodeError::DurationOutOfRange {
+                field: "duration",
+                duration: ChatDuration::ZERO,
+                min: ChatDuration::seconds(1),
+                max: ChatDuration::minutes(30),
+            }
+        );
+        assert!(super::duration("duration", ChatDuration::hours(1), &range).is_err());
+    }
+
+    #[test]
+    fn sanitize() {
+        assert_eq!(super::sanitize("a\r\nb\0c"), "a  b c");
+        assert!(matches!(super::sanitize("abc"), Cow::Borrowed("abc")));
+    }
+
+    #[test]
+    fn from_io() {
+        let err: std::io::Error = EncodeError::EmptyName { field: "channel" }.into();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(
+            EncodeError::from_io(&err),
+            Some(&EncodeError::EmptyName { field: "channel" })
+        );
+
+        let err = std::io::Error::new(std::io::ErrorKind::Other, "something else");
+        assert_eq!(EncodeError::from_io(&err), None);
+    }
+}
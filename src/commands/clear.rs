@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Clear chat history for all users in this room.
@@ -19,8 +19,14 @@ impl<'a> Encodable for Clear<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+
         write_cmd!(buf, Channel(self.channel) => "/clear")
     }
+
+    fn priority(&self) -> crate::writer::Priority {
+        crate::writer::Priority::Moderation
+    }
 }
 
 #[cfg(test)]
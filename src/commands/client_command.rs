@@ -0,0 +1,368 @@
+use super::{types::*, Encodable};
+use crate::{irc::IrcMessage, MaybeOwned, MessageError};
+use std::io::{Result, Write};
+
+macro_rules! client_commands {
+    ($($ty:ident)*) => {
+        /// A command sent by a client, decoded back into its [type][types]
+        ///
+        /// This is the inverse of the [functions][functions] in this module. It
+        /// is useful for mock servers, proxies and for asserting on what was sent.
+        ///
+        /// Slash commands sent to a channel, e.g. `PRIVMSG #museun :/timeout foo 600`,
+        /// and to `jtv`, e.g. `PRIVMSG jtv :/w museun hello`, are decoded to
+        /// their specific type. Unknown slash commands become a [Command][command]
+        /// or a [JtvCommand][jtv_command], and any other line becomes a [Raw][raw].
+        ///
+        /// [Register][register] is sent as several lines, so its `PASS` and `NICK`
+        /// lines are decoded as [Raw][raw] and its `CAP REQ` lines as [CapReq][cap_req].
+        ///
+        /// # Example
+        /// ```
+        /// use twitchchat::commands::{self, ClientCommand};
+        ///
+        /// let cmd = ClientCommand::parse("PRIVMSG #museun :/timeout foo 600 spam\r\n").unwrap();
+        /// assert_eq!(
+        ///     cmd,
+        ///     ClientCommand::Timeout(commands::timeout(
+        ///         "#museun",
+        ///         "foo",
+        ///         commands::ChatDuration::minutes(10),
+        ///         "spam"
+        ///     ))
+        /// );
+        /// ```
+        ///
+        /// [types]: ./types/index.html
+        /// [functions]: ./index.html#functions
+        /// [command]: ./types/struct.Command.html
+        /// [jtv_command]: ./types/struct.JtvCommand.html
+        /// [raw]: ./types/struct.Raw.html
+        /// [register]: ./types/struct.Register.html
+        /// [cap_req]: ./types/struct.CapReq.html
+        #[non_exhaustive]
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum ClientCommand<'a> {
+            $(
+                #[doc = concat!("A `", stringify!($ty), "` command")]
+                $ty($ty<'a>),
+            )*
+        }
+
+        impl<'a> Encodable for ClientCommand<'a> {
+            fn encode<W>(&self, buf: &mut W) -> Result<()>
+            where
+                W: Write + ?Sized,
+            {
+                match self {
+                    $( Self::$ty(cmd) => cmd.encode(buf), )*
+                }
+            }
+
+            fn priority(&self) -> crate::writer::Priority {
+                match self {
+                    $( Self::$ty(cmd) => cmd.priority(), )*
+                }
+            }
+
+            fn time_to_live(&self) -> Option<std::time::Duration> {
+                match self {
+                    $( Self::$ty(cmd) => cmd.time_to_live(), )*
+                }
+            }
+        }
+
+        $(
+            impl<'a> From<$ty<'a>> for ClientCommand<'a> {
+                fn from(cmd: $ty<'a>) -> Self {
+                    Self::$ty(cmd)
+                }
+            }
+        )*
+    };
+}
+
+client_commands! {
+    Ban CapLs CapReq Clear Color Command Commercial Disconnect EmoteOnly EmoteOnlyOff
+    Followers FollowersOff GiveMod Help Host Join JtvCommand Marker Me Mods
+    Part Ping Pong Privmsg R9kBeta R9kBetaOff Raid Raw Reply Slow SlowOff
+    Subscribers SubscribersOff Timeout Unban Unhost Unmod Unraid Untimeout
+    Unvip Vip Vips Whisper
+}
+
+impl<'a> ClientCommand<'a> {
+    /// Decode a single line, with or without its trailing `\r\n`
+    pub fn parse(line: &'a str) -> std::result::Result<Self, MessageError> {
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(line))?;
+        decode(line, &msg)
+    }
+
+    /// Decode an `IrcMessage`, borrowing from it
+    pub fn from_irc(msg: &'a IrcMessage<'_>) -> std::result::Result<Self, MessageError> {
+        decode(&msg.raw, msg)
+    }
+}
+
+fn decode<'a>(
+    raw: &'a str,
+    msg: &IrcMessage<'_>,
+) -> std::result::Result<ClientCommand<'a>, MessageError> {
+    let mut args = msg
+        .args
+        .map(|index| &raw[index])
+        .unwrap_or_default()
+        .split_ascii_whitespace();
+    let data = msg.data.map(|index| &raw[index]);
+
+    let cmd = match (&raw[msg.command], args.next(), data) {
+        (IrcMessage::PRIVMSG, Some("jtv"), Some(data)) => decode_jtv(data),
+        (IrcMessage::PRIVMSG, Some(channel), Some(data)) => match reply_parent(raw, msg) {
+            Some(msg_id) => super::reply(channel, msg_id, data).into(),
+            None => decode_channel(channel, data),
+        },
+        (IrcMessage::JOIN, Some(channel), None) => super::join(channel).into(),
+        (IrcMessage::PART, Some(channel), None) => super::part(channel).into(),
+        (IrcMessage::PING, Some(token), None) | (IrcMessage::PING, None, Some(token)) => {
+            super::ping(token).into()
+        }
+        (IrcMessage::PONG, None, Some(token)) | (IrcMessage::PONG, Some(token), None) => {
+            super::pong(token).into()
+        }
+        (IrcMessage::CAP, Some("LS"), None) => super::cap_ls().into(),
+        (IrcMessage::CAP, Some("REQ"), Some(caps)) if !caps.trim().is_empty() => {
+            let caps = caps.split_ascii_whitespace().map(Into::into);
+            super::cap_req(&caps.collect::<Vec<_>>()).into()
+        }
+        _ => super::raw(raw.trim_end_matches("\r\n")).into(),
+    };
+    Ok(cmd)
+}
+
+/// The `reply-parent-msg-id` tag, if there was one
+fn reply_parent<'a>(raw: &'a str, msg: &IrcMessage<'_>) -> Option<&'a str> {
+    raw[msg.tags?]
+        .trim_start_matches('@')
+        .split(';')
+        .find_map(|tag| tag.strip_prefix("reply-parent-msg-id="))
+}
+
+/// Split a slash command into its name and the rest of the line
+fn split_command(data: &str) -> Option<(&str, &str)> {
+    let data = data.strip_prefix('/')?;
+    let mut iter = data.splitn(2, ' ');
+    let name = iter.next()?;
+    let rest = iter.next().map(str::trim).unwrap_or_default();
+    Some((name, rest))
+}
+
+fn decode_jtv(data: &str) -> ClientCommand<'_> {
+    let fallback = || super::jtv_command(data).into();
+    let (name, rest) = match split_command(data) {
+        Some(split) => split,
+        None => return fallback(),
+    };
+
+    match (name, rest) {
+        ("color", color) => super::color(color)
+            .map(Into::into)
+            .unwrap_or_else(|_| fallback()),
+        ("disconnect", "") => super::disconnect().into(),
+        ("w", rest) => match split_word(rest) {
+            (username, Some(message)) => super::whisper(username, message).into(),
+            _ => fallback(),
+        },
+        _ => fallback(),
+    }
+}
+
+fn decode_channel<'a>(channel: &'a str, data: &'a str) -> ClientCommand<'a> {
+    let fallback = || command(channel, data).into();
+    let (name, rest) = match split_command(data) {
+        Some(split) => split,
+        None => return privmsg(channel, data).into(),
+    };
+
+    let (first, remaining) = split_word(rest);
+    let no_args = rest.is_empty();
+    let one_arg = !first.is_empty() && remaining.is_none();
+
+    use super::*;
+    match name {
+        "ban" if !first.is_empty() => ban(channel, first, remaining).into(),
+        "clear" if no_args => clear(channel).into(),
+        "commercial" if no_args => commercial(channel, None).into(),
+        "commercial" => match rest.parse::<ChatDuration>() {
+            Ok(length) => commercial(channel, Some(length)).into(),
+            Err(..) => fallback(),
+        },
+        "emoteonly" if no_args => emote_only(channel).into(),
+        "emoteonlyoff" if no_args => emote_only_off(channel).into(),
+        "followers" => match rest.parse::<ChatDuration>() {
+            Ok(duration) => followers(channel, duration).into(),
+            Err(..) => fallback(),
+        },
+        "followersoff" if no_args => followers_off(channel).into(),
+        "mod" if one_arg => give_mod(channel, first).into(),
+        "help" if no_args => help(channel).into(),
+        "host" if one_arg => host(channel, first).into(),
+        "marker" if no_args => marker(channel, None).into(),
+        "marker" => marker(channel, rest).into(),
+        "me" if !no_args => me(channel, rest).into(),
+        "mods" if no_args => mods(channel).into(),
+        "r9kbeta" if no_args => r9k_beta(channel).into(),
+        "r9kbetaoff" if no_args => r9k_beta_off(channel).into(),
+        "raid" if one_arg => raid(channel, first).into(),
+        "slow" => match rest.parse::<ChatDuration>() {
+            Ok(duration) => slow(channel, Some(duration)).into(),
+            Err(..) => fallback(),
+        },
+        "slowoff" if no_args => slow_off(channel).into(),
+        "subscribers" if no_args => subscribers(channel).into(),
+        "subscribersoff" if no_args => subscribers_off(channel).into(),
+        "timeout" if !first.is_empty() => {
+            // the duration is optional, but always comes before the reason
+            let (duration, reason) = match remaining.map(split_word) {
+                Some((duration, reason)) => match duration.parse::<ChatDuration>() {
+                    Ok(duration) => (Some(duration), reason),
+                    Err(..) => (None, remaining),
+                },
+                None => (None, None),
+            };
+            timeout(channel, first, duration, reason).into()
+        }
+        "unban" if one_arg => unban(channel, first).into(),
+        "unhost" if no_args => unhost(channel).into(),
+        "unmod" if one_arg => unmod(channel, first).into(),
+        "unraid" if no_args => unraid(channel).into(),
+        "untimeout" if one_arg => untimeout(channel, first).into(),
+        "unvip" if one_arg => unvip(channel, first).into(),
+        "vip" if one_arg => vip(channel, first).into(),
+        "vips" if no_args => vips(channel).into(),
+        _ => fallback(),
+    }
+}
+
+/// Split off the first word, returning the rest if there is any
+pub(super) fn split_word(input: &str) -> (&str, Option<&str>) {
+    let mut iter = input.splitn(2, ' ');
+    let first = iter.next().unwrap_or_default();
+    let rest = iter.next().map(str::trim).filter(|s| !s.is_empty());
+    (first, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+    use crate::twitch::Capability;
+
+    // encode the command, then decode it again
+    fn round_trip<'a>(cmd: impl Into<ClientCommand<'a>>) {
+        let cmd = cmd.into();
+        let mut buf = vec![];
+        cmd.encode(&mut buf).unwrap();
+        let line = std::str::from_utf8(&buf).unwrap();
+        assert_eq!(ClientCommand::parse(line).unwrap(), cmd, "{:?}", line);
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(ban("#museun", "foo", None));
+        round_trip(ban("#museun", "foo", "being rude"));
+        round_trip(cap_ls());
+        round_trip(cap_req(&[Capability::Tags]));
+        round_trip(cap_req(&[
+            Capability::Membership,
+            Capability::Custom("twitch.tv/foo".into()),
+        ]));
+        round_trip(clear("#museun"));
+        round_trip(color("blue").unwrap());
+        round_trip(command("#museun", "/unknown thing"));
+        round_trip(commercial("#museun", None));
+        round_trip(commercial("#museun", ChatDuration::seconds(30)));
+        round_trip(disconnect());
+        round_trip(emote_only("#museun"));
+        round_trip(emote_only_off("#museun"));
+        round_trip(followers("#museun", ChatDuration::weeks(1)));
+        round_trip(followers("#museun", ChatDuration::ZERO));
+        round_trip(followers_off("#museun"));
+        round_trip(give_mod("#museun", "foo"));
+        round_trip(help("#museun"));
+        round_trip(host("#museun", "#shaken"));
+        round_trip(join("#museun"));
+        round_trip(jtv_command("/unknown thing"));
+        round_trip(marker("#museun", None));
+        round_trip(marker("#museun", "a great moment"));
+        round_trip(me("#museun", "waves"));
+        round_trip(mods("#museun"));
+        round_trip(part("#museun"));
+        round_trip(ping("1234"));
+        round_trip(pong("1234"));
+        round_trip(privmsg("#museun", "hello world"));
+        round_trip(r9k_beta("#museun"));
+        round_trip(r9k_beta_off("#museun"));
+        round_trip(raid("#museun", "#shaken"));
+        round_trip(raw("NICK museun"));
+        round_trip(reply("#museun", "abc-123", "hello"));
+        round_trip(slow("#museun", ChatDuration::seconds(30)));
+        round_trip(slow_off("#museun"));
+        round_trip(subscribers("#museun"));
+        round_trip(subscribers_off("#museun"));
+        round_trip(timeout("#museun", "foo", None, None));
+        round_trip(timeout("#museun", "foo", ChatDuration::minutes(10), None));
+        round_trip(timeout("#museun", "foo", None, "spam"));
+        round_trip(timeout(
+            "#museun",
+            "foo",
+            ChatDuration::hours(25),
+            "too much spam",
+        ));
+        round_trip(unban("#museun", "foo"));
+        round_trip(unhost("#museun"));
+        round_trip(unmod("#museun", "foo"));
+        round_trip(unraid("#museun"));
+        round_trip(untimeout("#museun", "foo"));
+        round_trip(unvip("#museun", "foo"));
+        round_trip(vip("#museun", "foo"));
+        round_trip(vips("#museun"));
+        round_trip(whisper("museun", "hello world"));
+    }
+
+    #[test]
+    fn malformed_slash_commands() {
+        let cmd = ClientCommand::parse("PRIVMSG #museun :/ban\r\n").unwrap();
+        assert_eq!(cmd, command("#museun", "/ban").into());
+
+        let cmd = ClientCommand::parse("PRIVMSG #museun :/slow fast\r\n").unwrap();
+        assert_eq!(cmd, command("#museun", "/slow fast").into());
+
+        let cmd = ClientCommand::parse("PRIVMSG jtv :/w museun\r\n").unwrap();
+        assert_eq!(cmd, jtv_command("/w museun").into());
+
+        let cmd = ClientCommand::parse("PRIVMSG jtv :/color not_a_color\r\n").unwrap();
+        assert_eq!(cmd, jtv_command("/color not_a_color").into());
+    }
+
+    #[test]
+    fn raw_lines() {
+        let cmd = ClientCommand::parse("PASS oauth:abc\r\n").unwrap();
+        assert_eq!(cmd, raw("PASS oauth:abc").into());
+
+        let cmd = ClientCommand::parse("CAP END\r\n").unwrap();
+        assert_eq!(cmd, raw("CAP END").into());
+
+        assert!(ClientCommand::parse("\r\n").is_err());
+    }
+
+    #[test]
+    fn from_irc() {
+        let input = "PRIVMSG #museun :/timeout foo 600 spam\r\n";
+        let (_, msg) = crate::irc::parse_one(input).unwrap();
+        let cmd = ClientCommand::from_irc(&msg).unwrap();
+        assert_eq!(
+            cmd,
+            timeout("#museun", "foo", ChatDuration::minutes(10), "spam").into()
+        );
+        assert_eq!(cmd.priority(), crate::writer::Priority::Moderation);
+    }
+}
@@ -1,4 +1,5 @@
 use super::Encodable;
+use crate::twitch::color::{ParseError, TwitchColor};
 use std::convert::TryInto;
 use std::io::{Result, Write};
 
@@ -23,6 +24,49 @@ where
     })
 }
 
+/// Change your username color, checking whether your account can use it.
+///
+/// Only users with `Turbo` or `Prime` can use custom (hex) colors, everyone
+/// else has to use one of the [named colors][named].
+///
+/// If `allow_custom` is false, then this returns [`ParseError::RequiresTurbo`][err]
+/// for a custom color.
+///
+/// A hex color that matches a named color is always allowed.
+///
+/// ```
+/// # use twitchchat::{commands, twitch::color::ParseError};
+/// assert!(commands::color_checked("Blue Violet", false).is_ok());
+/// assert!(commands::color_checked("#8A2BE2", false).is_ok());
+///
+/// assert!(matches!(
+///     commands::color_checked("#FAFAFA", false),
+///     Err(ParseError::RequiresTurbo)
+/// ));
+/// assert!(commands::color_checked("#FAFAFA", true).is_ok());
+/// ```
+///
+/// [named]: ../twitch/color/enum.TwitchColor.html
+/// [err]: ../twitch/color/enum.ParseError.html#variant.RequiresTurbo
+pub fn color_checked<T>(
+    color: T,
+    allow_custom: bool,
+) -> std::result::Result<Color<'static>, ParseError>
+where
+    T: TryInto<crate::twitch::Color>,
+    T::Error: Into<ParseError>,
+{
+    let color = color.try_into().map_err(Into::into)?;
+    if color.kind == TwitchColor::Turbo && !allow_custom {
+        return Err(ParseError::RequiresTurbo);
+    }
+
+    Ok(Color {
+        color,
+        marker: std::marker::PhantomData,
+    })
+}
+
 impl<'a> Encodable for Color<'a> {
     fn encode<W>(&self, buf: &mut W) -> Result<()>
     where
@@ -46,6 +90,34 @@ mod tests {
         )
     }
 
+    #[test]
+    fn color_checked_encode() {
+        test_encode(
+            color_checked("blue", false).unwrap(),
+            "PRIVMSG jtv :/color Blue\r\n",
+        );
+        test_encode(
+            color_checked("#0000FF", false).unwrap(),
+            "PRIVMSG jtv :/color Blue\r\n",
+        );
+        test_encode(
+            color_checked("#FAFAFA", true).unwrap(),
+            "PRIVMSG jtv :/color #FAFAFA\r\n",
+        );
+
+        let blue: crate::twitch::Color = "blue".parse().unwrap();
+        assert!(color_checked(blue, false).is_ok());
+
+        assert!(matches!(
+            color_checked("#FAFAFA", false),
+            Err(ParseError::RequiresTurbo)
+        ));
+        assert!(matches!(
+            color_checked("not a color", true),
+            Err(ParseError::InvalidHexString)
+        ));
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn color_serde() {
@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Sends the `command` to the `channel` (e.g. `/color #FFFFFF`)
@@ -20,6 +20,9 @@ impl<'a> Encodable for Command<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::text("data", self.data)?;
+
         write_cmd!(buf, Channel(self.channel) => &self.data)
     }
 }
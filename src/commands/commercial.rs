@@ -1,4 +1,4 @@
-use super::{Channel, Encodable, MaybeEmpty};
+use super::{check, duration, Channel, ChatDuration, Encodable, MaybeEmpty};
 use std::io::{Result, Write};
 
 /// Triggers a commercial.
@@ -7,13 +7,13 @@ use std::io::{Result, Write};
 #[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
 pub struct Commercial<'a> {
     pub(crate) channel: &'a str,
-    pub(crate) length: Option<usize>,
+    pub(crate) length: Option<ChatDuration>,
 }
 
 /// Triggers a commercial.
 ///
-/// Length *(optional)* must be a positive number of seconds.
-pub fn commercial(channel: &str, length: impl Into<Option<usize>>) -> Commercial<'_> {
+/// Length *(optional)* must be between `30 seconds` and `3 minutes`.
+pub fn commercial(channel: &str, length: impl Into<Option<ChatDuration>>) -> Commercial<'_> {
     Commercial {
         channel,
         length: length.into(),
@@ -25,7 +25,12 @@ impl<'a> Encodable for Commercial<'a> {
     where
         W: Write + ?Sized,
     {
-        let length = self.length.map(|s| s.to_string());
+        check::channel("channel", self.channel)?;
+        if let Some(length) = self.length {
+            check::duration("length", length, &duration::COMMERCIAL)?;
+        }
+
+        let length = self.length.map(|s| s.as_secs().to_string());
         write_cmd!(buf, Channel(self.channel) => "/commercial{}", MaybeEmpty(length.as_deref()))
     }
 }
@@ -42,12 +47,12 @@ mod tests {
             "PRIVMSG #museun :/commercial\r\n",
         );
         test_encode(
-            commercial("#museun", 10),
-            "PRIVMSG #museun :/commercial 10\r\n",
+            commercial("#museun", ChatDuration::seconds(30)),
+            "PRIVMSG #museun :/commercial 30\r\n",
         );
         test_encode(
-            commercial("#museun", Some(10)),
-            "PRIVMSG #museun :/commercial 10\r\n",
+            commercial("#museun", Some(ChatDuration::seconds(30))),
+            "PRIVMSG #museun :/commercial 30\r\n",
         );
     }
 
@@ -58,12 +63,29 @@ mod tests {
             "PRIVMSG #museun :/commercial\r\n",
         );
         test_encode(
-            commercial("museun", 10),
-            "PRIVMSG #museun :/commercial 10\r\n",
+            commercial("museun", ChatDuration::seconds(30)),
+            "PRIVMSG #museun :/commercial 30\r\n",
         );
         test_encode(
-            commercial("museun", Some(10)),
-            "PRIVMSG #museun :/commercial 10\r\n",
+            commercial("museun", Some(ChatDuration::seconds(30))),
+            "PRIVMSG #museun :/commercial 30\r\n",
+        );
+    }
+
+    #[test]
+    fn commercial_length_encode() {
+        test_encode(
+            commercial("#museun", ChatDuration::minutes(3)),
+            "PRIVMSG #museun :/commercial 180\r\n",
+        );
+        test_encode_err(
+            commercial("#museun", ChatDuration::seconds(10)),
+            EncodeError::DurationOutOfRange {
+                field: "length",
+                duration: ChatDuration::seconds(10),
+                min: ChatDuration::seconds(30),
+                max: ChatDuration::minutes(3),
+            },
         );
     }
 
@@ -75,12 +97,12 @@ mod tests {
             "PRIVMSG #museun :/commercial\r\n",
         );
         test_serde(
-            commercial("#museun", 10),
-            "PRIVMSG #museun :/commercial 10\r\n",
+            commercial("#museun", ChatDuration::seconds(30)),
+            "PRIVMSG #museun :/commercial 30\r\n",
         );
         test_serde(
-            commercial("#museun", Some(10)),
-            "PRIVMSG #museun :/commercial 10\r\n",
+            commercial("#museun", Some(ChatDuration::seconds(30))),
+            "PRIVMSG #museun :/commercial 30\r\n",
         );
     }
 
@@ -92,12 +114,12 @@ mod tests {
             "PRIVMSG #museun :/commercial\r\n",
         );
         test_serde(
-            commercial("museun", 10),
-            "PRIVMSG #museun :/commercial 10\r\n",
+            commercial("museun", ChatDuration::seconds(30)),
+            "PRIVMSG #museun :/commercial 30\r\n",
         );
         test_serde(
-            commercial("museun", Some(10)),
-            "PRIVMSG #museun :/commercial 10\r\n",
+            commercial("museun", Some(ChatDuration::seconds(30))),
+            "PRIVMSG #museun :/commercial 30\r\n",
         );
     }
 }
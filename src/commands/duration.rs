@@ -0,0 +1,369 @@
+use std::ops::RangeInclusive;
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+const MONTH: u64 = 30 * DAY;
+
+/// The durations a [timeout] can be
+///
+/// [timeout]: ./fn.timeout.html
+pub(crate) const TIMEOUT: RangeInclusive<ChatDuration> =
+    ChatDuration::seconds(1)..=ChatDuration::weeks(2);
+
+/// The durations [slow] mode can be
+///
+/// [slow]: ./fn.slow.html
+pub(crate) const SLOW: RangeInclusive<ChatDuration> =
+    ChatDuration::seconds(1)..=ChatDuration::minutes(30);
+
+/// The durations [followers] only mode can be
+///
+/// [followers]: ./fn.followers.html
+pub(crate) const FOLLOWERS: RangeInclusive<ChatDuration> =
+    ChatDuration::ZERO..=ChatDuration::seconds(3 * MONTH);
+
+/// The lengths a [commercial] can be
+///
+/// [commercial]: ./fn.commercial.html
+pub(crate) const COMMERCIAL: RangeInclusive<ChatDuration> =
+    ChatDuration::seconds(30)..=ChatDuration::minutes(3);
+
+/// An error returned when a [ChatDuration] couldn't be parsed
+///
+/// [ChatDuration]: ./struct.ChatDuration.html
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DurationError {
+    /// The input was empty
+    Empty,
+    /// The input wasn't a sequence of numbers followed by units
+    Malformed {
+        /// The input
+        input: String,
+    },
+    /// A unit wasn't one of the known ones
+    UnknownUnit {
+        /// The unit
+        unit: String,
+    },
+    /// The duration is too long to be represented
+    Overflow,
+}
+
+impl std::fmt::Display for DurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the duration is empty"),
+            Self::Malformed { input } => write!(
+                f,
+                "'{}' is not a duration. expected something like '90', '10m' or '1h30m'",
+                input
+            ),
+            Self::UnknownUnit { unit } => write!(
+                f,
+                "unknown unit '{}'. expected one of s, m, h, d, w or mo",
+                unit
+            ),
+            Self::Overflow => f.write_str("the duration is too long"),
+        }
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+/// A duration for the commands that take one, e.g. [timeout] or [slow]
+///
+/// This has a resolution of whole seconds and is written in Twitch's own
+/// syntax, e.g. `1h30m`. It can be made from a `std::time::Duration`, with the
+/// constructors or parsed from a string:
+///
+/// * a number of seconds, e.g. `600`
+/// * numbers followed by a unit, e.g. `1h30m`, `2w` or `5 days 12 hours`
+///
+/// The units are `s`, `m`, `h`, `d`, `w` and `mo` (30 days), their long
+/// forms, e.g. `minute`, or their plurals, e.g. `minutes`.
+///
+/// Each command checks the duration against the range Twitch allows when
+/// it is encoded, returning an [EncodeError::DurationOutOfRange] otherwise.
+///
+/// # Example
+/// ```
+/// # use twitchchat::commands::ChatDuration;
+/// let duration: ChatDuration = "1h30m".parse().unwrap();
+/// assert_eq!(duration, ChatDuration::minutes(90));
+/// assert_eq!(duration.to_string(), "1h30m");
+///
+/// let duration = ChatDuration::from(std::time::Duration::from_secs(600));
+/// assert_eq!(duration.to_string(), "10m");
+///
+/// assert_eq!("5 days 12 hours".parse::<ChatDuration>().unwrap().to_string(), "5d12h");
+/// assert!("10 parsecs".parse::<ChatDuration>().is_err());
+/// ```
+///
+/// [timeout]: ./fn.timeout.html
+/// [slow]: ./fn.slow.html
+/// [EncodeError::DurationOutOfRange]: ./enum.EncodeError.html#variant.DurationOutOfRange
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChatDuration(u64);
+
+impl ChatDuration {
+    /// A zero duration
+    pub const ZERO: Self = Self(0);
+
+    /// A duration of `seconds`
+    pub const fn seconds(seconds: u64) -> Self {
+        Self(seconds)
+    }
+
+    /// A duration of `minutes`
+    pub const fn minutes(minutes: u64) -> Self {
+        Self(minutes.saturating_mul(MINUTE))
+    }
+
+    /// A duration of `hours`
+    pub const fn hours(hours: u64) -> Self {
+        Self(hours.saturating_mul(HOUR))
+    }
+
+    /// A duration of `days`
+    pub const fn days(days: u64) -> Self {
+        Self(days.saturating_mul(DAY))
+    }
+
+    /// A duration of `weeks`
+    pub const fn weeks(weeks: u64) -> Self {
+        Self(weeks.saturating_mul(WEEK))
+    }
+
+    /// The number of whole seconds in this duration
+    pub const fn as_secs(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<std::time::Duration> for ChatDuration {
+    /// Convert the duration, dropping any fractional seconds
+    fn from(duration: std::time::Duration) -> Self {
+        Self(duration.as_secs())
+    }
+}
+
+impl From<ChatDuration> for std::time::Duration {
+    fn from(duration: ChatDuration) -> Self {
+        Self::from_secs(duration.0)
+    }
+}
+
+impl std::fmt::Display for ChatDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 == 0 {
+            return f.write_str("0");
+        }
+
+        let mut rest = self.0;
+        for &(size, unit) in &[
+            (WEEK, "w"),
+            (DAY, "d"),
+            (HOUR, "h"),
+            (MINUTE, "m"),
+            (1, "s"),
+        ] {
+            if rest >= size {
+                write!(f, "{}{}", rest / size, unit)?;
+                rest %= size;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for ChatDuration {
+    type Err = DurationError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(DurationError::Empty);
+        }
+
+        // a bare number is a number of seconds
+        if input.bytes().all(|c| c.is_ascii_digit()) {
+            return input.parse().map(Self).map_err(|_| DurationError::Overflow);
+        }
+
+        let malformed = || DurationError::Malformed {
+            input: input.to_string(),
+        };
+
+        let mut total = 0_u64;
+        let mut rest = input;
+        while !rest.is_empty() {
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return Err(malformed());
+            }
+            let (number, tail) = rest.split_at(end);
+            let number = number.parse::<u64>().map_err(|_| DurationError::Overflow)?;
+
+            let tail = tail.trim_start();
+            let end = tail
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(tail.len());
+            if end == 0 {
+                return Err(malformed());
+            }
+            let (unit, tail) = tail.split_at(end);
+
+            total = number
+                .checked_mul(unit_size(unit)?)
+                .and_then(|seconds| total.checked_add(seconds))
+                .ok_or(DurationError::Overflow)?;
+            rest = tail.trim_start();
+        }
+
+        Ok(Self(total))
+    }
+}
+
+fn unit_size(unit: &str) -> Result<u64, DurationError> {
+    let size = match &*unit.to_ascii_lowercase() {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => MINUTE,
+        "h" | "hr" | "hrs" | "hour" | "hours" => HOUR,
+        "d" | "day" | "days" => DAY,
+        "w" | "week" | "weeks" => WEEK,
+        "mo" | "month" | "months" => MONTH,
+        _ => {
+            return Err(DurationError::UnknownUnit {
+                unit: unit.to_string(),
+            })
+        }
+    };
+    Ok(size)
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for ChatDuration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for ChatDuration {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        struct Visitor;
+        impl<'de> ::serde::de::Visitor<'de> for Visitor {
+            type Value = ChatDuration;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a number of seconds or a duration like '1h30m'")
+            }
+
+            fn visit_u64<E>(self, seconds: u64) -> Result<Self::Value, E> {
+                Ok(ChatDuration(seconds))
+            }
+
+            fn visit_str<E>(self, input: &str) -> Result<Self::Value, E>
+            where
+                E: ::serde::de::Error,
+            {
+                input.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let tests = &[
+            ("600", ChatDuration::minutes(10)),
+            ("0", ChatDuration::ZERO),
+            ("10m", ChatDuration::minutes(10)),
+            ("1d2h", ChatDuration::hours(26)),
+            ("1h30m15s", ChatDuration::seconds(5415)),
+            ("2w", ChatDuration::weeks(2)),
+            ("1mo", ChatDuration::days(30)),
+            ("1 week", ChatDuration::weeks(1)),
+            ("5 days 12 hours", ChatDuration::hours(132)),
+            ("  30 Minutes ", ChatDuration::minutes(30)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(
+                input.parse::<ChatDuration>().unwrap(),
+                *expected,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "".parse::<ChatDuration>().unwrap_err(),
+            DurationError::Empty
+        );
+        for &input in &["m", "10m5", "1.5h", "-10m", "1h m"] {
+            assert_eq!(
+                input.parse::<ChatDuration>().unwrap_err(),
+                DurationError::Malformed {
+                    input: input.to_string()
+                },
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            "10y".parse::<ChatDuration>().unwrap_err(),
+            DurationError::UnknownUnit {
+                unit: "y".to_string()
+            }
+        );
+        assert_eq!(
+            "99999999999999999999w".parse::<ChatDuration>().unwrap_err(),
+            DurationError::Overflow
+        );
+    }
+
+    #[test]
+    fn display() {
+        let tests = &[
+            (ChatDuration::ZERO, "0"),
+            (ChatDuration::seconds(45), "45s"),
+            (ChatDuration::minutes(90), "1h30m"),
+            (ChatDuration::seconds(WEEK + DAY + 1), "1w1d1s"),
+            (ChatDuration::days(90), "12w6d"),
+        ];
+        for (duration, expected) in tests {
+            assert_eq!(duration.to_string(), *expected);
+            assert_eq!(expected.parse::<ChatDuration>().unwrap(), *duration);
+        }
+    }
+
+    #[test]
+    fn std_duration() {
+        let duration = std::time::Duration::from_millis(1500);
+        assert_eq!(ChatDuration::from(duration), ChatDuration::seconds(1));
+        assert_eq!(
+            std::time::Duration::from(ChatDuration::minutes(1)),
+            std::time::Duration::from_secs(60)
+        );
+    }
+}
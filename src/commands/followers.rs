@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, duration, Channel, ChatDuration, Encodable};
 use std::io::{Result, Write};
 
 /// Enables followers-only mode (only users who have followed for `duration` may chat).
@@ -7,20 +7,23 @@ use std::io::{Result, Write};
 #[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
 pub struct Followers<'a> {
     pub(crate) channel: &'a str,
-    pub(crate) duration: &'a str,
+    pub(crate) duration: ChatDuration,
 }
 
 /// Enables followers-only mode (only users who have followed for `duration` may chat).
 ///
-/// Examples: `"30m"`, `"1 week"`, `"5 days 12 hours"`.
-///
-/// Must be less than 3 months.
+/// Duration must be at most `3 months`, zero lets any follower chat. See
+/// [ChatDuration] for how to make one, e.g. from `"1 week"`.
 ///
 /// Use [followers_off] to disable.
 ///
 /// [followers_off]: ./fn.followers_off.html
-pub const fn followers<'a>(channel: &'a str, duration: &'a str) -> Followers<'a> {
-    Followers { channel, duration }
+/// [ChatDuration]: ./struct.ChatDuration.html
+pub fn followers(channel: &str, duration: impl Into<ChatDuration>) -> Followers<'_> {
+    Followers {
+        channel,
+        duration: duration.into(),
+    }
 }
 
 impl<'a> Encodable for Followers<'a> {
@@ -28,6 +31,9 @@ impl<'a> Encodable for Followers<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::duration("duration", self.duration, &duration::FOLLOWERS)?;
+
         write_cmd!(buf, Channel(self.channel) => "/followers {}", self.duration)
     }
 }
@@ -40,16 +46,37 @@ mod tests {
     #[test]
     fn followers_encode() {
         test_encode(
-            followers("#museun", "1 week"),
-            "PRIVMSG #museun :/followers 1 week\r\n",
+            followers("#museun", ChatDuration::weeks(1)),
+            "PRIVMSG #museun :/followers 1w\r\n",
         );
     }
 
     #[test]
     fn followers_ensure_channel_encode() {
         test_encode(
-            followers("museun", "1 week"),
-            "PRIVMSG #museun :/followers 1 week\r\n",
+            followers("museun", ChatDuration::weeks(1)),
+            "PRIVMSG #museun :/followers 1w\r\n",
+        );
+    }
+
+    #[test]
+    fn followers_duration_encode() {
+        test_encode(
+            followers("#museun", ChatDuration::ZERO),
+            "PRIVMSG #museun :/followers 0\r\n",
+        );
+        test_encode(
+            followers("#museun", ChatDuration::days(90)),
+            "PRIVMSG #museun :/followers 12w6d\r\n",
+        );
+        test_encode_err(
+            followers("#museun", ChatDuration::days(91)),
+            EncodeError::DurationOutOfRange {
+                field: "duration",
+                duration: ChatDuration::days(91),
+                min: ChatDuration::ZERO,
+                max: ChatDuration::days(90),
+            },
         );
     }
 
@@ -57,8 +84,8 @@ mod tests {
     #[cfg(feature = "serde")]
     fn followers_serde() {
         test_serde(
-            followers("#museun", "1 week"),
-            "PRIVMSG #museun :/followers 1 week\r\n",
+            followers("#museun", ChatDuration::weeks(1)),
+            "PRIVMSG #museun :/followers 1w\r\n",
         );
     }
 
@@ -66,8 +93,8 @@ mod tests {
     #[cfg(feature = "serde")]
     fn followers_ensure_channel_serde() {
         test_serde(
-            followers("museun", "1 week"),
-            "PRIVMSG #museun :/followers 1 week\r\n",
+            followers("museun", ChatDuration::weeks(1)),
+            "PRIVMSG #museun :/followers 1w\r\n",
         );
     }
 }
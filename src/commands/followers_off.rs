@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Disables followers-only mode.
@@ -16,6 +16,8 @@ pub const fn followers_off(channel: &str) -> FollowersOff<'_> {
 
 impl<'a> Encodable for FollowersOff<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        check::channel("channel", self.channel)?;
+
         write_cmd!(buf, Channel(self.channel) => "/followersoff")
     }
 }
@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Grant moderator status to a user.
@@ -24,6 +24,9 @@ impl<'a> Encodable for GiveMod<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::name("username", self.username)?;
+
         write_cmd!(buf, Channel(self.channel) => "/mod {}", self.username)
     }
 }
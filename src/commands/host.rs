@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Host another channel.
@@ -24,6 +24,9 @@ impl<'a> Encodable for Host<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("source", self.source)?;
+        check::channel("target", self.target)?;
+
         write_cmd!(buf, Channel(self.source) => "/host {}", Channel(self.target))
     }
 }
@@ -0,0 +1,504 @@
+use super::{client_command::split_word, ChatDuration, ClientCommand};
+use crate::twitch::{ChannelName, Login};
+
+/// An error returned by [parse_input] when a slash command is invalid
+///
+/// [parse_input]: ./fn.parse_input.html
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputError {
+    /// The input was empty
+    Empty,
+    /// The command isn't one that's known
+    UnknownCommand {
+        /// The command, without the leading `/`
+        command: String,
+    },
+    /// A required argument wasn't provided
+    MissingArgument {
+        /// The command
+        command: String,
+        /// The argument that is missing
+        argument: &'static str,
+    },
+    /// An argument couldn't be parsed
+    InvalidArgument {
+        /// The command
+        command: String,
+        /// The argument that is invalid
+        argument: &'static str,
+        /// What was provided
+        value: String,
+    },
+    /// The command doesn't take any (more) arguments
+    UnexpectedArgument {
+        /// The command
+        command: String,
+        /// The extra input
+        value: String,
+    },
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("nothing to send"),
+            Self::UnknownCommand { command } => write!(f, "unknown command: /{}", command),
+            Self::MissingArgument { command, argument } => {
+                write!(f, "/{} requires a {}", command, argument)
+            }
+            Self::InvalidArgument {
+                command,
+                argument,
+                value,
+            } => write!(f, "/{}: '{}' is not a valid {}", command, value, argument),
+            Self::UnexpectedArgument { command, value } => {
+                write!(f, "/{} doesn't take '{}'", command, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Parse a line a user typed into the command it stands for
+///
+/// Lines starting with a `/` are parsed as slash commands, e.g.
+/// `/timeout museun 10m being rude`, `/me waves` or `/slow 30`, which apply
+/// to `channel`. Anything else is sent to `channel` as a [privmsg].
+/// Start the line with `//` to send a message that starts with a `/`.
+///
+/// The arguments are validated, so the returned command is what Twitch
+/// would expect.
+///
+/// # Example
+/// ```
+/// use twitchchat::commands::{self, parse_input, ChatDuration, ClientCommand, InputError};
+///
+/// let cmd = parse_input("#museun", "/timeout shaken_bot 10m being rude").unwrap();
+/// assert_eq!(
+///     cmd,
+///     ClientCommand::Timeout(commands::timeout(
+///         "#museun",
+///         "shaken_bot",
+///         ChatDuration::minutes(10),
+///         "being rude"
+///     ))
+/// );
+///
+/// let cmd = parse_input("#museun", "hello world").unwrap();
+/// assert_eq!(cmd, ClientCommand::Privmsg(commands::privmsg("#museun", "hello world")));
+///
+/// let err = parse_input("#museun", "/timeout").unwrap_err();
+/// assert_eq!(
+///     err,
+///     InputError::MissingArgument { command: "timeout".into(), argument: "username" }
+/// );
+/// ```
+///
+/// [privmsg]: ./fn.privmsg.html
+pub fn parse_input<'a>(channel: &'a str, input: &'a str) -> Result<ClientCommand<'a>, InputError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(InputError::Empty);
+    }
+
+    let line = match input.strip_prefix('/') {
+        Some(line) if line.starts_with('/') => return Ok(privmsg(channel, line).into()),
+        Some(line) => line,
+        None => return Ok(privmsg(channel, input).into()),
+    };
+
+    let (command, rest) = split_word(line);
+    let mut args = Args {
+        command,
+        rest: rest.unwrap_or_default(),
+    };
+
+    use super::*;
+    let cmd = match command {
+        "ban" => ban(channel, args.username()?, args.remaining()).into(),
+        "unban" => unban(channel, args.username()?).into(),
+        "timeout" => {
+            let username = args.username()?;
+            let duration = match args.optional(is_duration) {
+                Some(value) => Some(args.duration(value, &duration::TIMEOUT)?),
+                None => None,
+            };
+            timeout(channel, username, duration, args.remaining()).into()
+        }
+        "untimeout" => untimeout(channel, args.username()?).into(),
+        "clear" => clear(channel).into(),
+        "color" => {
+            let value = args.required("color")?;
+            color(value)
+                .map_err(|_| args.invalid("color", value))?
+                .into()
+        }
+        "commercial" => {
+            let length = match args.next() {
+                Some(value) => Some(args.duration(value, &duration::COMMERCIAL)?),
+                None => None,
+            };
+            commercial(channel, length).into()
+        }
+        "disconnect" => disconnect().into(),
+        "emoteonly" => emote_only(channel).into(),
+        "emoteonlyoff" => emote_only_off(channel).into(),
+        "followers" => match args.remaining() {
+            Some(value) => followers(channel, args.duration(value, &duration::FOLLOWERS)?).into(),
+            None => followers(channel, ChatDuration::ZERO).into(),
+        },
+        "followersoff" => followers_off(channel).into(),
+        "mod" => give_mod(channel, args.username()?).into(),
+        "unmod" => unmod(channel, args.username()?).into(),
+        "vip" => vip(channel, args.username()?).into(),
+        "unvip" => unvip(channel, args.username()?).into(),
+        "help" => help(channel).into(),
+        "host" => host(channel, args.channel()?).into(),
+        "unhost" => unhost(channel).into(),
+        "raid" => raid(channel, args.channel()?).into(),
+        "unraid" => unraid(channel).into(),
+        "marker" => marker(channel, args.remaining()).into(),
+        "me" => me(channel, args.required_text("message")?).into(),
+        "mods" => mods(channel).into(),
+        "vips" => vips(channel).into(),
+        "r9kbeta" | "uniquechat" => r9k_beta(channel).into(),
+        "r9kbetaoff" | "uniquechatoff" => r9k_beta_off(channel).into(),
+        "slow" => {
+            let duration = match args.next() {
+                Some(value) => Some(args.duration(value, &duration::SLOW)?),
+                None => None,
+            };
+            slow(channel, duration).into()
+        }
+        "slowoff" => slow_off(channel).into(),
+        "subscribers" => subscribers(channel).into(),
+        "subscribersoff" => subscribers_off(channel).into(),
+        "w" | "whisper" => {
+            let username = args.username()?;
+            whisper(username, args.required_text("message")?).into()
+        }
+        "join" => join(args.channel()?).into(),
+        "part" if args.rest.is_empty() => part(channel).into(),
+        "part" => part(args.channel()?).into(),
+        _ => {
+            return Err(InputError::UnknownCommand {
+                command: command.to_string(),
+            })
+        }
+    };
+
+    // anything left over wasn't expected
+    match args.remaining() {
+        Some(value) => Err(InputError::UnexpectedArgument {
+            command: command.to_string(),
+            value: value.to_string(),
+        }),
+        None => Ok(cmd),
+    }
+}
+
+/// The arguments of a slash command, consumed from the left
+struct Args<'a> {
+    command: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Args<'a> {
+    fn next(&mut self) -> Option<&'a str> {
+        let (head, tail) = split_word(self.rest);
+        if head.is_empty() {
+            return None;
+        }
+        self.rest = tail.unwrap_or_default();
+        Some(head)
+    }
+
+    /// Everything that is left, if anything
+    fn remaining(&mut self) -> Option<&'a str> {
+        let rest = std::mem::take(&mut self.rest).trim();
+        Some(rest).filter(|s| !s.is_empty())
+    }
+
+    fn required(&mut self, argument: &'static str) -> Result<&'a str, InputError> {
+        self.next().ok_or(InputError::MissingArgument {
+            command: self.command.to_string(),
+            argument,
+        })
+    }
+
+    fn required_text(&mut self, argument: &'static str) -> Result<&'a str, InputError> {
+        self.remaining().ok_or(InputError::MissingArgument {
+            command: self.command.to_string(),
+            argument,
+        })
+    }
+
+    /// Take the next argument if it matches `predicate`
+    fn optional(&mut self, predicate: fn(&str) -> bool) -> Option<&'a str> {
+        let (head, tail) = split_word(self.rest);
+        if head.is_empty() || !predicate(head) {
+            return None;
+        }
+        self.rest = tail.unwrap_or_default();
+        Some(head)
+    }
+
+    fn username(&mut self) -> Result<&'a str, InputError> {
+        let username = self.required("username")?;
+        let login = username.strip_prefix('@').unwrap_or(username);
+        Login::new(login)
+            .map(|_| login)
+            .map_err(|_| self.invalid("username", username))
+    }
+
+    fn channel(&mut self) -> Result<&'a str, InputError> {
+        let channel = self.required("channel")?;
+        ChannelName::new(channel)
+            .map(|_| channel)
+            .map_err(|_| self.invalid("channel", channel))
+    }
+
+    /// Parse `value` as a duration within `range`
+    fn duration(
+        &self,
+        value: &str,
+        range: &std::ops::RangeInclusive<ChatDuration>,
+    ) -> Result<ChatDuration, InputError> {
+        value
+            .parse()
+            .ok()
+            .filter(|duration| range.contains(duration))
+            .ok_or_else(|| self.invalid("duration", value))
+    }
+
+    fn invalid(&self, argument: &'static str, value: &str) -> InputError {
+        InputError::InvalidArgument {
+            command: self.command.to_string(),
+            argument,
+            value: value.to_string(),
+        }
+    }
+}
+
+fn is_duration(input: &str) -> bool {
+    input.parse::<ChatDuration>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    fn parse(input: &str) -> ClientCommand<'_> {
+        parse_input("#museun", input).unwrap()
+    }
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(
+            parse("hello world"),
+            privmsg("#museun", "hello world").into()
+        );
+        assert_eq!(parse("  hello  "), privmsg("#museun", "hello").into());
+        assert_eq!(parse("//shrug"), privmsg("#museun", "/shrug").into());
+        assert_eq!(
+            parse_input("#museun", "   ").unwrap_err(),
+            InputError::Empty
+        );
+    }
+
+    #[test]
+    fn commands() {
+        let tests: Vec<(&str, ClientCommand<'_>)> = vec![
+            ("/ban foo", ban("#museun", "foo", None).into()),
+            (
+                "/ban @foo being rude",
+                ban("#museun", "foo", "being rude").into(),
+            ),
+            ("/unban foo", unban("#museun", "foo").into()),
+            ("/timeout foo", timeout("#museun", "foo", None, None).into()),
+            (
+                "/timeout foo 10m",
+                timeout("#museun", "foo", ChatDuration::minutes(10), None).into(),
+            ),
+            (
+                "/timeout foo 1d2h",
+                timeout("#museun", "foo", ChatDuration::hours(26), None).into(),
+            ),
+            (
+                "/timeout foo spam",
+                timeout("#museun", "foo", None, "spam").into(),
+            ),
+            (
+                "/timeout foo 600 too much spam",
+                timeout("#museun", "foo", ChatDuration::minutes(10), "too much spam").into(),
+            ),
+            ("/untimeout foo", untimeout("#museun", "foo").into()),
+            ("/clear", clear("#museun").into()),
+            ("/color blue", color("blue").unwrap().into()),
+            ("/commercial", commercial("#museun", None).into()),
+            (
+                "/commercial 30",
+                commercial("#museun", ChatDuration::seconds(30)).into(),
+            ),
+            ("/disconnect", disconnect().into()),
+            ("/emoteonly", emote_only("#museun").into()),
+            ("/emoteonlyoff", emote_only_off("#museun").into()),
+            (
+                "/followers",
+                followers("#museun", ChatDuration::ZERO).into(),
+            ),
+            (
+                "/followers 1 week",
+                followers("#museun", ChatDuration::weeks(1)).into(),
+            ),
+            ("/followersoff", followers_off("#museun").into()),
+            ("/mod foo", give_mod("#museun", "foo").into()),
+            ("/unmod foo", unmod("#museun", "foo").into()),
+            ("/vip foo", vip("#museun", "foo").into()),
+            ("/unvip foo", unvip("#museun", "foo").into()),
+            ("/help", help("#museun").into()),
+            ("/host shaken", host("#museun", "shaken").into()),
+            ("/unhost", unhost("#museun").into()),
+            ("/raid shaken", raid("#museun", "shaken").into()),
+            ("/unraid", unraid("#museun").into()),
+            ("/marker", marker("#museun", None).into()),
+            ("/marker good bit", marker("#museun", "good bit").into()),
+            ("/me waves", me("#museun", "waves").into()),
+            ("/mods", mods("#museun").into()),
+            ("/vips", vips("#museun").into()),
+            ("/r9kbeta", r9k_beta("#museun").into()),
+            ("/uniquechatoff", r9k_beta_off("#museun").into()),
+            ("/slow", slow("#museun", None).into()),
+            (
+                "/slow 30",
+                slow("#museun", ChatDuration::seconds(30)).into(),
+            ),
+            ("/slowoff", slow_off("#museun").into()),
+            ("/subscribers", subscribers("#museun").into()),
+            ("/subscribersoff", subscribers_off("#museun").into()),
+            ("/w foo hello there", whisper("foo", "hello there").into()),
+            ("/join shaken", join("shaken").into()),
+            ("/part", part("#museun").into()),
+            ("/part #shaken", part("#shaken").into()),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(parse(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let tests = vec![
+            (
+                "/foo bar",
+                InputError::UnknownCommand {
+                    command: "foo".into(),
+                },
+            ),
+            (
+                "/timeout",
+                InputError::MissingArgument {
+                    command: "timeout".into(),
+                    argument: "username",
+                },
+            ),
+            (
+                "/ban #foo",
+                InputError::InvalidArgument {
+                    command: "ban".into(),
+                    argument: "username",
+                    value: "#foo".into(),
+                },
+            ),
+            (
+                "/me",
+                InputError::MissingArgument {
+                    command: "me".into(),
+                    argument: "message",
+                },
+            ),
+            (
+                "/w foo",
+                InputError::MissingArgument {
+                    command: "w".into(),
+                    argument: "message",
+                },
+            ),
+            (
+                "/slow fast",
+                InputError::InvalidArgument {
+                    command: "slow".into(),
+                    argument: "duration",
+                    value: "fast".into(),
+                },
+            ),
+            (
+                "/slow 1h",
+                InputError::InvalidArgument {
+                    command: "slow".into(),
+                    argument: "duration",
+                    value: "1h".into(),
+                },
+            ),
+            (
+                "/timeout foo 3w",
+                InputError::InvalidArgument {
+                    command: "timeout".into(),
+                    argument: "duration",
+                    value: "3w".into(),
+                },
+            ),
+            (
+                "/commercial 10",
+                InputError::InvalidArgument {
+                    command: "commercial".into(),
+                    argument: "duration",
+                    value: "10".into(),
+                },
+            ),
+            (
+                "/followers forever",
+                InputError::InvalidArgument {
+                    command: "followers".into(),
+                    argument: "duration",
+                    value: "forever".into(),
+                },
+            ),
+            (
+                "/color not_a_color",
+                InputError::InvalidArgument {
+                    command: "color".into(),
+                    argument: "color",
+                    value: "not_a_color".into(),
+                },
+            ),
+            (
+                "/clear everything",
+                InputError::UnexpectedArgument {
+                    command: "clear".into(),
+                    value: "everything".into(),
+                },
+            ),
+            (
+                "/unban foo bar",
+                InputError::UnexpectedArgument {
+                    command: "unban".into(),
+                    value: "bar".into(),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_input("#museun", input).unwrap_err(),
+                expected,
+                "{}",
+                input
+            );
+        }
+    }
+}
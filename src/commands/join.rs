@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{check, Encodable};
 use std::io::{Result, Write};
 
 /// Join a channel. This handles prepending a leading '#' for you if you omit it.
@@ -16,6 +16,8 @@ pub const fn join(channel: &str) -> Join<'_> {
 
 impl<'a> Encodable for Join<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        check::channel("channel", self.channel)?;
+
         write_nl!(buf, "JOIN {}", super::Channel(self.channel))
     }
 }
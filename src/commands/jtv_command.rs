@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{check, Encodable};
 use std::io::{Result, Write};
 
 /// Sends the data as a command to the 'jtv' channel (e.g. `/color #FFFFFF`)
@@ -19,6 +19,8 @@ impl<'a> Encodable for JtvCommand<'a> {
     where
         W: Write + ?Sized,
     {
+        check::text("data", self.data)?;
+
         write_jtv_cmd!(buf, self.data)
     }
 }
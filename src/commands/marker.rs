@@ -1,4 +1,4 @@
-use super::{Channel, Encodable, MaybeEmpty};
+use super::{check, Channel, Encodable, MaybeEmpty};
 use std::io::{Result, Write};
 
 /// Adds a stream marker (with an optional comment, **max 140** characters) at the current timestamp.
@@ -24,6 +24,9 @@ pub fn marker<'a>(channel: &'a str, comment: impl Into<Option<&'a str>>) -> Mark
 
 impl<'a> Encodable for Marker<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        check::channel("channel", self.channel)?;
+        check::opt_text("comment", self.comment)?;
+
         fn truncate(s: &str) -> &str {
             const MAX: usize = 140;
             if s.len() <= MAX {
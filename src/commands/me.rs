@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Sends an "emote" message in the third person to the channel
@@ -20,6 +20,9 @@ impl<'a> Encodable for Me<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::text("msg", self.msg)?;
+
         write_cmd!(buf, Channel(self.channel) => "/me {}", self.msg)
     }
 }
@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{check, Encodable};
 use std::io::{Result, Write};
 
 /// Leave a channel. This handles prepending a leading '#' for you if you omit it.
@@ -16,6 +16,8 @@ pub const fn part(channel: &str) -> Part<'_> {
 
 impl<'a> Encodable for Part<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        check::channel("channel", self.channel)?;
+
         write!(buf, "PART {}\r\n", super::Channel(self.channel))
     }
 }
@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{check, Encodable};
 use std::io::{Result, Write};
 
 /// Request a servver response  with the provided token
@@ -19,6 +19,8 @@ impl<'a> Encodable for Ping<'a> {
     where
         W: Write + ?Sized,
     {
+        check::text("token", self.token)?;
+
         write_nl!(buf, "PING {}", self.token)
     }
 }
@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{check, Encodable};
 use std::io::{Result, Write};
 
 /// Respond to a server request (normally a PING) with the provided token
@@ -19,6 +19,8 @@ impl<'a> Encodable for Pong<'a> {
     where
         W: Write + ?Sized,
     {
+        check::text("token", self.token)?;
+
         write_nl!(buf, "PONG :{}", self.token)
     }
 }
@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Send a normal message to a channel
@@ -20,6 +20,9 @@ impl<'a> Encodable for Privmsg<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::text("msg", self.msg)?;
+
         write_nl!(buf, "PRIVMSG {} :{}", Channel(self.channel), self.msg)
     }
 }
@@ -55,6 +58,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn privmsg_injection() {
+        test_encode_err(
+            privmsg("#museun", "hello\r\nJOIN #foo"),
+            EncodeError::InvalidCharacter {
+                field: "msg",
+                character: '\r',
+            },
+        );
+
+        test_encode_err(
+            privmsg("#museun foo", "hello"),
+            EncodeError::InvalidName {
+                field: "channel",
+                name: "#museun foo".to_string(),
+            },
+        );
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn privmsg_serde() {
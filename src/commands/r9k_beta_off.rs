@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 
 use std::io::{Result, Write};
 
@@ -20,6 +20,8 @@ impl<'a> Encodable for R9kBetaOff<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+
         write_cmd!(buf, Channel(self.channel) => "/r9kbetaoff")
     }
 }
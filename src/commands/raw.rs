@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{check, Encodable};
 use std::io::{Result, Write};
 
 /// Send a raw IRC-style message
@@ -19,6 +19,8 @@ impl<'a> Encodable for Raw<'a> {
     where
         W: Write + ?Sized,
     {
+        check::text("data", self.data)?;
+
         write_nl!(buf, "{}", self.data)
     }
 }
@@ -36,6 +38,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn raw_injection() {
+        test_encode_err(
+            raw("PRIVMSG #test :hello\r\nPART #test"),
+            EncodeError::InvalidCharacter {
+                field: "data",
+                character: '\r',
+            },
+        );
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn raw_serde() {
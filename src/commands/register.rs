@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{check, Encodable};
 use crate::twitch::UserConfig;
 
 use std::io::Write;
@@ -16,6 +16,10 @@ pub struct Register<'a> {
 /// Write the User Registration to the connection.
 ///
 /// This is required to be done before you do anything else.
+///
+/// The required capabilities are requested together, so Twitch either grants
+/// all of them or none. Each optional capability is requested on its own, so
+/// one being denied doesn't affect the others.
 pub fn register(user_config: &UserConfig) -> Register<'_> {
     // TODO serde really doesn't like this type, so lets clone it
     let user_config = user_config.clone();
@@ -31,12 +35,18 @@ impl<'a> Encodable for Register<'a> {
             name,
             token,
             capabilities,
+            optional_capabilities,
         } = &self.user_config;
 
+        check::name("name", name)?;
+        check::text("token", token)?;
+
         // the caps have to be written first
-        for cap in capabilities {
-            let cap = cap.encode_as_str();
-            write!(buf, "{}\r\n", cap)?;
+        if !capabilities.is_empty() {
+            super::cap_req(capabilities).encode(buf)?;
+        }
+        for cap in optional_capabilities {
+            super::cap_req(std::slice::from_ref(cap)).encode(buf)?;
         }
 
         write!(buf, "PASS {}\r\n", token)?;
@@ -49,6 +59,7 @@ impl<'a> Encodable for Register<'a> {
 mod tests {
     use super::super::*;
     use super::*;
+    use crate::twitch::Capability;
 
     #[test]
     fn register_encode() {
@@ -60,9 +71,29 @@ mod tests {
 
         test_encode(
             register(&config),
-            "CAP REQ :twitch.tv/membership\r\n\
-            CAP REQ :twitch.tv/tags\r\n\
+            "CAP REQ :twitch.tv/membership twitch.tv/tags twitch.tv/commands\r\n\
+            PASS justinfan1234\r\n\
+            NICK justinfan1234\r\n",
+        )
+    }
+
+    #[test]
+    fn register_optional_capabilities_encode() {
+        let config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[Capability::Tags])
+            .optional_capabilities(&[
+                Capability::Commands,
+                Capability::Custom("twitch.tv/foo".into()),
+            ])
+            .build()
+            .unwrap();
+
+        test_encode(
+            register(&config),
+            "CAP REQ :twitch.tv/tags\r\n\
             CAP REQ :twitch.tv/commands\r\n\
+            CAP REQ :twitch.tv/foo\r\n\
             PASS justinfan1234\r\n\
             NICK justinfan1234\r\n",
         )
@@ -79,9 +110,7 @@ mod tests {
 
         test_serde(
             register(&config),
-            "CAP REQ :twitch.tv/membership\r\n\
-            CAP REQ :twitch.tv/tags\r\n\
-            CAP REQ :twitch.tv/commands\r\n\
+            "CAP REQ :twitch.tv/membership twitch.tv/tags twitch.tv/commands\r\n\
             PASS justinfan1234\r\n\
             NICK justinfan1234\r\n",
         )
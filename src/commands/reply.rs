@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Reply to a specific message (using an UUID) on a channel
@@ -11,14 +11,11 @@ pub struct Reply<'a> {
     pub(crate) msg: &'a str,
 }
 
-// "reply-parent-display-name": "shaken_bot",
-// "reply-parent-msg-body": "hello\\smuseun!",
-// "reply-parent-msg-id": "1b136720-3a9a-4805-ab60-8c083e9f6fd2",
-// "reply-parent-user-id": "241015868",
-// "reply-parent-user-login": "shaken_bot",
-// "id": "2953829c-177c-42a3-9497-aed7ad916c78",
-
 /// Reply to a specific message (using an UUID) on a channel
+///
+/// The replies can be read back with [`Privmsg::reply_parent`][reply_parent]
+///
+/// [reply_parent]: ../messages/struct.Privmsg.html#method.reply_parent
 pub const fn reply<'a>(channel: &'a str, msg_id: &'a str, msg: &'a str) -> Reply<'a> {
     Reply {
         channel,
@@ -29,6 +26,10 @@ pub const fn reply<'a>(channel: &'a str, msg_id: &'a str, msg: &'a str) -> Reply
 
 impl<'a> Encodable for Reply<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        check::channel("channel", self.channel)?;
+        check::name("msg_id", self.msg_id)?;
+        check::text("msg", self.msg)?;
+
         write_nl!(
             buf,
             "@reply-parent-msg-id={} PRIVMSG {} :{}",
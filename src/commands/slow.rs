@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, duration, Channel, ChatDuration, Encodable};
 use std::io::{Result, Write};
 
 /// Enables slow mode (limit how often users may send messages).
@@ -7,20 +7,20 @@ use std::io::{Result, Write};
 #[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
 pub struct Slow<'a> {
     pub(crate) channel: &'a str,
-    pub(crate) duration: usize,
+    pub(crate) duration: ChatDuration,
 }
 
 /// Enables slow mode (limit how often users may send messages).
 ///
-/// Duration (optional, **default=120**) must be a positive number of seconds.
+/// Duration (optional, **default=120 seconds**) must be between `1 second` and `30 minutes`.
 ///
 /// Use [slow_off] to disable.
 ///
 /// [slow_off]: ./fn.slow_off.html
-pub fn slow(channel: &str, duration: impl Into<Option<usize>>) -> Slow<'_> {
+pub fn slow(channel: &str, duration: impl Into<Option<ChatDuration>>) -> Slow<'_> {
     Slow {
         channel,
-        duration: duration.into().unwrap_or(120),
+        duration: duration.into().unwrap_or_else(|| ChatDuration::minutes(2)),
     }
 }
 
@@ -29,11 +29,14 @@ impl<'a> Encodable for Slow<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::duration("duration", self.duration, &duration::SLOW)?;
+
         write_cmd!(
             buf,
             Channel(self.channel) =>
             "/slow {}",
-            &self.duration.to_string()
+            &self.duration.as_secs().to_string()
         )
     }
 }
@@ -45,31 +48,75 @@ mod tests {
 
     #[test]
     fn slow_encode() {
-        test_encode(slow("#museun", Some(42)), "PRIVMSG #museun :/slow 42\r\n");
-        test_encode(slow("#museun", 42), "PRIVMSG #museun :/slow 42\r\n");
+        test_encode(
+            slow("#museun", Some(ChatDuration::seconds(42))),
+            "PRIVMSG #museun :/slow 42\r\n",
+        );
+        test_encode(
+            slow("#museun", ChatDuration::seconds(42)),
+            "PRIVMSG #museun :/slow 42\r\n",
+        );
         test_encode(slow("#museun", None), "PRIVMSG #museun :/slow 120\r\n");
     }
 
     #[test]
     fn slow_ensure_channel_encode() {
-        test_encode(slow("museun", Some(42)), "PRIVMSG #museun :/slow 42\r\n");
-        test_encode(slow("museun", 42), "PRIVMSG #museun :/slow 42\r\n");
+        test_encode(
+            slow("museun", Some(ChatDuration::seconds(42))),
+            "PRIVMSG #museun :/slow 42\r\n",
+        );
+        test_encode(
+            slow("museun", ChatDuration::seconds(42)),
+            "PRIVMSG #museun :/slow 42\r\n",
+        );
         test_encode(slow("museun", None), "PRIVMSG #museun :/slow 120\r\n");
     }
 
+    #[test]
+    fn slow_duration_encode() {
+        test_encode(
+            slow("#museun", ChatDuration::minutes(30)),
+            "PRIVMSG #museun :/slow 1800\r\n",
+        );
+        test_encode_err(
+            slow("#museun", ChatDuration::ZERO),
+            EncodeError::DurationOutOfRange {
+                field: "duration",
+                duration: ChatDuration::ZERO,
+                min: ChatDuration::seconds(1),
+                max: ChatDuration::minutes(30),
+            },
+        );
+        assert!(slow("#museun", ChatDuration::seconds(1801))
+            .encode(&mut vec![])
+            .is_err());
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn slow_serde() {
-        test_serde(slow("#museun", Some(42)), "PRIVMSG #museun :/slow 42\r\n");
-        test_serde(slow("#museun", 42), "PRIVMSG #museun :/slow 42\r\n");
+        test_serde(
+            slow("#museun", Some(ChatDuration::seconds(42))),
+            "PRIVMSG #museun :/slow 42\r\n",
+        );
+        test_serde(
+            slow("#museun", ChatDuration::seconds(42)),
+            "PRIVMSG #museun :/slow 42\r\n",
+        );
         test_serde(slow("#museun", None), "PRIVMSG #museun :/slow 120\r\n");
     }
 
     #[test]
     #[cfg(feature = "serde")]
     fn slow_ensure_channel_serde() {
-        test_serde(slow("museun", Some(42)), "PRIVMSG #museun :/slow 42\r\n");
-        test_serde(slow("museun", 42), "PRIVMSG #museun :/slow 42\r\n");
+        test_serde(
+            slow("museun", Some(ChatDuration::seconds(42))),
+            "PRIVMSG #museun :/slow 42\r\n",
+        );
+        test_serde(
+            slow("museun", ChatDuration::seconds(42)),
+            "PRIVMSG #museun :/slow 42\r\n",
+        );
         test_serde(slow("museun", None), "PRIVMSG #museun :/slow 120\r\n");
     }
 }
@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Enables subscribers-only mode (only subscribers may chat in this channel).
@@ -23,6 +23,8 @@ impl<'a> Encodable for Subscribers<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+
         write_cmd!(buf, Channel(self.channel) => "/subscribers")
     }
 }
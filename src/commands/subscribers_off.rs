@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Disables subscribers-only mode.
@@ -19,6 +19,8 @@ impl<'a> Encodable for SubscribersOff<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+
         write_cmd!(buf, Channel(self.channel) => "/subscribersoff")
     }
 }
@@ -1,4 +1,4 @@
-use super::{Channel, Encodable, MaybeEmpty};
+use super::{check, duration, Channel, ChatDuration, Encodable, MaybeEmpty};
 use std::io::{Result, Write};
 
 /// Temporarily prevent a user from chatting.
@@ -8,32 +8,25 @@ use std::io::{Result, Write};
 pub struct Timeout<'a> {
     pub(crate) channel: &'a str,
     pub(crate) username: &'a str,
-    pub(crate) duration: Option<&'a str>,
+    pub(crate) duration: Option<ChatDuration>,
     pub(crate) reason: Option<&'a str>,
 }
 
 /// Temporarily prevent a user from chatting.
 ///
-/// * duration (*optional*, default=`10 minutes`) must be a positive integer.
-/// * time unit (*optional*, default=`s`) must be one of
-///   * s
-///   * m
-///   * h
-///   * d
-///   * w
-/// * maximum duration is `2 weeks`.
-///
-/// Combinations like `1d2h` are also allowed.
+/// Duration (*optional*, default=`10 minutes`) must be between `1 second` and
+/// `2 weeks`. See [ChatDuration] for how to make one, e.g. from `"1d2h"`.
 ///
 /// Reason is optional and will be shown to the target user and other moderators.
 ///
 /// Use [untimeout] to remove a timeout.
 ///
 /// [untimeout]: ./fn.untimeout.html
+/// [ChatDuration]: ./struct.ChatDuration.html
 pub fn timeout<'a>(
     channel: &'a str,
     username: &'a str,
-    duration: impl Into<Option<&'a str>>,
+    duration: impl Into<Option<ChatDuration>>,
     reason: impl Into<Option<&'a str>>,
 ) -> Timeout<'a> {
     Timeout {
@@ -49,13 +42,25 @@ impl<'a> Encodable for Timeout<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::name("username", self.username)?;
+        if let Some(duration) = self.duration {
+            check::duration("duration", duration, &duration::TIMEOUT)?;
+        }
+        check::opt_text("reason", self.reason)?;
+
+        let duration = self.duration.map(|d| d.to_string());
         write_cmd!(buf, Channel(self.channel)=>
             "/timeout {}{}{}",
             self.username,
-            MaybeEmpty(self.duration),
+            MaybeEmpty(duration.as_deref()),
             MaybeEmpty(self.reason),
         )
     }
+
+    fn priority(&self) -> crate::writer::Priority {
+        crate::writer::Priority::Moderation
+    }
 }
 
 #[cfg(test)]
@@ -70,7 +75,7 @@ mod tests {
             "PRIVMSG #museun :/timeout museun\r\n",
         );
         test_encode(
-            timeout("#museun", "museun", Some("1d2h"), None),
+            timeout("#museun", "museun", Some(ChatDuration::hours(26)), None),
             "PRIVMSG #museun :/timeout museun 1d2h\r\n",
         );
         test_encode(
@@ -78,7 +83,12 @@ mod tests {
             "PRIVMSG #museun :/timeout museun spamming\r\n",
         );
         test_encode(
-            timeout("#museun", "museun", Some("1d2h"), Some("spamming")),
+            timeout(
+                "#museun",
+                "museun",
+                Some(ChatDuration::hours(26)),
+                Some("spamming"),
+            ),
             "PRIVMSG #museun :/timeout museun 1d2h spamming\r\n",
         );
     }
@@ -90,7 +100,7 @@ mod tests {
             "PRIVMSG #museun :/timeout museun\r\n",
         );
         test_encode(
-            timeout("museun", "museun", Some("1d2h"), None),
+            timeout("museun", "museun", Some(ChatDuration::hours(26)), None),
             "PRIVMSG #museun :/timeout museun 1d2h\r\n",
         );
         test_encode(
@@ -98,11 +108,40 @@ mod tests {
             "PRIVMSG #museun :/timeout museun spamming\r\n",
         );
         test_encode(
-            timeout("museun", "museun", Some("1d2h"), Some("spamming")),
+            timeout(
+                "museun",
+                "museun",
+                Some(ChatDuration::hours(26)),
+                Some("spamming"),
+            ),
             "PRIVMSG #museun :/timeout museun 1d2h spamming\r\n",
         );
     }
 
+    #[test]
+    fn timeout_duration_encode() {
+        test_encode(
+            timeout("#museun", "museun", ChatDuration::seconds(90), None),
+            "PRIVMSG #museun :/timeout museun 1m30s\r\n",
+        );
+        test_encode(
+            timeout("#museun", "museun", ChatDuration::weeks(2), None),
+            "PRIVMSG #museun :/timeout museun 2w\r\n",
+        );
+        test_encode_err(
+            timeout("#museun", "museun", ChatDuration::ZERO, None),
+            EncodeError::DurationOutOfRange {
+                field: "duration",
+                duration: ChatDuration::ZERO,
+                min: ChatDuration::seconds(1),
+                max: ChatDuration::weeks(2),
+            },
+        );
+        assert!(timeout("#museun", "museun", ChatDuration::weeks(3), None)
+            .encode(&mut vec![])
+            .is_err());
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn timeout_serde() {
@@ -111,7 +150,7 @@ mod tests {
             "PRIVMSG #museun :/timeout museun\r\n",
         );
         test_serde(
-            timeout("#museun", "museun", Some("1d2h"), None),
+            timeout("#museun", "museun", Some(ChatDuration::hours(26)), None),
             "PRIVMSG #museun :/timeout museun 1d2h\r\n",
         );
         test_serde(
@@ -119,7 +158,12 @@ mod tests {
             "PRIVMSG #museun :/timeout museun spamming\r\n",
         );
         test_serde(
-            timeout("#museun", "museun", Some("1d2h"), Some("spamming")),
+            timeout(
+                "#museun",
+                "museun",
+                Some(ChatDuration::hours(26)),
+                Some("spamming"),
+            ),
             "PRIVMSG #museun :/timeout museun 1d2h spamming\r\n",
         );
     }
@@ -132,7 +176,7 @@ mod tests {
             "PRIVMSG #museun :/timeout museun\r\n",
         );
         test_serde(
-            timeout("museun", "museun", Some("1d2h"), None),
+            timeout("museun", "museun", Some(ChatDuration::hours(26)), None),
             "PRIVMSG #museun :/timeout museun 1d2h\r\n",
         );
         test_serde(
@@ -140,7 +184,12 @@ mod tests {
             "PRIVMSG #museun :/timeout museun spamming\r\n",
         );
         test_serde(
-            timeout("museun", "museun", Some("1d2h"), Some("spamming")),
+            timeout(
+                "museun",
+                "museun",
+                Some(ChatDuration::hours(26)),
+                Some("spamming"),
+            ),
             "PRIVMSG #museun :/timeout museun 1d2h spamming\r\n",
         );
     }
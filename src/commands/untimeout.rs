@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{check, Channel, Encodable};
 use std::io::{Result, Write};
 
 /// Removes a timeout on a user.
@@ -20,8 +20,15 @@ impl<'a> Encodable for Untimeout<'a> {
     where
         W: Write + ?Sized,
     {
+        check::channel("channel", self.channel)?;
+        check::name("username", self.username)?;
+
         write_cmd!(buf, Channel(self.channel) => "/untimeout {}", self.username)
     }
+
+    fn priority(&self) -> crate::writer::Priority {
+        crate::writer::Priority::Moderation
+    }
 }
 
 #[cfg(test)]
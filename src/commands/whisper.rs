@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{check, Encodable};
 use std::io::{Result, Write};
 
 /// Whispers a message to the username.
@@ -20,6 +20,9 @@ impl<'a> Encodable for Whisper<'a> {
     where
         W: Write + ?Sized,
     {
+        check::name("username", self.username)?;
+        check::text("message", self.message)?;
+
         write_jtv_cmd!(buf, "/w {} {}", self.username, self.message)
     }
 }
@@ -37,6 +40,22 @@ mod tests {
         )
     }
 
+    #[test]
+    fn whisper_injection() {
+        test_encode_err(
+            whisper("museun", "hello\0world"),
+            EncodeError::InvalidCharacter {
+                field: "message",
+                character: '\0',
+            },
+        );
+
+        test_encode_err(
+            whisper("", "hello world"),
+            EncodeError::EmptyName { field: "username" },
+        );
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn whisper_serde() {
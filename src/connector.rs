@@ -23,11 +23,37 @@
 //! | [`tokio`](https://docs.rs/tokio/latest/tokio/)             | `tokio`     | `"tokio-util"`, `"tokio-rustls"`, `"webpki-roots"`   | [`rustls`][rustls]         |
 //! | [`tokio`](https://docs.rs/tokio/latest/tokio/)             | `tokio`     | `"tokio-util"`, `"tokio-native-tls"`, `"native-tls"` | [`native-tls`][native-tls] |
 //!
+//! ## Proxies
+//!
+//! Each runtime also has proxy connectors (e.g. [`SmolProxyConnector`][smol_proxy]) that tunnel
+//! through an HTTP `CONNECT` or SOCKS5 [`Proxy`][proxy] before handing the stream to the runner.
+//! They are enabled by the same features as the connectors above. The proxy is connected to like
+//! any other address, see [Connecting](#connecting).
+//!
+//! ## Connecting
+//!
+//! The connectors resolve their address each time they connect, so building one never touches the network.
+//! When an address resolves to several IPs, the attempts are raced (IPv6 and IPv4 interleaved, "happy eyeballs" style)
+//! and the first one to connect is used. Each attempt and the connection as a whole have timeouts, see
+//! [`DEFAULT_ATTEMPT_TIMEOUT`] and [`DEFAULT_CONNECT_TIMEOUT`].
+//!
+//! [`DEFAULT_ATTEMPT_TIMEOUT`]: ./constant.DEFAULT_ATTEMPT_TIMEOUT.html
+//! [`DEFAULT_CONNECT_TIMEOUT`]: ./constant.DEFAULT_CONNECT_TIMEOUT.html
+//!
+//! ## Custom TLS configuration
+//!
+//! The TLS connectors have a `with_config` constructor that takes your own `rustls::ClientConfig` (or `native_tls::TlsConnector`),
+//! so you can trust a private CA or a self-signed certificate. With the `"rustls-native-certs"` and `"rustls"` features,
+//! the `rustls` connectors also have a `with_native_roots` constructor that trusts the system's root certificates
+//! instead of the bundled `webpki-roots`.
+//!
+//! [smol_proxy]: ./smol/struct.ProxyConnector.html
+//! [proxy]: ./struct.Proxy.html
 //! [rustls]: https://docs.rs/rustls/0.18.1/rustls/
 //! [native-tls]: https://docs.rs/native-tls/0.2.4/native_tls/
 //!
 use futures_lite::{AsyncRead, AsyncWrite};
-use std::{future::Future, io::Result as IoResult, net::SocketAddr};
+use std::io::Result as IoResult;
 
 #[allow(unused_macros)]
 macro_rules! connector_ctor {
@@ -36,20 +62,30 @@ macro_rules! connector_ctor {
         $(#[$meta])*
         #[doc = "non-TLS connector that connects to the ***default Twitch*** address."]
         pub fn twitch() -> Self {
-            Self::custom($crate::TWITCH_IRC_ADDRESS).expect("twitch DNS resolution")
+            Self {
+                address: $crate::TWITCH_IRC_ADDRESS.into(),
+                timeouts: ::std::default::Default::default(),
+            }
         }
 
         #[doc = "Create a new"]
         $(#[$meta])*
-        #[doc = "non-TLS connector with a custom address."]
-        pub fn custom<A>(addrs: A) -> ::std::io::Result<Self>
+        #[doc = "non-TLS connector with a custom `host:port` address."]
+        #[doc = ""]
+        #[doc = "The address is resolved each time the connector connects. This returns an error if it isn't a `host:port` pair."]
+        pub fn custom<A>(address: A) -> ::std::io::Result<Self>
         where
-            A: ::std::net::ToSocketAddrs,
+            A: Into<::std::string::String>,
         {
-            addrs.to_socket_addrs().map(|addrs| Self {
-                addrs: addrs.collect(),
+            let address = address.into();
+            $crate::connector::connect::validate(&address)?;
+            Ok(Self {
+                address,
+                timeouts: ::std::default::Default::default(),
             })
         }
+
+        connector_ctor!(@timeouts);
     };
 
     (tls: $(#[$meta:meta])*) => {
@@ -57,28 +93,211 @@ macro_rules! connector_ctor {
         $(#[$meta])*
         #[doc = "TLS connector that connects to the ***default Twitch*** address."]
         pub fn twitch() -> Self {
-            Self::custom($crate::TWITCH_IRC_ADDRESS_TLS, $crate::TWITCH_TLS_DOMAIN)
-                .expect("twitch DNS resolution")
+            Self {
+                address: $crate::TWITCH_IRC_ADDRESS_TLS.into(),
+                tls_domain: $crate::TWITCH_TLS_DOMAIN.into(),
+                timeouts: ::std::default::Default::default(),
+                tls_config: ::std::default::Default::default(),
+            }
         }
 
-
         #[doc = "Create a new"]
         $(#[$meta])*
-        #[doc = "TLS connector with a custom address and TLS domain."]
-        pub fn custom<A, D>(addrs: A, domain: D) -> ::std::io::Result<Self>
+        #[doc = "TLS connector with a custom `host:port` address and TLS domain."]
+        #[doc = ""]
+        #[doc = "The address is resolved each time the connector connects. This returns an error if it isn't a `host:port` pair."]
+        pub fn custom<A, D>(address: A, domain: D) -> ::std::io::Result<Self>
         where
-            A: ::std::net::ToSocketAddrs,
+            A: Into<::std::string::String>,
             D: Into<::std::string::String>,
         {
-            let tls_domain = domain.into();
-            addrs.to_socket_addrs().map(|addrs| Self {
-                addrs: addrs.collect(),
-                tls_domain,
+            let address = address.into();
+            $crate::connector::connect::validate(&address)?;
+            Ok(Self {
+                address,
+                tls_domain: domain.into(),
+                timeouts: ::std::default::Default::default(),
+                tls_config: ::std::default::Default::default(),
             })
         }
+
+        connector_ctor!(@timeouts);
+    };
+
+    (tls_config: $config:ty, $(#[$meta:meta])*) => {
+        #[doc = "Create a new"]
+        $(#[$meta])*
+        #[doc = "TLS connector with a custom `host:port` address, TLS domain and TLS configuration."]
+        #[doc = ""]
+        #[doc = "The configuration is used instead of the default one, e.g. to trust a private CA or a self-signed certificate."]
+        pub fn with_config<A, D, C>(address: A, domain: D, config: C) -> ::std::io::Result<Self>
+        where
+            A: Into<::std::string::String>,
+            D: Into<::std::string::String>,
+            C: Into<$config>,
+        {
+            let mut this = Self::custom(address, domain)?;
+            this.tls_config = $crate::connector::tls_config::TlsConfig::custom(config.into());
+            Ok(this)
+        }
+    };
+
+    (native_roots: $(#[$meta:meta])*) => {
+        #[doc = "Create a new"]
+        $(#[$meta])*
+        #[doc = "TLS connector with a custom `host:port` address and TLS domain that trusts the system's root certificates."]
+        #[doc = ""]
+        #[doc = "This returns an error if the root certificates cannot be loaded."]
+        #[cfg(all(feature = "rustls-native-certs", feature = "rustls"))]
+        pub fn with_native_roots<A, D>(address: A, domain: D) -> ::std::io::Result<Self>
+        where
+            A: Into<::std::string::String>,
+            D: Into<::std::string::String>,
+        {
+            let config = $crate::connector::tls_config::native_roots()?;
+            Self::with_config(address, domain, ::std::sync::Arc::new(config))
+        }
+    };
+
+    (@timeouts) => {
+        #[doc = "How long to wait on a single address before giving up on it. `None` waits forever."]
+        #[doc = ""]
+        #[doc = "This defaults to [`DEFAULT_ATTEMPT_TIMEOUT`](../constant.DEFAULT_ATTEMPT_TIMEOUT.html)."]
+        pub fn attempt_timeout(mut self, timeout: impl Into<Option<::std::time::Duration>>) -> Self {
+            self.timeouts.attempt = timeout.into();
+            self
+        }
+
+        #[doc = "How long to wait for the whole connection, including resolving the address. `None` waits forever."]
+        #[doc = ""]
+        #[doc = "This defaults to [`DEFAULT_CONNECT_TIMEOUT`](../constant.DEFAULT_CONNECT_TIMEOUT.html)."]
+        pub fn connect_timeout(mut self, timeout: impl Into<Option<::std::time::Duration>>) -> Self {
+            self.timeouts.connect = timeout.into();
+            self
+        }
     };
 }
 
+#[allow(unused_macros)]
+macro_rules! proxy_connector_ctor {
+    (non_tls: $(#[$meta:meta])*) => {
+        #[doc = "Create a new"]
+        $(#[$meta])*
+        #[doc = "non-TLS connector that tunnels through `proxy` to the ***default Twitch*** address."]
+        pub fn twitch(proxy: $crate::connector::Proxy) -> Self {
+            Self::custom(proxy, $crate::TWITCH_IRC_ADDRESS)
+        }
+
+        #[doc = "Create a new"]
+        $(#[$meta])*
+        #[doc = "non-TLS connector that tunnels through `proxy` to a custom `host:port` address."]
+        #[doc = ""]
+        #[doc = "The proxy resolves the address."]
+        pub fn custom<A>(proxy: $crate::connector::Proxy, target: A) -> Self
+        where
+            A: Into<::std::string::String>,
+        {
+            Self {
+                proxy,
+                target: target.into(),
+                timeouts: ::std::default::Default::default(),
+            }
+        }
+
+        connector_ctor!(@timeouts);
+    };
+
+    (tls: $(#[$meta:meta])*) => {
+        #[doc = "Create a new"]
+        $(#[$meta])*
+        #[doc = "TLS connector that tunnels through `proxy` to the ***default Twitch*** address."]
+        pub fn twitch(proxy: $crate::connector::Proxy) -> Self {
+            Self::custom(
+                proxy,
+                $crate::TWITCH_IRC_ADDRESS_TLS,
+                $crate::TWITCH_TLS_DOMAIN,
+            )
+        }
+
+        #[doc = "Create a new"]
+        $(#[$meta])*
+        #[doc = "TLS connector that tunnels through `proxy` to a custom `host:port` address and TLS domain."]
+        #[doc = ""]
+        #[doc = "The proxy resolves the address."]
+        pub fn custom<A, D>(proxy: $crate::connector::Proxy, target: A, domain: D) -> Self
+        where
+            A: Into<::std::string::String>,
+            D: Into<::std::string::String>,
+        {
+            Self {
+                proxy,
+                target: target.into(),
+                tls_domain: domain.into(),
+                timeouts: ::std::default::Default::default(),
+                tls_config: ::std::default::Default::default(),
+            }
+        }
+
+        connector_ctor!(@timeouts);
+    };
+
+    (tls_config: $config:ty, $(#[$meta:meta])*) => {
+        #[doc = "Create a new"]
+        $(#[$meta])*
+        #[doc = "TLS connector that tunnels through `proxy` to a custom `host:port` address, with a TLS domain and TLS configuration."]
+        #[doc = ""]
+        #[doc = "The configuration is used instead of the default one, e.g. to trust a private CA or a self-signed certificate."]
+        pub fn with_config<A, D, C>(
+            proxy: $crate::connector::Proxy,
+            target: A,
+            domain: D,
+            config: C,
+        ) -> Self
+        where
+            A: Into<::std::string::String>,
+            D: Into<::std::string::String>,
+            C: Into<$config>,
+        {
+            let mut this = Self::custom(proxy, target, domain);
+            this.tls_config = $crate::connector::tls_config::TlsConfig::custom(config.into());
+            this
+        }
+    };
+}
+
+// only the runtime connectors connect
+#[cfg_attr(
+    not(any(
+        feature = "async-io",
+        feature = "async-std",
+        feature = "smol",
+        feature = "tokio"
+    )),
+    allow(dead_code)
+)]
+mod connect;
+pub use connect::{DEFAULT_ATTEMPT_TIMEOUT, DEFAULT_CONNECT_TIMEOUT};
+
+// the handshakes are only used by the runtime connectors
+#[cfg_attr(
+    not(any(
+        feature = "async-io",
+        feature = "async-std",
+        feature = "smol",
+        feature = "tokio"
+    )),
+    allow(dead_code)
+)]
+mod proxy;
+pub use proxy::{Proxy, ProxyError, ProxyKind};
+
+#[cfg(any(
+    feature = "async-tls",
+    feature = "tokio-rustls",
+    feature = "tokio-native-tls"
+))]
+mod tls_config;
+
 #[cfg(feature = "async-io")]
 /// Connector for using an [`async_io`](https://docs.rs/async-io/latest/async_io/) wrapper over [`std::net::TcpStream`](https://doc.rust-lang.org/std/net/struct.TcpStream.html)
 pub mod async_io;
@@ -91,6 +310,14 @@ pub use self::async_io::Connector as AsyncIoConnector;
 #[doc(inline)]
 pub use self::async_io::ConnectorTls as AsyncIoConnectorTls;
 
+#[cfg(feature = "async-io")]
+#[doc(inline)]
+pub use self::async_io::ProxyConnector as AsyncIoProxyConnector;
+
+#[cfg(all(feature = "async-io", feature = "async-tls"))]
+#[doc(inline)]
+pub use self::async_io::ProxyConnectorTls as AsyncIoProxyConnectorTls;
+
 #[cfg(feature = "async-std")]
 /// Connector for using an [`async_std::net::TcpStream`](https://docs.rs/async-std/latest/async_std/net/struct.TcpStream.html)
 pub mod async_std;
@@ -103,6 +330,14 @@ pub use self::async_std::Connector as AsyncStdConnector;
 #[doc(inline)]
 pub use self::async_std::ConnectorTls as AsyncStdConnectorTls;
 
+#[cfg(feature = "async-std")]
+#[doc(inline)]
+pub use self::async_std::ProxyConnector as AsyncStdProxyConnector;
+
+#[cfg(all(feature = "async-std", feature = "async-tls"))]
+#[doc(inline)]
+pub use self::async_std::ProxyConnectorTls as AsyncStdProxyConnectorTls;
+
 #[cfg(feature = "smol")]
 /// Connector for using a [`smol::Async`](https://docs.rs/smol/latest/smol/struct.Async.html) wrapper over [`std::net::TcpStream`](https://doc.rust-lang.org/std/net/struct.TcpStream.html)
 pub mod smol;
@@ -115,6 +350,14 @@ pub use self::smol::Connector as SmolConnector;
 #[doc(inline)]
 pub use self::smol::ConnectorTls as SmolConnectorTls;
 
+#[cfg(feature = "smol")]
+#[doc(inline)]
+pub use self::smol::ProxyConnector as SmolProxyConnector;
+
+#[cfg(all(feature = "smol", feature = "async-tls"))]
+#[doc(inline)]
+pub use self::smol::ProxyConnectorTls as SmolProxyConnectorTls;
+
 #[cfg(all(feature = "tokio", feature = "tokio-util"))]
 /// Connector for using a [`tokio::net::TcpStream`](https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html)
 pub mod tokio;
@@ -141,6 +384,28 @@ pub use self::tokio::ConnectorRustTls as TokioConnectorRustTls;
 #[doc(inline)]
 pub use self::tokio::ConnectorNativeTls as TokioConnectorNativeTls;
 
+#[cfg(all(feature = "tokio", feature = "tokio-util"))]
+#[doc(inline)]
+pub use self::tokio::ProxyConnector as TokioProxyConnector;
+
+#[cfg(all(
+    feature = "tokio",
+    feature = "tokio-util",
+    feature = "tokio-rustls",
+    feature = "webpki-roots"
+))]
+#[doc(inline)]
+pub use self::tokio::ProxyConnectorRustTls as TokioProxyConnectorRustTls;
+
+#[cfg(all(
+    feature = "tokio",
+    feature = "tokio-util",
+    feature = "tokio-native-tls",
+    feature = "native-tls"
+))]
+#[doc(inline)]
+pub use self::tokio::ProxyConnectorNativeTls as TokioProxyConnectorNativeTls;
+
 /// The connector trait. This is used to abstract out runtimes.
 ///
 /// You can implement this on your own type to provide a custom connection behavior.
@@ -155,36 +420,12 @@ pub trait Connector: Send + Sync + Clone {
     fn connect(&mut self) -> crate::BoxedFuture<IoResult<Self::Output>>;
 }
 
-// This is used because smol/async_io uses an indv. SocketAddr for their connect
-// instead of the normal ToSocketAddrs trait
-//
-// thus this will be dead if those features aren't enabled.
-#[allow(dead_code)]
-async fn try_connect<F, T, R>(addrs: &[SocketAddr], connect: F) -> IoResult<T>
-where
-    F: Fn(SocketAddr) -> R + Send,
-    R: Future<Output = IoResult<T>> + Send,
-    T: Send,
-{
-    let mut last = None;
-    for addr in addrs {
-        let fut = connect(*addr);
-        match fut.await {
-            Ok(socket) => return Ok(socket),
-            Err(err) => last.replace(err),
-        };
+mod required {
+    #[cfg(all(feature = "rustls-native-certs", not(feature = "rustls")))]
+    compile_error! {
+        "'rustls' must be enabled when 'rustls-native-certs' is enabled"
     }
 
-    match last {
-        Some(last) => Err(last),
-        None => Err(std::io::Error::new(
-            std::io::ErrorKind::ConnectionRefused,
-            "cannot connect with any provided address",
-        )),
-    }
-}
-
-mod required {
     #[cfg(all(
         feature = "async-tls",
         not(any(feature = "async-io", feature = "async-std", feature = "smol"))
@@ -218,8 +459,12 @@ mod required {
 #[cfg(test)]
 #[allow(dead_code)]
 mod testing {
-    use crate::connector::Connector as ConnectorTrait;
+    use crate::connector::{Connector as ConnectorTrait, ProxyKind};
     use futures_lite::{AsyncRead, AsyncWrite};
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    };
 
     pub fn assert_connector<T: ConnectorTrait>() {}
     pub fn assert_type_is_read_write<T: AsyncRead + AsyncWrite>() {}
@@ -230,4 +475,204 @@ mod testing {
         for<'a> &'a T::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
     {
     }
+
+    /// What the target server sends when a client connects
+    pub const READY: &str = ":tmi.twitch.tv 376 justinfan1234 :>\r\n";
+
+    /// Spawn a server that accepts one client and sends it `READY`
+    pub fn spawn_target() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.write_all(READY.as_bytes());
+            // wait for the client to hang up
+            let _ = stream.read(&mut [0; 1]);
+        });
+        addr
+    }
+
+    /// Spawn a TLS server for `localhost` that accepts one client and sends it `READY`
+    #[cfg(feature = "rustls")]
+    pub fn spawn_tls_target() -> SocketAddr {
+        const CERT: &[u8] = include_bytes!("connector/testdata/localhost.der");
+        const KEY: &[u8] = include_bytes!("connector/testdata/localhost.key.der");
+
+        let mut config = rustls::ServerConfig::new(rustls::NoClientAuth::new());
+        config
+            .set_single_cert(
+                vec![rustls::Certificate(CERT.to_vec())],
+                rustls::PrivateKey(KEY.to_vec()),
+            )
+            .unwrap();
+        let config = std::sync::Arc::new(config);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut session = rustls::ServerSession::new(&config);
+            let mut stream = rustls::Stream::new(&mut session, &mut stream);
+            let _ = stream.write_all(READY.as_bytes());
+            // wait for the client to hang up
+            let _ = stream.read(&mut [0; 1]);
+        });
+        addr
+    }
+
+    /// A client configuration that trusts the CA that signed the `spawn_tls_target` certificate
+    #[cfg(feature = "rustls")]
+    pub fn test_client_config() -> rustls::ClientConfig {
+        const CA: &[u8] = include_bytes!("connector/testdata/ca.der");
+
+        let mut config = rustls::ClientConfig::new();
+        config
+            .root_store
+            .add(&rustls::Certificate(CA.to_vec()))
+            .unwrap();
+        config
+    }
+
+    /// Spawn a proxy that tunnels one client, optionally requiring a username and password
+    pub fn spawn_proxy(
+        kind: ProxyKind,
+        credentials: Option<(&'static str, &'static str)>,
+    ) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let target = match kind {
+                ProxyKind::Http => http_handshake(&stream, credentials),
+                ProxyKind::Socks5 => socks5_handshake(&stream, credentials),
+            };
+            if let Some(target) = target {
+                relay(stream, target)
+            }
+        });
+        addr.to_string()
+    }
+
+    fn http_handshake(
+        mut stream: &TcpStream,
+        credentials: Option<(&str, &str)>,
+    ) -> Option<TcpStream> {
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        reader.read_line(&mut request).ok()?;
+        let target = request
+            .strip_prefix("CONNECT ")?
+            .split(' ')
+            .next()?
+            .to_string();
+
+        let mut authorization = None;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).ok()?;
+            if line == "\r\n" {
+                break;
+            }
+            if let Some(value) = line.strip_prefix("Proxy-Authorization: Basic ") {
+                authorization.replace(value.trim().to_string());
+            }
+        }
+
+        if let Some((user, pass)) = credentials {
+            let expected = super::proxy::base64(format!("{}:{}", user, pass).as_bytes());
+            if authorization.as_deref() != Some(&*expected) {
+                let _ = stream.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n");
+                return None;
+            }
+        }
+
+        match TcpStream::connect(target) {
+            Ok(target) => {
+                stream
+                    .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                    .ok()?;
+                Some(target)
+            }
+            Err(..) => {
+                let _ = stream.write_all(b"HTTP/1.1 502 Bad Gateway\r\n\r\n");
+                None
+            }
+        }
+    }
+
+    fn socks5_handshake(
+        stream: &TcpStream,
+        credentials: Option<(&str, &str)>,
+    ) -> Option<TcpStream> {
+        let read = |len: usize| {
+            let mut buf = vec![0; len];
+            let mut stream = stream;
+            stream.read_exact(&mut buf).map(|_| buf).ok()
+        };
+
+        let head = read(2)?;
+        let methods = read(head[1] as usize)?;
+
+        let method = if credentials.is_some() { 0x02 } else { 0x00 };
+        if !methods.contains(&method) {
+            let _ = { stream }.write_all(&[0x05, 0xFF]);
+            return None;
+        }
+        { stream }.write_all(&[0x05, method]).ok()?;
+
+        if let Some((user, pass)) = credentials {
+            let _version = read(1)?;
+            let len = read(1)?[0] as usize;
+            let username = read(len)?;
+            let len = read(1)?[0] as usize;
+            let password = read(len)?;
+            if username != user.as_bytes() || password != pass.as_bytes() {
+                let _ = { stream }.write_all(&[0x01, 0x01]);
+                return None;
+            }
+            { stream }.write_all(&[0x01, 0x00]).ok()?;
+        }
+
+        let head = read(4)?;
+        let host = match head[3] {
+            0x01 => read(4).map(|ip| format!("{}.{}.{}.{}", ip[0], ip[1], ip[2], ip[3]))?,
+            0x03 => {
+                let len = read(1)?[0] as usize;
+                String::from_utf8(read(len)?).ok()?
+            }
+            _ => return None,
+        };
+        let port = read(2)?;
+        let port = u16::from_be_bytes([port[0], port[1]]);
+
+        let target = (host.as_str(), port)
+            .to_socket_addrs()
+            .ok()
+            .and_then(|addrs| {
+                addrs
+                    .filter(SocketAddr::is_ipv4)
+                    .find_map(|addr| TcpStream::connect(addr).ok())
+            });
+
+        match target {
+            Some(target) => {
+                { stream }
+                    .write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+                    .ok()?;
+                Some(target)
+            }
+            None => {
+                let _ = { stream }.write_all(&[0x05, 0x05, 0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+                None
+            }
+        }
+    }
+
+    fn relay(client: TcpStream, target: TcpStream) {
+        let (mut client_read, mut target_write) =
+            (client.try_clone().unwrap(), target.try_clone().unwrap());
+        std::thread::spawn(move || std::io::copy(&mut client_read, &mut target_write));
+        let (mut target_read, mut client_write) = (target, client);
+        let _ = std::io::copy(&mut target_read, &mut client_write);
+    }
 }
@@ -3,7 +3,8 @@ use super::*;
 /// A `async_io` connector. This does not use TLS
 #[derive(Debug, Clone, PartialEq)]
 pub struct Connector {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
 }
 
 impl Connector {
@@ -16,8 +17,8 @@ impl crate::connector::Connector for Connector {
     type Output = TcpStream;
 
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
-        let addrs = self.addrs.clone();
-        let fut = async move { try_connect(&*addrs, TcpStream::connect).await };
+        let this = self.clone();
+        let fut = async move { connect(&this.address, this.timeouts, TcpStream::connect).await };
         Box::pin(fut)
     }
 }
@@ -0,0 +1,114 @@
+use super::*;
+use crate::connector::{proxy::tunnel, Proxy};
+
+#[cfg(feature = "async-tls")]
+use crate::connector::tls_config::TlsConfig;
+
+/// A `async_io` connector that tunnels through a [`Proxy`](../struct.Proxy.html). This does not use TLS
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyConnector {
+    proxy: Proxy,
+    target: String,
+    timeouts: Timeouts,
+}
+
+impl ProxyConnector {
+    proxy_connector_ctor!(non_tls:
+        /// [`async_io`](https://docs.rs/async-io/latest/async_io/)
+    );
+}
+
+impl crate::connector::Connector for ProxyConnector {
+    type Output = TcpStream;
+
+    fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+        let this = self.clone();
+        let fut = async move {
+            let mut stream =
+                connect(this.proxy.address(), this.timeouts, TcpStream::connect).await?;
+            tunnel(&mut stream, &this.proxy, &this.target).await?;
+            Ok(stream)
+        };
+        Box::pin(fut)
+    }
+}
+
+/// A `async_io` connector that tunnels through a [`Proxy`](../struct.Proxy.html) and then uses `async-tls` (a `rustls` wrapper). This does use TLS.
+#[cfg(feature = "async-tls")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyConnectorTls {
+    proxy: Proxy,
+    target: String,
+    timeouts: Timeouts,
+    tls_domain: String,
+    tls_config: TlsConfig<async_tls::TlsConnector>,
+}
+
+#[cfg(feature = "async-tls")]
+impl ProxyConnectorTls {
+    proxy_connector_ctor!(tls:
+        /// [`async_io`](https://docs.rs/async-io/latest/async_io/)
+    );
+
+    proxy_connector_ctor!(tls_config: async_tls::TlsConnector,
+        /// [`async_io`](https://docs.rs/async-io/latest/async_io/)
+    );
+}
+
+#[cfg(feature = "async-tls")]
+impl crate::connector::Connector for ProxyConnectorTls {
+    type Output = async_dup::Mutex<async_tls::client::TlsStream<TcpStream>>;
+
+    fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+        let this = self.clone();
+        let fut = async move {
+            let mut stream =
+                connect(this.proxy.address(), this.timeouts, TcpStream::connect).await?;
+            tunnel(&mut stream, &this.proxy, &this.target).await?;
+            let connector = this.tls_config.get().cloned();
+            connector
+                .unwrap_or_else(async_tls::TlsConnector::new)
+                .connect(this.tls_domain, stream)
+                .await
+                .map(async_dup::Mutex::new)
+        };
+        Box::pin(fut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connector::{testing::*, Connector as _, ProxyKind};
+    use futures_lite::{future::block_on, AsyncBufReadExt as _};
+
+    #[test]
+    fn assert_connector_trait_is_fulfilled() {
+        use crate::connector::Connector as C;
+
+        let proxy = Proxy::http("127.0.0.1:8080").unwrap();
+        assert_connector::<ProxyConnector>();
+        assert_type_is_read_write::<<ProxyConnector as C>::Output>();
+        assert_obj_is_sane(ProxyConnector::twitch(proxy));
+    }
+
+    #[test]
+    fn connect() {
+        for &kind in &[ProxyKind::Http, ProxyKind::Socks5] {
+            let target = format!("localhost:{}", spawn_target().port());
+            let proxy = Proxy::new(kind, spawn_proxy(kind, None)).unwrap();
+            let mut connector = ProxyConnector::custom(proxy, target);
+
+            let line = block_on(async {
+                let stream = connector.connect().await.unwrap();
+                let mut line = String::new();
+                futures_lite::io::BufReader::new(stream)
+                    .read_line(&mut line)
+                    .await
+                    .unwrap();
+                line
+            });
+            assert_eq!(line, READY);
+        }
+    }
+}
@@ -1,17 +1,28 @@
 use super::*;
+use crate::connector::tls_config::TlsConfig;
 use std::io::Result;
 
 /// A `async_io` connector that uses `async-tls` (a `rustls` wrapper). This does use TLS.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectorTls {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
     tls_domain: String,
+    tls_config: TlsConfig<async_tls::TlsConnector>,
 }
 
 impl ConnectorTls {
     connector_ctor!(tls:
         /// [`async_io`](https://docs.rs/async-io/latest/async_io/)
     );
+
+    connector_ctor!(tls_config: async_tls::TlsConnector,
+        /// [`async_io`](https://docs.rs/async-io/latest/async_io/)
+    );
+
+    connector_ctor!(native_roots:
+        /// [`async_io`](https://docs.rs/async-io/latest/async_io/)
+    );
 }
 
 impl crate::connector::Connector for ConnectorTls {
@@ -20,8 +31,10 @@ impl crate::connector::Connector for ConnectorTls {
     fn connect(&mut self) -> BoxedFuture<Result<Self::Output>> {
         let this = self.clone();
         let fut = async move {
-            let stream = try_connect(&*this.addrs, TcpStream::connect).await?;
-            async_tls::TlsConnector::new()
+            let stream = connect(&this.address, this.timeouts, TcpStream::connect).await?;
+            let connector = this.tls_config.get().cloned();
+            connector
+                .unwrap_or_else(async_tls::TlsConnector::new)
                 .connect(this.tls_domain, stream)
                 .await
                 .map(async_dup::Mutex::new)
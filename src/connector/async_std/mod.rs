@@ -1,8 +1,12 @@
+use crate::connector::connect::{connect, Timeouts};
 use crate::BoxedFuture;
 
 mod non_tls;
 pub use non_tls::*;
 
+mod proxy;
+pub use proxy::*;
+
 #[cfg(feature = "async-tls")]
 mod tls;
 
@@ -3,7 +3,8 @@ use super::*;
 /// A `async_std` connector. This does not use TLS
 #[derive(Debug, Clone, PartialEq)]
 pub struct Connector {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
 }
 
 impl Connector {
@@ -16,8 +17,15 @@ impl crate::connector::Connector for Connector {
     type Output = async_std::net::TcpStream;
 
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
-        let addrs = self.addrs.clone();
-        let fut = async move { async_std::net::TcpStream::connect(&*addrs).await };
+        let this = self.clone();
+        let fut = async move {
+            connect(
+                &this.address,
+                this.timeouts,
+                async_std::net::TcpStream::connect,
+            )
+            .await
+        };
         Box::pin(fut)
     }
 }
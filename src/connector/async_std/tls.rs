@@ -1,4 +1,5 @@
 use super::*;
+use crate::connector::tls_config::TlsConfig;
 
 /// A `async_std` connector that uses `async-tls` (a `rustls` wrapper). This does use TLS.
 ///
@@ -7,14 +8,24 @@ use super::*;
 /// The crate provides the 'TLS domain' for Twitch in the root of this crate.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectorTls {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
     tls_domain: String,
+    tls_config: TlsConfig<async_tls::TlsConnector>,
 }
 
 impl ConnectorTls {
     connector_ctor!(tls:
         /// [`async-std`](https://docs.rs/async-std/latest/async_std/)
     );
+
+    connector_ctor!(tls_config: async_tls::TlsConnector,
+        /// [`async-std`](https://docs.rs/async-std/latest/async_std/)
+    );
+
+    connector_ctor!(native_roots:
+        /// [`async-std`](https://docs.rs/async-std/latest/async_std/)
+    );
 }
 
 impl crate::connector::Connector for ConnectorTls {
@@ -23,8 +34,15 @@ impl crate::connector::Connector for ConnectorTls {
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
         let this = self.clone();
         let fut = async move {
-            let stream = async_std::net::TcpStream::connect(&*this.addrs).await?;
-            async_tls::TlsConnector::new()
+            let stream = connect(
+                &this.address,
+                this.timeouts,
+                async_std::net::TcpStream::connect,
+            )
+            .await?;
+            let connector = this.tls_config.get().cloned();
+            connector
+                .unwrap_or_else(async_tls::TlsConnector::new)
                 .connect(this.tls_domain, stream)
                 .await
                 .map(async_dup::Mutex::new)
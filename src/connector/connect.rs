@@ -0,0 +1,280 @@
+use futures_lite::future::{self, FutureExt as _};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    io::{Error, ErrorKind, Result},
+    net::{SocketAddr, ToSocketAddrs},
+    pin::Pin,
+    task::Poll,
+    time::Duration,
+};
+
+/// The default timeout for a single connection attempt
+pub const DEFAULT_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default timeout for connecting, including DNS resolution and every attempt
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait on an attempt before racing the next address against it
+const ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Timeouts used by the connectors
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Timeouts {
+    pub(crate) attempt: Option<Duration>,
+    pub(crate) connect: Option<Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            attempt: Some(DEFAULT_ATTEMPT_TIMEOUT),
+            connect: Some(DEFAULT_CONNECT_TIMEOUT),
+        }
+    }
+}
+
+/// Make sure `address` looks like a `host:port` pair, without resolving it
+pub(crate) fn validate(address: &str) -> Result<()> {
+    super::proxy::split_target(address).map(drop)
+}
+
+/// Resolve `address` then connect to it, giving up after the connect timeout
+pub(crate) async fn connect<F, T, R>(address: &str, timeouts: Timeouts, connect: F) -> Result<T>
+where
+    F: Fn(SocketAddr) -> R + Send + Sync,
+    R: Future<Output = Result<T>> + Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    let fut = async {
+        let addrs = resolve(address).await?;
+        try_connect(addrs, timeouts.attempt, connect).await
+    };
+
+    match timeouts.connect {
+        Some(timeout) => fut.or(timed_out(timeout, "connect timed out")).await,
+        None => fut.await,
+    }
+}
+
+/// Resolve `address` on a separate thread, so the executor isn't blocked on DNS
+async fn resolve(address: &str) -> Result<Vec<SocketAddr>> {
+    if let Ok(addr) = address.parse() {
+        return Ok(vec![addr]);
+    }
+
+    let (tx, rx) = crate::channel::bounded(1);
+    let address = address.to_string();
+    std::thread::Builder::new()
+        .name("twitchchat-resolve".into())
+        .spawn(move || {
+            let addrs = address.to_socket_addrs().map(Iterator::collect);
+            let _ = tx.try_send(addrs);
+        })?;
+
+    rx.recv()
+        .await
+        .unwrap_or_else(|| Err(Error::other("the resolver thread quit unexpectedly")))
+}
+
+/// Try to connect to `addrs`, preferring the first one to succeed
+///
+/// Addresses are interleaved by family and a new attempt is started whenever
+/// the previous one fails or hasn't finished within a short delay, so a
+/// broken IPv6 (or IPv4) route doesn't hold up the connection.
+pub(crate) async fn try_connect<F, T, R>(
+    addrs: impl IntoIterator<Item = SocketAddr>,
+    attempt_timeout: Option<Duration>,
+    connect: F,
+) -> Result<T>
+where
+    F: Fn(SocketAddr) -> R + Send + Sync,
+    R: Future<Output = Result<T>> + Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    type Attempt<T> = Pin<Box<dyn Future<Output = Result<T>> + Send + Sync>>;
+
+    let mut pending = interleave(addrs);
+    let mut running: Vec<Attempt<T>> = vec![];
+    let mut delay = futures_timer::Delay::new(ATTEMPT_DELAY);
+    let mut start_now = true;
+    let mut last = None;
+
+    future::poll_fn(|cx| loop {
+        if start_now || running.is_empty() || Pin::new(&mut delay).poll(cx).is_ready() {
+            start_now = false;
+            if let Some(addr) = pending.pop_front() {
+                let fut = connect(addr);
+                running.push(match attempt_timeout {
+                    Some(timeout) => Box::pin(fut.or(timed_out(timeout, "attempt timed out"))),
+                    None => Box::pin(fut),
+                });
+                delay.reset(ATTEMPT_DELAY);
+                continue;
+            }
+        }
+
+        let mut i = 0;
+        while i < running.len() {
+            match running[i].as_mut().poll(cx) {
+                Poll::Ready(Ok(stream)) => return Poll::Ready(Ok(stream)),
+                Poll::Ready(Err(err)) => {
+                    log::debug!("connection attempt failed: {}", err);
+                    drop(running.swap_remove(i));
+                    last.replace(err);
+                    // a failure means the next address should be tried right away
+                    start_now = true;
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+
+        if running.is_empty() && pending.is_empty() {
+            return Poll::Ready(Err(last.take().unwrap_or_else(|| {
+                Error::new(
+                    ErrorKind::ConnectionRefused,
+                    "cannot connect with any provided address",
+                )
+            })));
+        }
+
+        if !start_now || pending.is_empty() {
+            return Poll::Pending;
+        }
+    })
+    .await
+}
+
+/// Alternate between the address families, starting with the first one given
+fn interleave(addrs: impl IntoIterator<Item = SocketAddr>) -> VecDeque<SocketAddr> {
+    let (mut first, mut second): (VecDeque<_>, VecDeque<_>) = (VecDeque::new(), VecDeque::new());
+    let mut family = None;
+    for addr in addrs {
+        match family {
+            None => {
+                family.replace(addr.is_ipv6());
+                first.push_back(addr)
+            }
+            Some(v6) if addr.is_ipv6() == v6 => first.push_back(addr),
+            Some(..) => second.push_back(addr),
+        }
+    }
+
+    let mut out = VecDeque::with_capacity(first.len() + second.len());
+    loop {
+        match (first.pop_front(), second.pop_front()) {
+            (None, None) => break out,
+            (left, right) => out.extend(left.into_iter().chain(right)),
+        }
+    }
+}
+
+async fn timed_out<T>(timeout: Duration, msg: &'static str) -> Result<T> {
+    futures_timer::Delay::new(timeout).await;
+    Err(Error::new(ErrorKind::TimedOut, msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future::block_on;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    async fn refused<T>() -> Result<T> {
+        Err(Error::new(ErrorKind::ConnectionRefused, "refused"))
+    }
+
+    #[test]
+    fn interleave() {
+        let addrs = vec![
+            addr("[::1]:1"),
+            addr("[::1]:2"),
+            addr("[::1]:3"),
+            addr("127.0.0.1:1"),
+        ];
+        let expected = vec![
+            addr("[::1]:1"),
+            addr("127.0.0.1:1"),
+            addr("[::1]:2"),
+            addr("[::1]:3"),
+        ];
+        assert_eq!(super::interleave(addrs), expected);
+    }
+
+    #[test]
+    fn validate() {
+        assert!(super::validate(crate::TWITCH_IRC_ADDRESS).is_ok());
+        assert!(super::validate("127.0.0.1:6667").is_ok());
+        assert!(super::validate("irc.chat.twitch.tv").is_err());
+    }
+
+    #[test]
+    fn resolve_literal() {
+        let addrs = block_on(resolve("127.0.0.1:6667")).unwrap();
+        assert_eq!(addrs, vec![addr("127.0.0.1:6667")]);
+    }
+
+    #[test]
+    fn first_success() {
+        let addrs = vec![addr("[::1]:1"), addr("127.0.0.1:2"), addr("127.0.0.1:3")];
+        let port = block_on(try_connect(addrs, None, |addr| async move {
+            match addr.port() {
+                2 => Ok(addr.port()),
+                _ => refused().await,
+            }
+        }))
+        .unwrap();
+        assert_eq!(port, 2);
+    }
+
+    #[test]
+    fn races_slow_attempts() {
+        // the first address never answers, the second one does
+        let addrs = vec![addr("[::1]:1"), addr("127.0.0.1:2")];
+        let port = block_on(try_connect(addrs, None, |addr| async move {
+            if addr.is_ipv6() {
+                future::pending::<()>().await;
+            }
+            Ok(addr.port())
+        }))
+        .unwrap();
+        assert_eq!(port, 2);
+    }
+
+    #[test]
+    fn attempt_timeout() {
+        let addrs = vec![addr("127.0.0.1:1")];
+        let timeout = Some(Duration::from_millis(10));
+        let err = block_on(try_connect(addrs, timeout, |_| {
+            future::pending::<Result<()>>()
+        }))
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn connect_timeout() {
+        let timeouts = Timeouts {
+            attempt: None,
+            connect: Some(Duration::from_millis(10)),
+        };
+        let err = block_on(connect("127.0.0.1:1", timeouts, |_| {
+            future::pending::<Result<()>>()
+        }))
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn all_failed() {
+        let addrs = vec![addr("[::1]:1"), addr("127.0.0.1:1")];
+        let err = block_on(try_connect(addrs, None, |_| refused::<()>())).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConnectionRefused);
+
+        let err = block_on(try_connect(vec![], None, |_| refused::<()>())).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConnectionRefused);
+    }
+}
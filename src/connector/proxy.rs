@@ -0,0 +1,526 @@
+use futures_lite::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use std::{
+    convert::TryFrom,
+    io::{Error, ErrorKind, Result},
+    net::IpAddr,
+};
+
+/// The kind of proxy to tunnel through
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ProxyKind {
+    /// An HTTP proxy that supports the `CONNECT` method
+    Http,
+    /// A SOCKS5 proxy
+    Socks5,
+}
+
+/**
+A proxy that the proxy connectors tunnel through
+
+The address of the proxy is resolved each time a connector connects, like the address of the other connectors.
+
+# example
+```
+# use twitchchat::connector::{Proxy, ProxyKind};
+let proxy = Proxy::socks5("127.0.0.1:1080")
+    .unwrap()
+    .with_credentials("user", "hunter2");
+assert_eq!(proxy.kind(), ProxyKind::Socks5);
+```
+*/
+#[derive(Clone, PartialEq)]
+pub struct Proxy {
+    kind: ProxyKind,
+    address: String,
+    credentials: Option<(String, String)>,
+}
+
+impl std::fmt::Debug for Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Proxy")
+            .field("kind", &self.kind)
+            .field("address", &self.address)
+            .field("has_credentials", &self.credentials.is_some())
+            .finish()
+    }
+}
+
+impl Proxy {
+    /// Create an HTTP `CONNECT` proxy at this `host:port` address
+    pub fn http<A>(address: A) -> Result<Self>
+    where
+        A: Into<String>,
+    {
+        Self::new(ProxyKind::Http, address)
+    }
+
+    /// Create a SOCKS5 proxy at this `host:port` address
+    pub fn socks5<A>(address: A) -> Result<Self>
+    where
+        A: Into<String>,
+    {
+        Self::new(ProxyKind::Socks5, address)
+    }
+
+    /// Create a proxy of this kind at this `host:port` address
+    ///
+    /// This returns an error if the address isn't a `host:port` pair.
+    pub fn new<A>(kind: ProxyKind, address: A) -> Result<Self>
+    where
+        A: Into<String>,
+    {
+        let address = address.into();
+        super::connect::validate(&address)?;
+        Ok(Self {
+            kind,
+            address,
+            credentials: None,
+        })
+    }
+
+    /// Authenticate with the proxy using this username and password
+    pub fn with_credentials(
+        self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            credentials: Some((username.into(), password.into())),
+            ..self
+        }
+    }
+
+    /// The kind of this proxy
+    pub fn kind(&self) -> ProxyKind {
+        self.kind
+    }
+
+    /// The `host:port` address of this proxy
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+/// An error returned by the proxy during the handshake
+///
+/// This is wrapped in the `std::io::Error` returned by the proxy connectors.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProxyError {
+    /// The proxy requires a username and password, and none were provided
+    AuthenticationRequired,
+    /// The proxy rejected the username and password
+    AuthenticationFailed,
+    /// The proxy refused to connect to the target
+    Refused {
+        /// Why the proxy refused
+        reason: String,
+    },
+    /// The proxy sent something we didn't expect
+    InvalidResponse,
+}
+
+impl std::fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AuthenticationRequired => f.write_str("the proxy requires authentication"),
+            Self::AuthenticationFailed => f.write_str("the proxy rejected the credentials"),
+            Self::Refused { reason } => write!(f, "the proxy refused to connect: {}", reason),
+            Self::InvalidResponse => f.write_str("the proxy sent an invalid response"),
+        }
+    }
+}
+
+impl std::error::Error for ProxyError {}
+
+impl From<ProxyError> for Error {
+    fn from(err: ProxyError) -> Self {
+        let kind = match err {
+            ProxyError::AuthenticationRequired | ProxyError::AuthenticationFailed => {
+                ErrorKind::PermissionDenied
+            }
+            ProxyError::Refused { .. } => ErrorKind::ConnectionRefused,
+            ProxyError::InvalidResponse => ErrorKind::InvalidData,
+        };
+        Self::new(kind, err)
+    }
+}
+
+/// Ask the `proxy` on the other end of `stream` to connect to `target` (a `host:port` pair)
+pub(crate) async fn tunnel<S>(stream: &mut S, proxy: &Proxy, target: &str) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    let (host, port) = split_target(target)?;
+    match proxy.kind {
+        ProxyKind::Http => http_connect(stream, proxy, host, port).await,
+        ProxyKind::Socks5 => socks5_connect(stream, proxy, host, port).await,
+    }
+}
+
+pub(super) fn split_target(target: &str) -> Result<(&str, u16)> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("'{}' is not a valid host:port pair", target),
+        )
+    };
+
+    let pos = target.rfind(':').ok_or_else(invalid)?;
+    let (host, port) = (&target[..pos], &target[pos + 1..]);
+    let port = port.parse().map_err(|_| invalid())?;
+
+    // allow bracketed ipv6 addresses
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    Ok((host, port))
+}
+
+async fn http_connect<S>(stream: &mut S, proxy: &Proxy, host: &str, port: u16) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    // responses larger than this are probably not from a proxy
+    const MAX_RESPONSE: usize = 8 * 1024;
+
+    let authority = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(..)) => format!("[{}]:{}", host, port),
+        _ => format!("{}:{}", host, port),
+    };
+
+    let mut request = format!(
+        "CONNECT {0} HTTP/1.1\r\nHost: {0}\r\nProxy-Connection: keep-alive\r\n",
+        authority
+    );
+    if let Some((username, password)) = &proxy.credentials {
+        let credentials = format!("{}:{}", username, password);
+        request.push_str("Proxy-Authorization: Basic ");
+        request.push_str(&base64(credentials.as_bytes()));
+        request.push_str("\r\n");
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+
+    // read a byte at a time so we don't consume anything after the headers
+    let mut response = Vec::new();
+    let mut byte = [0; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > MAX_RESPONSE {
+            return Err(ProxyError::InvalidResponse.into());
+        }
+        stream.read_exact(&mut byte).await?;
+        response.push(byte[0]);
+    }
+
+    let response = std::str::from_utf8(&response).map_err(|_| ProxyError::InvalidResponse)?;
+    let status = response.lines().next().unwrap_or_default();
+
+    let mut parts = status.splitn(3, ' ');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(version), Some(code), reason) if version.starts_with("HTTP/1.") => match code {
+            "200" => Ok(()),
+            "407" if proxy.credentials.is_some() => Err(ProxyError::AuthenticationFailed.into()),
+            "407" => Err(ProxyError::AuthenticationRequired.into()),
+            _ => Err(ProxyError::Refused {
+                reason: format!("{} {}", code, reason.unwrap_or_default()),
+            }
+            .into()),
+        },
+        _ => Err(ProxyError::InvalidResponse.into()),
+    }
+}
+
+async fn socks5_connect<S>(stream: &mut S, proxy: &Proxy, host: &str, port: u16) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    const VERSION: u8 = 0x05;
+    const NO_AUTH: u8 = 0x00;
+    const USER_PASS: u8 = 0x02;
+    const NO_ACCEPTABLE: u8 = 0xFF;
+
+    // greeting
+    let greeting: &[u8] = match proxy.credentials {
+        Some(..) => &[VERSION, 2, NO_AUTH, USER_PASS],
+        None => &[VERSION, 1, NO_AUTH],
+    };
+    stream.write_all(greeting).await?;
+
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).await?;
+    match reply {
+        [VERSION, NO_AUTH] => {}
+        [VERSION, USER_PASS] => {
+            let (username, password) = proxy
+                .credentials
+                .as_ref()
+                .ok_or(ProxyError::InvalidResponse)?;
+            socks5_authenticate(stream, username, password).await?;
+        }
+        [VERSION, NO_ACCEPTABLE] if proxy.credentials.is_none() => {
+            return Err(ProxyError::AuthenticationRequired.into())
+        }
+        [VERSION, NO_ACCEPTABLE] => {
+            return Err(ProxyError::Refused {
+                reason: "no acceptable authentication methods".into(),
+            }
+            .into())
+        }
+        _ => return Err(ProxyError::InvalidResponse.into()),
+    }
+
+    // connect request
+    let mut request = vec![VERSION, 0x01, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(0x01);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(0x04);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(..) => {
+            let len = u8::try_from(host.len())
+                .map_err(|_| Error::new(ErrorKind::InvalidInput, "host name is too long"))?;
+            request.push(0x03);
+            request.push(len);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+    stream.flush().await?;
+
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != VERSION {
+        return Err(ProxyError::InvalidResponse.into());
+    }
+    if reply[1] != 0x00 {
+        return Err(ProxyError::Refused {
+            reason: socks5_reply(reply[1]).to_string(),
+        }
+        .into());
+    }
+
+    // skip the bound address
+    let len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => {
+            let mut len = [0; 1];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        _ => return Err(ProxyError::InvalidResponse.into()),
+    };
+    let mut bound = vec![0; len + 2];
+    stream.read_exact(&mut bound).await?;
+
+    Ok(())
+}
+
+async fn socks5_authenticate<S>(stream: &mut S, username: &str, password: &str) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    let too_long = |_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            "SOCKS5 usernames and passwords are limited to 255 bytes",
+        )
+    };
+
+    let mut request = vec![0x01];
+    request.push(u8::try_from(username.len()).map_err(too_long)?);
+    request.extend_from_slice(username.as_bytes());
+    request.push(u8::try_from(password.len()).map_err(too_long)?);
+    request.extend_from_slice(password.as_bytes());
+    stream.write_all(&request).await?;
+    stream.flush().await?;
+
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).await?;
+    match reply {
+        [0x01, 0x00] => Ok(()),
+        [0x01, ..] => Err(ProxyError::AuthenticationFailed.into()),
+        _ => Err(ProxyError::InvalidResponse.into()),
+    }
+}
+
+fn socks5_reply(code: u8) -> &'static str {
+    match code {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+pub(super) fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connector::testing::{spawn_proxy, spawn_target, READY};
+    use futures_lite::{future::block_on, io::AssertAsync, AsyncBufReadExt as _};
+
+    fn connect(proxy: &Proxy, target: &str) -> Result<String> {
+        block_on(async {
+            let stream = std::net::TcpStream::connect(proxy.address())?;
+            let mut stream = AssertAsync::new(stream);
+            tunnel(&mut stream, proxy, target).await?;
+
+            let mut line = String::new();
+            futures_lite::io::BufReader::new(stream)
+                .read_line(&mut line)
+                .await?;
+            Ok(line)
+        })
+    }
+
+    fn proxy_error(err: Error) -> ProxyError {
+        err.into_inner()
+            .and_then(|err| err.downcast::<ProxyError>().ok())
+            .map(|err| *err)
+            .expect("proxy error")
+    }
+
+    #[test]
+    fn base64() {
+        let inputs = &[
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("user:hunter2", "dXNlcjpodW50ZXIy"),
+        ];
+        for (input, expected) in inputs {
+            assert_eq!(super::base64(input.as_bytes()), *expected);
+        }
+    }
+
+    #[test]
+    fn split_target() {
+        assert_eq!(
+            super::split_target("irc.chat.twitch.tv:6667").unwrap(),
+            ("irc.chat.twitch.tv", 6667)
+        );
+        assert_eq!(super::split_target("[::1]:6667").unwrap(), ("::1", 6667));
+        assert!(super::split_target("irc.chat.twitch.tv").is_err());
+        assert!(super::split_target(":6667").is_err());
+    }
+
+    #[test]
+    fn not_resolved() {
+        // this would fail if it was resolved when the proxy is created
+        let proxy = Proxy::http("proxy.invalid:8080").unwrap();
+        assert_eq!(proxy.address(), "proxy.invalid:8080");
+
+        let err = Proxy::socks5("proxy.invalid").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn http() {
+        let target = spawn_target();
+        let proxy = Proxy::http(spawn_proxy(ProxyKind::Http, None)).unwrap();
+        let target = format!("localhost:{}", target.port());
+        assert_eq!(connect(&proxy, &target).unwrap(), READY);
+    }
+
+    #[test]
+    fn http_credentials() {
+        let credentials = Some(("user", "hunter2"));
+        let target = format!("127.0.0.1:{}", spawn_target().port());
+
+        let proxy = Proxy::http(spawn_proxy(ProxyKind::Http, credentials)).unwrap();
+        let err = connect(&proxy, &target).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(proxy_error(err), ProxyError::AuthenticationRequired);
+
+        let proxy = Proxy::http(spawn_proxy(ProxyKind::Http, credentials))
+            .unwrap()
+            .with_credentials("user", "wrong");
+        let err = connect(&proxy, &target).unwrap_err();
+        assert_eq!(proxy_error(err), ProxyError::AuthenticationFailed);
+
+        let proxy = Proxy::http(spawn_proxy(ProxyKind::Http, credentials))
+            .unwrap()
+            .with_credentials("user", "hunter2");
+        assert_eq!(connect(&proxy, &target).unwrap(), READY);
+    }
+
+    #[test]
+    fn socks5() {
+        let target = spawn_target();
+        let proxy = Proxy::socks5(spawn_proxy(ProxyKind::Socks5, None)).unwrap();
+        let target = format!("localhost:{}", target.port());
+        assert_eq!(connect(&proxy, &target).unwrap(), READY);
+    }
+
+    #[test]
+    fn socks5_credentials() {
+        let credentials = Some(("user", "hunter2"));
+        let target = format!("127.0.0.1:{}", spawn_target().port());
+
+        let proxy = Proxy::socks5(spawn_proxy(ProxyKind::Socks5, credentials)).unwrap();
+        let err = connect(&proxy, &target).unwrap_err();
+        assert_eq!(proxy_error(err), ProxyError::AuthenticationRequired);
+
+        let proxy = Proxy::socks5(spawn_proxy(ProxyKind::Socks5, credentials))
+            .unwrap()
+            .with_credentials("user", "wrong");
+        let err = connect(&proxy, &target).unwrap_err();
+        assert_eq!(proxy_error(err), ProxyError::AuthenticationFailed);
+
+        let proxy = Proxy::socks5(spawn_proxy(ProxyKind::Socks5, credentials))
+            .unwrap()
+            .with_credentials("user", "hunter2");
+        assert_eq!(connect(&proxy, &target).unwrap(), READY);
+    }
+
+    #[test]
+    fn socks5_refused() {
+        // nothing is listening on this port
+        let port = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+
+        let proxy = Proxy::socks5(spawn_proxy(ProxyKind::Socks5, None)).unwrap();
+        let err = connect(&proxy, &format!("127.0.0.1:{}", port)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConnectionRefused);
+        assert!(matches!(proxy_error(err), ProxyError::Refused { .. }));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::connector::try_connect;
+use crate::connector::connect::{connect, Timeouts};
 use crate::BoxedFuture;
 
 type TcpStream = smol::Async<std::net::TcpStream>;
@@ -6,6 +6,9 @@ type TcpStream = smol::Async<std::net::TcpStream>;
 mod non_tls;
 pub use non_tls::*;
 
+mod proxy;
+pub use proxy::*;
+
 #[cfg(feature = "async-tls")]
 mod tls;
 
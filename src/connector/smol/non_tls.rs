@@ -3,7 +3,8 @@ use super::*;
 /// A `smol` connector. This does not use TLS
 #[derive(Debug, Clone, PartialEq)]
 pub struct Connector {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
 }
 
 impl Connector {
@@ -16,8 +17,8 @@ impl crate::connector::Connector for Connector {
     type Output = TcpStream;
 
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
-        let addrs = self.addrs.clone();
-        let fut = async move { try_connect(&*addrs, TcpStream::connect).await };
+        let this = self.clone();
+        let fut = async move { connect(&this.address, this.timeouts, TcpStream::connect).await };
         Box::pin(fut)
     }
 }
@@ -35,4 +36,30 @@ mod tests {
         assert_type_is_read_write::<<Connector as C>::Output>();
         assert_obj_is_sane(Connector::twitch());
     }
+
+    #[test]
+    fn connect() {
+        use crate::connector::{testing::*, Connector as _};
+        use futures_lite::{future::block_on, AsyncBufReadExt as _};
+
+        let address = format!("localhost:{}", spawn_target().port());
+        let mut connector = Connector::custom(address).unwrap();
+
+        let line = block_on(async {
+            let stream = connector.connect().await.unwrap();
+            let mut line = String::new();
+            futures_lite::io::BufReader::new(stream)
+                .read_line(&mut line)
+                .await
+                .unwrap();
+            line
+        });
+        assert_eq!(line, READY);
+    }
+
+    #[test]
+    fn invalid_address() {
+        let err = Connector::custom("localhost").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
 }
@@ -1,4 +1,5 @@
 use super::*;
+use crate::connector::tls_config::TlsConfig;
 
 /// A `smol` connector that uses `async-tls` (a `rustls` wrapper). This does use TLS.
 ///
@@ -6,14 +7,24 @@ use super::*;
 /// configuration. The crate provides the 'TLS domain' for Twitch in the root of this crate.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectorTls {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
     tls_domain: String,
+    tls_config: TlsConfig<async_tls::TlsConnector>,
 }
 
 impl ConnectorTls {
     connector_ctor!(tls:
         /// [`smol`](https://docs.rs/smol/latest/smol/)
     );
+
+    connector_ctor!(tls_config: async_tls::TlsConnector,
+        /// [`smol`](https://docs.rs/smol/latest/smol/)
+    );
+
+    connector_ctor!(native_roots:
+        /// [`smol`](https://docs.rs/smol/latest/smol/)
+    );
 }
 
 impl crate::connector::Connector for ConnectorTls {
@@ -22,8 +33,10 @@ impl crate::connector::Connector for ConnectorTls {
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
         let this = self.clone();
         let fut = async move {
-            let stream = try_connect(&*this.addrs, TcpStream::connect).await?;
-            async_tls::TlsConnector::new()
+            let stream = connect(&this.address, this.timeouts, TcpStream::connect).await?;
+            let connector = this.tls_config.get().cloned();
+            connector
+                .unwrap_or_else(async_tls::TlsConnector::new)
                 .connect(this.tls_domain, stream)
                 .await
                 .map(async_dup::Mutex::new)
@@ -45,4 +58,36 @@ mod tests {
         assert_type_is_read_write::<<ConnectorTls as C>::Output>();
         assert_obj_is_sane(ConnectorTls::twitch());
     }
+
+    #[test]
+    #[cfg(feature = "rustls")]
+    fn custom_config() {
+        use crate::connector::{testing::*, Connector as _};
+        use futures_lite::{future::block_on, AsyncBufReadExt as _};
+
+        let address = format!("127.0.0.1:{}", spawn_tls_target().port());
+        let mut connector =
+            ConnectorTls::with_config(address, "localhost", test_client_config()).unwrap();
+
+        let line = block_on(async {
+            let stream = connector.connect().await.unwrap();
+            let mut line = String::new();
+            futures_lite::io::BufReader::new(stream)
+                .read_line(&mut line)
+                .await
+                .unwrap();
+            line
+        });
+        assert_eq!(line, READY);
+    }
+
+    #[test]
+    #[cfg(feature = "rustls")]
+    fn untrusted_certificate() {
+        use crate::connector::{testing::*, Connector as _};
+
+        let address = format!("127.0.0.1:{}", spawn_tls_target().port());
+        let mut connector = ConnectorTls::custom(address, "localhost").unwrap();
+        assert!(futures_lite::future::block_on(connector.connect()).is_err());
+    }
 }
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+/// A TLS configuration given to a connector, or `Default` to let the connector build its own
+///
+/// Clones are compared by identity, so connectors made from the same
+/// configuration compare as equal.
+pub(crate) struct TlsConfig<T>(Option<Arc<T>>);
+
+impl<T> TlsConfig<T> {
+    pub(crate) fn custom(config: T) -> Self {
+        Self(Some(Arc::new(config)))
+    }
+
+    pub(crate) fn get(&self) -> Option<&T> {
+        self.0.as_deref()
+    }
+}
+
+impl<T> Default for TlsConfig<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> Clone for TlsConfig<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> PartialEq for TlsConfig<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(left), Some(right)) => Arc::ptr_eq(left, right),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for TlsConfig<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(..) => f.write_str("Custom"),
+            None => f.write_str("Default"),
+        }
+    }
+}
+
+/// Make a rustls configuration that trusts the system's root certificates
+#[cfg(all(feature = "rustls-native-certs", feature = "rustls"))]
+pub(crate) fn native_roots() -> std::io::Result<rustls::ClientConfig> {
+    let mut config = rustls::ClientConfig::new();
+    config.root_store = rustls_native_certs::load_native_certs().map_err(|(_, err)| err)?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compared_by_identity() {
+        let config = TlsConfig::custom(String::from("config"));
+        assert_eq!(config, config.clone());
+        assert_ne!(config, TlsConfig::custom(String::from("config")));
+        assert_ne!(config, TlsConfig::default());
+        assert_eq!(TlsConfig::<String>::default(), TlsConfig::default());
+
+        assert_eq!(format!("{:?}", config), "Custom");
+        assert_eq!(config.get().map(|s| &**s), Some("config"));
+    }
+}
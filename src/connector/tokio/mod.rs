@@ -1,8 +1,12 @@
+use crate::connector::connect::{connect, Timeouts};
 use crate::BoxedFuture;
 
 mod non_tls;
 pub use non_tls::*;
 
+mod proxy;
+pub use proxy::*;
+
 #[cfg(all(feature = "tokio-native-tls", feature = "native-tls"))]
 mod native_tls;
 
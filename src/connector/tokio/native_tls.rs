@@ -1,4 +1,5 @@
 use super::*;
+use crate::connector::tls_config::TlsConfig;
 
 /// A `tokio` connector that uses `tokio-native-tls` (a `native-tls` wrapper). This does use TLS.
 ///
@@ -7,18 +8,24 @@ use super::*;
 /// The crate provides the 'TLS domain' for Twitch in the root of this crate.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectorNativeTls {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
     tls_domain: String,
+    tls_config: TlsConfig<::native_tls::TlsConnector>,
 }
 
 impl ConnectorNativeTls {
     connector_ctor!(tls:
         /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-native-tls`](https://docs.rs/tokio-native-tls/latest/tokio_native_tls/))
     );
+
+    connector_ctor!(tls_config: ::native_tls::TlsConnector,
+        /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-native-tls`](https://docs.rs/tokio-native-tls/latest/tokio_native_tls/))
+    );
 }
 
 type CloneStream<T> = async_dup::Mutex<tokio_util::compat::Compat<T>>;
-type Stream = tokio_native_tls::TlsStream<tokio::net::TcpStream>;
+pub(super) type Stream = tokio_native_tls::TlsStream<tokio::net::TcpStream>;
 
 impl crate::connector::Connector for ConnectorNativeTls {
     type Output = CloneStream<Stream>;
@@ -29,15 +36,9 @@ impl crate::connector::Connector for ConnectorNativeTls {
         let fut = async move {
             use tokio_util::compat::Tokio02AsyncReadCompatExt as _;
 
-            let connector: tokio_native_tls::TlsConnector = ::native_tls::TlsConnector::new()
-                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
-                .into();
-
-            let stream = tokio::net::TcpStream::connect(&*this.addrs).await?;
-            let stream = connector
-                .connect(&this.tls_domain, stream)
-                .await
-                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+            let stream =
+                connect(&this.address, this.timeouts, tokio::net::TcpStream::connect).await?;
+            let stream = connect_tls(&this.tls_config, &this.tls_domain, stream).await?;
 
             Ok(async_dup::Mutex::new(stream.compat()))
         };
@@ -45,6 +46,24 @@ impl crate::connector::Connector for ConnectorNativeTls {
     }
 }
 
+pub(super) async fn connect_tls(
+    tls_config: &TlsConfig<::native_tls::TlsConnector>,
+    tls_domain: &str,
+    stream: tokio::net::TcpStream,
+) -> std::io::Result<Stream> {
+    let connector: tokio_native_tls::TlsConnector = match tls_config.get() {
+        Some(connector) => connector.clone(),
+        None => ::native_tls::TlsConnector::new()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?,
+    }
+    .into();
+
+    connector
+        .connect(tls_domain, stream)
+        .await
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,7 +3,8 @@ use super::*;
 /// A `tokio` connector. This does not use TLS
 #[derive(Debug, Clone, PartialEq)]
 pub struct Connector {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
 }
 
 impl Connector {
@@ -16,10 +17,11 @@ impl crate::connector::Connector for Connector {
     type Output = async_dup::Mutex<tokio_util::compat::Compat<tokio::net::TcpStream>>;
 
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
-        let addrs = self.addrs.clone();
+        let this = self.clone();
         let fut = async move {
             use tokio_util::compat::Tokio02AsyncReadCompatExt as _;
-            let stream = tokio::net::TcpStream::connect(&*addrs).await?;
+            let stream =
+                connect(&this.address, this.timeouts, tokio::net::TcpStream::connect).await?;
             Ok(async_dup::Mutex::new(stream.compat()))
         };
         Box::pin(fut)
@@ -0,0 +1,149 @@
+use super::*;
+use crate::connector::{proxy::tunnel, Proxy};
+
+#[cfg(all(feature = "tokio-native-tls", feature = "native-tls"))]
+use super::native_tls::Stream as NativeTlsStream;
+
+#[cfg(any(
+    all(feature = "tokio-rustls", feature = "webpki-roots"),
+    all(feature = "tokio-native-tls", feature = "native-tls")
+))]
+use crate::connector::tls_config::TlsConfig;
+
+/// Connect to the `proxy` and ask it to tunnel to `target`
+async fn connect_proxy(
+    proxy: &Proxy,
+    target: &str,
+    timeouts: Timeouts,
+) -> std::io::Result<tokio::net::TcpStream> {
+    use tokio_util::compat::Tokio02AsyncReadCompatExt as _;
+
+    let stream = connect(proxy.address(), timeouts, tokio::net::TcpStream::connect).await?;
+    let mut stream = stream.compat();
+    tunnel(&mut stream, proxy, target).await?;
+    Ok(stream.into_inner())
+}
+
+/// A `tokio` connector that tunnels through a [`Proxy`](../struct.Proxy.html). This does not use TLS
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyConnector {
+    proxy: Proxy,
+    target: String,
+    timeouts: Timeouts,
+}
+
+impl ProxyConnector {
+    proxy_connector_ctor!(non_tls:
+        /// [`tokio`](https://docs.rs/tokio/latest/tokio/)
+    );
+}
+
+impl crate::connector::Connector for ProxyConnector {
+    type Output = async_dup::Mutex<tokio_util::compat::Compat<tokio::net::TcpStream>>;
+
+    fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+        let this = self.clone();
+        let fut = async move {
+            use tokio_util::compat::Tokio02AsyncReadCompatExt as _;
+            let stream = connect_proxy(&this.proxy, &this.target, this.timeouts).await?;
+            Ok(async_dup::Mutex::new(stream.compat()))
+        };
+        Box::pin(fut)
+    }
+}
+
+/// A `tokio` connector that tunnels through a [`Proxy`](../struct.Proxy.html) and then uses `tokio-rustls` (a `rustls` wrapper). This does use TLS.
+#[cfg(all(feature = "tokio-rustls", feature = "webpki-roots"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyConnectorRustTls {
+    proxy: Proxy,
+    target: String,
+    timeouts: Timeouts,
+    tls_domain: String,
+    tls_config: TlsConfig<tokio_rustls::TlsConnector>,
+}
+
+#[cfg(all(feature = "tokio-rustls", feature = "webpki-roots"))]
+impl ProxyConnectorRustTls {
+    proxy_connector_ctor!(tls:
+        /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-rustls`](https://docs.rs/tokio-rustls/latest/tokio_rustls/))
+    );
+
+    proxy_connector_ctor!(tls_config: tokio_rustls::TlsConnector,
+        /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-rustls`](https://docs.rs/tokio-rustls/latest/tokio_rustls/))
+    );
+}
+
+#[cfg(all(feature = "tokio-rustls", feature = "webpki-roots"))]
+impl crate::connector::Connector for ProxyConnectorRustTls {
+    type Output = async_dup::Mutex<
+        tokio_util::compat::Compat<tokio_rustls::client::TlsStream<tokio::net::TcpStream>>,
+    >;
+
+    fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+        let this = self.clone();
+        let fut = async move {
+            use super::rustls::connect_tls;
+            use tokio_util::compat::Tokio02AsyncReadCompatExt as _;
+            let stream = connect_proxy(&this.proxy, &this.target, this.timeouts).await?;
+            let stream = connect_tls(&this.tls_config, &this.tls_domain, stream).await?;
+            Ok(async_dup::Mutex::new(stream.compat()))
+        };
+        Box::pin(fut)
+    }
+}
+
+/// A `tokio` connector that tunnels through a [`Proxy`](../struct.Proxy.html) and then uses `tokio-native-tls` (a `native-tls` wrapper). This does use TLS.
+#[cfg(all(feature = "tokio-native-tls", feature = "native-tls"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyConnectorNativeTls {
+    proxy: Proxy,
+    target: String,
+    timeouts: Timeouts,
+    tls_domain: String,
+    tls_config: TlsConfig<::native_tls::TlsConnector>,
+}
+
+#[cfg(all(feature = "tokio-native-tls", feature = "native-tls"))]
+impl ProxyConnectorNativeTls {
+    proxy_connector_ctor!(tls:
+        /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-native-tls`](https://docs.rs/tokio-native-tls/latest/tokio_native_tls/))
+    );
+
+    proxy_connector_ctor!(tls_config: ::native_tls::TlsConnector,
+        /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-native-tls`](https://docs.rs/tokio-native-tls/latest/tokio_native_tls/))
+    );
+}
+
+#[cfg(all(feature = "tokio-native-tls", feature = "native-tls"))]
+impl crate::connector::Connector for ProxyConnectorNativeTls {
+    type Output = async_dup::Mutex<tokio_util::compat::Compat<NativeTlsStream>>;
+
+    fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+        let this = self.clone();
+        let fut = async move {
+            use super::native_tls::connect_tls;
+            use tokio_util::compat::Tokio02AsyncReadCompatExt as _;
+            let stream = connect_proxy(&this.proxy, &this.target, this.timeouts).await?;
+            let stream = connect_tls(&this.tls_config, &this.tls_domain, stream).await?;
+            Ok(async_dup::Mutex::new(stream.compat()))
+        };
+        Box::pin(fut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assert_connector_trait_is_fulfilled() {
+        use crate::connector::testing::*;
+        use crate::connector::Connector as C;
+
+        let proxy = Proxy::http("127.0.0.1:8080").unwrap();
+        assert_connector::<ProxyConnector>();
+        assert_type_is_read_write::<<ProxyConnector as C>::Output>();
+        assert_obj_is_sane(ProxyConnector::twitch(proxy));
+    }
+}
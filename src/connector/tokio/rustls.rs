@@ -1,4 +1,5 @@
 use super::*;
+use crate::connector::tls_config::TlsConfig;
 
 /// A `tokio` connector that uses `tokio-rustls` (a `rustls` wrapper). This does use TLS.
 ///
@@ -7,14 +8,24 @@ use super::*;
 /// The crate provides the 'TLS domain' for Twitch in the root of this crate.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectorRustTls {
-    addrs: Vec<std::net::SocketAddr>,
+    address: String,
+    timeouts: Timeouts,
     tls_domain: String,
+    tls_config: TlsConfig<tokio_rustls::TlsConnector>,
 }
 
 impl ConnectorRustTls {
     connector_ctor!(tls:
         /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-rustls`](https://docs.rs/tokio-rustls/latest/tokio_rustls/))
     );
+
+    connector_ctor!(tls_config: tokio_rustls::TlsConnector,
+        /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-rustls`](https://docs.rs/tokio-rustls/latest/tokio_rustls/))
+    );
+
+    connector_ctor!(native_roots:
+        /// [`tokio`](https://docs.rs/tokio/latest/tokio/) (using [`tokio-rustls`](https://docs.rs/tokio-rustls/latest/tokio_rustls/))
+    );
 }
 
 impl crate::connector::Connector for ConnectorRustTls {
@@ -26,25 +37,36 @@ impl crate::connector::Connector for ConnectorRustTls {
         let this = self.clone();
         let fut = async move {
             use tokio_util::compat::Tokio02AsyncReadCompatExt as _;
-            let domain = tokio_rustls::webpki::DNSNameRef::try_from_ascii_str(&this.tls_domain)
-                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
-
-            let connector: tokio_rustls::TlsConnector = std::sync::Arc::new({
-                let mut c = tokio_rustls::rustls::ClientConfig::new();
-                c.root_store
-                    .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
-                c
-            })
-            .into();
-
-            let stream = tokio::net::TcpStream::connect(&*this.addrs).await?;
-            let stream = connector.connect(domain, stream).await?;
+            let stream =
+                connect(&this.address, this.timeouts, tokio::net::TcpStream::connect).await?;
+            let stream = connect_tls(&this.tls_config, &this.tls_domain, stream).await?;
             Ok(async_dup::Mutex::new(stream.compat()))
         };
         Box::pin(fut)
     }
 }
 
+pub(super) async fn connect_tls(
+    tls_config: &TlsConfig<tokio_rustls::TlsConnector>,
+    tls_domain: &str,
+    stream: tokio::net::TcpStream,
+) -> std::io::Result<tokio_rustls::client::TlsStream<tokio::net::TcpStream>> {
+    let domain = tokio_rustls::webpki::DNSNameRef::try_from_ascii_str(tls_domain)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+
+    let connector = tls_config.get().cloned().unwrap_or_else(|| {
+        std::sync::Arc::new({
+            let mut c = tokio_rustls::rustls::ClientConfig::new();
+            c.root_store
+                .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+            c
+        })
+        .into()
+    });
+
+    connector.connect(domain, stream).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,6 +54,22 @@ pub trait Encodable {
     fn encode<W>(&self, buf: &mut W) -> IoResult<()>
     where
         W: Write + ?Sized;
+
+    /// The priority of this message, when sent through the runner's queues
+    ///
+    /// This is [Priority::Normal] unless overridden
+    ///
+    /// [Priority::Normal]: ../writer/enum.Priority.html#variant.Normal
+    fn priority(&self) -> crate::writer::Priority {
+        crate::writer::Priority::Normal
+    }
+
+    /// How long this message can wait in the runner's queues before it is dropped
+    ///
+    /// This is `None` (no limit) unless overridden
+    fn time_to_live(&self) -> Option<std::time::Duration> {
+        None
+    }
 }
 
 impl<T> Encodable for &T
@@ -66,6 +82,14 @@ where
     {
         <_ as Encodable>::encode(*self, buf)
     }
+
+    fn priority(&self) -> crate::writer::Priority {
+        <_ as Encodable>::priority(*self)
+    }
+
+    fn time_to_live(&self) -> Option<std::time::Duration> {
+        <_ as Encodable>::time_to_live(*self)
+    }
 }
 
 impl Encodable for str {
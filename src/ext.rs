@@ -1,4 +1,7 @@
-use crate::{messages::Privmsg, Encodable};
+use crate::{
+    messages::{Privmsg, Whisper},
+    Encodable,
+};
 use std::io::Write;
 
 /// Extensions to the `Privmsg` message type
@@ -12,8 +15,9 @@ pub trait PrivmsgExt {
 
 impl<'a, W: Write + ?Sized> PrivmsgExt for W {
     fn reply(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()> {
+        let channel = msg.channel();
         let cmd = crate::commands::reply(
-            msg.channel(),
+            &channel,
             msg.tags().get("id").ok_or_else(|| {
                 std::io::Error::new(
                     std::io::ErrorKind::PermissionDenied,
@@ -27,7 +31,23 @@ impl<'a, W: Write + ?Sized> PrivmsgExt for W {
     }
 
     fn say(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()> {
-        let cmd = crate::commands::privmsg(msg.channel(), data);
+        let channel = msg.channel();
+        let cmd = crate::commands::privmsg(&channel, data);
+        cmd.encode(self)?;
+        self.flush()
+    }
+}
+
+/// Extensions to the `Whisper` message type
+pub trait WhisperExt {
+    /// Whisper `data` back to the user who sent this message
+    fn reply_whisper(&mut self, msg: &Whisper<'_>, data: &str) -> std::io::Result<()>;
+}
+
+impl<W: Write + ?Sized> WhisperExt for W {
+    fn reply_whisper(&mut self, msg: &Whisper<'_>, data: &str) -> std::io::Result<()> {
+        let name = msg.name();
+        let cmd = crate::commands::whisper(&name, data);
         cmd.encode(self)?;
         self.flush()
     }
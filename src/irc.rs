@@ -62,6 +62,7 @@ mod prefix;
 pub use prefix::{Prefix, PrefixIndex};
 
 mod tags;
+pub(crate) use tags::escape_value;
 pub use tags::{Tags, TagsIter};
 
 mod tag_indices;
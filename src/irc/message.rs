@@ -64,11 +64,25 @@ impl<'a> IrcMessage<'a> {
         self.prefix.map(|index| &self.raw[index.as_index()])
     }
 
+    /// Get the prefix, split into its parts
+    pub fn get_prefix_parts(&self) -> Option<Prefix<'_>> {
+        self.prefix.map(|index| Prefix {
+            data: &self.raw,
+            index,
+        })
+    }
+
     /// Get the raw command
     pub fn get_command(&self) -> &str {
         &self.raw[self.command]
     }
 
+    /// Is the command a numeric reply, e.g. `001` or `353`?
+    pub fn is_numeric(&self) -> bool {
+        let command = self.get_command();
+        command.len() == 3 && command.bytes().all(|c| c.is_ascii_digit())
+    }
+
     /// Get the raw args
     pub fn get_args(&self) -> Option<&str> {
         self.args.map(|index| &self.raw[index])
@@ -127,10 +141,32 @@ impl<'a> IrcMessage<'a> {
     ///
     /// This is sent when you've connected.
     pub const IRC_READY: &'static str = "001";
+    /// The server you're connected to -- `002`.
+    pub const YOUR_HOST: &'static str = "002";
+    /// When the server was created -- `003`.
+    pub const CREATED: &'static str = "003";
+    /// Information about the server -- `004`.
+    pub const MY_INFO: &'static str = "004";
+    /// The features the server supports -- `005`.
+    pub const I_SUPPORT: &'static str = "005";
+    /// A line of the message of the day -- `372`.
+    pub const MOTD: &'static str = "372";
+    /// The start of the message of the day -- `375`.
+    pub const MOTD_START: &'static str = "375";
     /// A Twitch Ready event -- `376`.
     ///
     /// This is sent by Twitch with your user information.
     pub const READY: &'static str = "376";
+    /// A list of users in a channel -- `353`.
+    ///
+    /// This is sent when you join a channel with the `membership` capability enabled.
+    pub const NAMES: &'static str = "353";
+    /// The end of the list of users in a channel -- `366`.
+    pub const END_OF_NAMES: &'static str = "366";
+    /// A command the server doesn't know about -- `421`.
+    ///
+    /// This is sent in response to a command Twitch doesn't support.
+    pub const UNKNOWN_COMMAND: &'static str = "421";
     /// A capability response -- `CAP`.
     ///
     /// This is sent to acknowledge whether the capability requested is valid and applied to your connections.
@@ -198,13 +234,7 @@ impl<'a> std::fmt::Debug for IrcMessage<'a> {
         f.debug_struct("IrcMessage")
             .field("raw", &&*self.raw)
             .field("tags", &self.get_tags())
-            .field(
-                "prefix",
-                &self.prefix.map(|index| Prefix {
-                    data: &self.raw,
-                    index,
-                }),
-            )
+            .field("prefix", &self.get_prefix_parts())
             .field("command", &self.get_command())
             .field("args", &self.get_args())
             .field("data", &self.get_data())
@@ -253,6 +283,31 @@ mod tests {
         crate::serde::round_trip_rmp::<IrcMessage>(input);
     }
 
+    #[test]
+    fn prefix_parts() {
+        let msg = IrcMessage::parse(":museun!user@host PRIVMSG #museun :hi\r\n".into()).unwrap();
+        assert_eq!(msg.get_prefix(), Some("museun!user@host"));
+        let prefix = msg.get_prefix_parts().unwrap();
+        assert_eq!(prefix.get_nick(), Some("museun"));
+        assert_eq!(prefix.get_user(), Some("user"));
+        assert_eq!(prefix.get_host(), Some("host"));
+        assert_eq!(msg.get_command(), "PRIVMSG");
+
+        // a '!' after the prefix doesn't make it a user
+        let msg = IrcMessage::parse(":tmi.twitch.tv NOTICE #museun :hi!\r\n".into()).unwrap();
+        let prefix = msg.get_prefix_parts().unwrap();
+        assert!(prefix.is_server());
+        assert_eq!(prefix.get_host(), Some("tmi.twitch.tv"));
+        assert_eq!(msg.get_command(), "NOTICE");
+
+        let msg = IrcMessage::parse(": PING :1234\r\n".into()).unwrap();
+        assert!(msg.get_prefix_parts().is_none());
+        assert_eq!(msg.get_command(), "PING");
+
+        let msg = IrcMessage::parse("PING :1234\r\n".into()).unwrap();
+        assert!(msg.get_prefix_parts().is_none());
+    }
+
     #[test]
     fn parse_empty_spaces() {
         for i in 0..10 {
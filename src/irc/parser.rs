@@ -25,22 +25,14 @@ impl<'a> Parser<'a> {
 
     pub(super) fn prefix(&mut self) -> Option<PrefixIndex> {
         let input = self.input.get(self.pos..)?;
-        if input.starts_with(':') {
-            if let Some(pos) = input.find(' ') {
-                self.pos += 1;
-                let prefix = match input.find('!') {
-                    Some(bang) => PrefixIndex::User {
-                        nick: self.mark_index(bang - 1, pos),
-                    },
-                    None => PrefixIndex::Server {
-                        host: self.mark_index(pos - 1, pos),
-                    },
-                };
-
-                return Some(prefix);
-            }
+        if !input.starts_with(':') {
+            return None;
         }
-        None
+
+        let end = input.find(' ')?;
+        let start = self.pos + 1;
+        self.pos += end + 1;
+        PrefixIndex::parse(&input[1..end], start)
     }
 
     pub(super) fn command(&mut self) -> MaybeOwnedIndex {
@@ -1,6 +1,26 @@
 use crate::{IntoOwned, MaybeOwned, MaybeOwnedIndex};
 
 /// Prefix is the sender of a message
+///
+/// A user prefix looks like `nick!user@host`, where the `user` and `host` are
+/// optional. Anything else with a `.` in it, e.g. `tmi.twitch.tv`, is a server.
+///
+/// ```
+/// # use twitchchat::irc::parse_one;
+/// let (_, msg) = parse_one(":museun!museun@museun.tmi.twitch.tv JOIN #museun\r\n").unwrap();
+/// let prefix = msg.get_prefix_parts().unwrap();
+/// assert!(prefix.is_user());
+/// assert_eq!(prefix.get_nick(), Some("museun"));
+/// assert_eq!(prefix.get_user(), Some("museun"));
+/// assert_eq!(prefix.get_host(), Some("museun.tmi.twitch.tv"));
+///
+/// let (_, msg) = parse_one(":tmi.twitch.tv PING 1234\r\n").unwrap();
+/// let prefix = msg.get_prefix_parts().unwrap();
+/// assert!(prefix.is_server());
+/// assert_eq!(prefix.get_nick(), None);
+/// assert_eq!(prefix.get_host(), Some("tmi.twitch.tv"));
+/// ```
+#[derive(Copy, Clone)]
 pub struct Prefix<'a> {
     pub(crate) data: &'a MaybeOwned<'a>,
     pub(crate) index: PrefixIndex,
@@ -20,7 +40,7 @@ impl<'a> Prefix<'a> {
 
     /// Was this message from a user?
     pub fn is_user(&self) -> bool {
-        matches!(self.index, PrefixIndex::User{ .. })
+        self.index.is_nick()
     }
 
     /// Get the full, raw string
@@ -32,6 +52,18 @@ impl<'a> Prefix<'a> {
     pub fn get_nick(&self) -> Option<&'a str> {
         self.index.nick_index().map(|index| &self.data[index])
     }
+
+    /// Get the username of this prefix, the part after the `!`, if it has one
+    pub fn get_user(&self) -> Option<&'a str> {
+        self.index.user_index().map(|index| &self.data[index])
+    }
+
+    /// Get the hostname of this prefix
+    ///
+    /// For a server this is the whole prefix, for a user it is the part after the `@`, if it has one
+    pub fn get_host(&self) -> Option<&'a str> {
+        self.index.host_index().map(|index| &self.data[index])
+    }
 }
 
 /// Prefix is the sender of a message
@@ -41,6 +73,14 @@ pub enum PrefixIndex {
     User {
         /// Index of the nickname
         nick: MaybeOwnedIndex,
+        /// Index of the username, the part after the `!`
+        ///
+        /// This is empty if the prefix was malformed, e.g. `nick!@host`
+        user: Option<MaybeOwnedIndex>,
+        /// Index of the hostname, the part after the `@`
+        ///
+        /// This is empty if the prefix was malformed, e.g. `nick!user@`
+        host: Option<MaybeOwnedIndex>,
     },
     /// A server prefix
     Server {
@@ -50,6 +90,46 @@ pub enum PrefixIndex {
 }
 
 impl PrefixIndex {
+    /// Parse the `prefix`, without its leading `:`, which starts at `offset` in the message
+    pub(crate) fn parse(prefix: &str, offset: usize) -> Option<Self> {
+        if prefix.is_empty() {
+            return None;
+        }
+
+        let index = |start: usize, end: usize| MaybeOwnedIndex::raw(offset + start, offset + end);
+
+        let len = prefix.len();
+        let nick_end = match prefix.find(&['!', '@'][..]) {
+            // a nickname can't start with a separator, so treat it as opaque
+            Some(0) => {
+                return Some(Self::Server {
+                    host: index(0, len),
+                })
+            }
+            Some(end) => end,
+            None if prefix.contains('.') => {
+                return Some(Self::Server {
+                    host: index(0, len),
+                })
+            }
+            None => len,
+        };
+
+        let at = prefix[nick_end..].find('@').map(|at| at + nick_end);
+        let user = if prefix[nick_end..].starts_with('!') {
+            Some(index(nick_end + 1, at.unwrap_or(len)))
+        } else {
+            None
+        };
+        let host = at.map(|at| index(at + 1, len));
+
+        Some(Self::User {
+            nick: index(0, nick_end),
+            user,
+            host,
+        })
+    }
+
     /// Was this message from the server?
     pub fn is_server(&self) -> bool {
         !self.is_nick()
@@ -57,29 +137,40 @@ impl PrefixIndex {
 
     /// Was this message from a user?
     pub fn is_nick(&self) -> bool {
-        matches!(self, Self::User{ .. })
+        matches!(self, Self::User { .. })
     }
 
     /// Get the index of the nickname
     pub fn nick_index(self) -> Option<MaybeOwnedIndex> {
         match self {
-            Self::User { nick } => Some(nick),
+            Self::User { nick, .. } => Some(nick),
+            Self::Server { .. } => None,
+        }
+    }
+
+    /// Get the index of the username, if it isn't empty
+    pub fn user_index(self) -> Option<MaybeOwnedIndex> {
+        match self {
+            Self::User { user, .. } => user.filter(|index| !index.is_empty()),
             Self::Server { .. } => None,
         }
     }
 
-    /// Get the index of the hostname
+    /// Get the index of the hostname, if it isn't empty
     pub fn host_index(self) -> Option<MaybeOwnedIndex> {
         match self {
             Self::Server { host } => Some(host),
-            Self::User { .. } => None,
+            Self::User { host, .. } => host.filter(|index| !index.is_empty()),
         }
     }
 
-    /// Consumes this returning the index
+    /// Consumes this returning the index of the whole prefix
     pub fn as_index(self) -> MaybeOwnedIndex {
         match self {
-            Self::User { nick } => nick,
+            Self::User { nick, user, host } => {
+                let end = host.or(user).unwrap_or(nick).end;
+                MaybeOwnedIndex { end, ..nick }
+            }
             Self::Server { host } => host,
         }
     }
@@ -91,3 +182,93 @@ impl IntoOwned<'static> for PrefixIndex {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The nick, user, host and the whole prefix
+    type Parts<'a> = (Option<&'a str>, Option<&'a str>, Option<&'a str>, &'a str);
+
+    fn parts(input: &str) -> Option<Parts<'_>> {
+        let index = PrefixIndex::parse(input, 0)?;
+        let get = |index: Option<MaybeOwnedIndex>| index.map(|index| &input[index.as_range()]);
+        let raw = &input[index.as_index().as_range()];
+        Some((
+            get(index.nick_index()),
+            get(index.user_index()),
+            get(index.host_index()),
+            raw,
+        ))
+    }
+
+    #[test]
+    fn user() {
+        let tests = &[
+            (
+                "museun!museun@museun.tmi.twitch.tv",
+                (Some("museun"), Some("museun"), Some("museun.tmi.twitch.tv")),
+            ),
+            ("museun!user", (Some("museun"), Some("user"), None)),
+            ("museun@host", (Some("museun"), None, Some("host"))),
+            ("museun", (Some("museun"), None, None)),
+        ];
+        for (input, (nick, user, host)) in tests {
+            assert_eq!(
+                parts(input),
+                Some((*nick, *user, *host, *input)),
+                "{}",
+                input
+            );
+            assert!(PrefixIndex::parse(input, 0).unwrap().is_nick());
+        }
+    }
+
+    #[test]
+    fn server() {
+        let index = PrefixIndex::parse("tmi.twitch.tv", 0).unwrap();
+        assert!(index.is_server());
+        assert_eq!(
+            parts("tmi.twitch.tv"),
+            Some((None, None, Some("tmi.twitch.tv"), "tmi.twitch.tv"))
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(PrefixIndex::parse("", 0), None);
+
+        // empty parts are missing
+        assert_eq!(
+            parts("museun!@"),
+            Some((Some("museun"), None, None, "museun!@"))
+        );
+        assert_eq!(
+            parts("museun!@host"),
+            Some((Some("museun"), None, Some("host"), "museun!@host"))
+        );
+
+        // the user can't contain an '@', the host can
+        assert_eq!(
+            parts("museun@host!user"),
+            Some((Some("museun"), None, Some("host!user"), "museun@host!user"))
+        );
+        assert_eq!(
+            parts("museun!a@b@c"),
+            Some((Some("museun"), Some("a"), Some("b@c"), "museun!a@b@c"))
+        );
+
+        // without a nick, it's opaque
+        for &input in &["!user@host", "@host"] {
+            assert_eq!(parts(input), Some((None, None, Some(input), input)));
+        }
+    }
+
+    #[test]
+    fn offset() {
+        let input = ":museun!user@host JOIN #museun";
+        let index = PrefixIndex::parse(&input[1..17], 1).unwrap();
+        assert_eq!(&input[index.as_index().as_range()], "museun!user@host");
+        assert_eq!(&input[index.user_index().unwrap().as_range()], "user");
+    }
+}
@@ -90,6 +90,29 @@ impl<'a> Tags<'a> {
             .flatten()
     }
 
+    /** Tries to get the tag, unescaping its value.
+
+    Tag values are escaped by the server: `\s` is a space, `\:` is a
+    semicolon, `\\` is a backslash and `\r`/`\n` are CR and LF.
+
+    ```rust
+    # use twitchchat::irc::{TagIndices, Tags};
+    # use twitchchat::maybe_owned::MaybeOwned;
+    let input: MaybeOwned<'_> = r"@msg=hello\sworld\:\s:)".into();
+    let indices = TagIndices::build_indices(&*input);
+    let tags = Tags::from_data_indices(&input, &indices);
+
+    assert_eq!(tags.get_unescaped("msg").unwrap(), "hello world; :)");
+    assert!(tags.get_unescaped("missing").is_none());
+    ```
+    */
+    pub fn get_unescaped<K>(&self, key: &K) -> Option<String>
+    where
+        K: ?Sized + Borrow<str>,
+    {
+        self.get(key).map(unescape_value)
+    }
+
     /** Tries to get the tag as a bool.
 
     If it wasn't found it'll return false
@@ -140,6 +163,43 @@ impl<'a> Tags<'a> {
     }
 }
 
+fn unescape_value(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut iter = input.chars();
+    while let Some(ch) = iter.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match iter.next() {
+            Some('s') => out.push(' '),
+            Some(':') => out.push(';'),
+            Some('r') => out.push('\r'),
+            Some('n') => out.push('\n'),
+            Some(ch) => out.push(ch),
+            // a trailing backslash is dropped
+            None => {}
+        }
+    }
+    out
+}
+
+/// Escape a tag value, the inverse of `unescape_value`
+pub(crate) fn escape_value(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            ' ' => out.push_str("\\s"),
+            ';' => out.push_str("\\:"),
+            '\r' => out.push_str("\\r"),
+            '\n' => out.push_str("\\n"),
+            '\\' => out.push_str("\\\\"),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
 impl<'a> IntoIterator for &'a Tags<'a> {
     type Item = (&'a str, &'a str);
 
@@ -272,6 +332,31 @@ mod tests {
         assert!(!tags.get_as_bool("nope"));
     }
 
+    #[test]
+    fn unescape() {
+        let inputs = &[
+            (r"hello\sworld", "hello world"),
+            (r"a\:b", "a;b"),
+            (r"a\\b", r"a\b"),
+            (r"a\r\nb", "a\r\nb"),
+            (r"trailing\", "trailing"),
+            (r"\x", "x"),
+            ("", ""),
+        ];
+        for (input, expected) in inputs {
+            assert_eq!(unescape_value(input), *expected);
+        }
+    }
+
+    #[test]
+    fn escape() {
+        let inputs = &["hello world", "a;b", r"a\b", "a\r\nb", "", r"\s"];
+        for input in inputs {
+            assert_eq!(unescape_value(&escape_value(input)), *input);
+        }
+        assert_eq!(escape_value("a b;c"), r"a\sb\:c");
+    }
+
     #[test]
     fn parse_empty_value() {
         let inputs = &[
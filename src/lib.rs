@@ -142,7 +142,7 @@ pub use self::twitch::UserConfig;
 // traits
 #[doc(inline)]
 pub use encoder::Encodable;
-pub use ext::PrivmsgExt;
+pub use ext::{PrivmsgExt, WhisperExt};
 #[doc(inline)]
 pub use irc::{FromIrcMessage, IntoIrcMessage};
 pub use maybe_owned::IntoOwned;
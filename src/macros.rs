@@ -101,6 +101,34 @@ macro_rules! str_field {
     };
 }
 
+macro_rules! channel_field {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        pub fn $name(&self) -> $crate::twitch::ChannelName<'_> {
+            $crate::twitch::ChannelName::from_server(&self.raw[self.$name])
+        }
+    };
+}
+
+macro_rules! login_field {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        pub fn $name(&self) -> $crate::twitch::Login<'_> {
+            $crate::twitch::Login::from_server(&self.raw[self.$name])
+        }
+    };
+}
+
+macro_rules! opt_login_field {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        pub fn $name(&self) -> Option<$crate::twitch::Login<'_>> {
+            self.$name
+                .map(|index| $crate::twitch::Login::from_server(&self.raw[index]))
+        }
+    };
+}
+
 macro_rules! opt_str_field {
     ($(#[$meta:meta])* $name:ident) => {
         $(#[$meta])*
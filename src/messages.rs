@@ -29,10 +29,38 @@
 //! assert!(matches!(all, Commands::Privmsg{..}));
 //! ```
 //!
+//! # Building messages
+//!
+//! Every message can be built with its `builder()` function, and encoded with
+//! the [`Encodable`](../trait.Encodable.html) trait. This is useful for tests,
+//! mocks and proxies.
+//!
+//! ```
+//! use twitchchat::{messages::ClearChat, Encodable as _};
+//!
+//! let msg = ClearChat::builder()
+//!     .channel("museun")
+//!     .name("shaken_bot")
+//!     .tag("ban-duration", 600)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(msg.name().unwrap(), "shaken_bot");
+//!
+//! let mut buf = vec![];
+//! msg.encode(&mut buf).unwrap();
+//! assert_eq!(
+//!     std::str::from_utf8(&buf).unwrap(),
+//!     "@ban-duration=600 :tmi.twitch.tv CLEARCHAT #museun :shaken_bot\r\n"
+//! );
+//! ```
+//!
 
 mod commands;
 pub use commands::Commands;
 
+mod builder;
+pub use builder::MessageBuilder;
+
 mod irc_ready;
 pub use irc_ready::IrcReady;
 
@@ -57,9 +85,18 @@ pub use host_target::{HostTarget, HostTargetKind};
 mod join;
 pub use join::Join;
 
+mod motd;
+pub use motd::{Motd, MotdStart};
+
+mod names;
+pub use names::{EndOfNames, Names};
+
 mod notice;
 pub use notice::{MessageId, Notice};
 
+mod numeric;
+pub use numeric::Numeric;
+
 mod part;
 pub use part::Part;
 
@@ -70,7 +107,7 @@ mod pong;
 pub use pong::Pong;
 
 mod privmsg;
-pub use privmsg::Privmsg;
+pub use privmsg::{PinnedChat, Privmsg, ReplyParent};
 
 mod reconnect;
 pub use reconnect::Reconnect;
@@ -78,12 +115,18 @@ pub use reconnect::Reconnect;
 mod room_state;
 pub use room_state::{FollowersOnly, RoomState};
 
+mod unknown_command;
+pub use unknown_command::UnknownCommand;
+
 mod user_notice;
 pub use user_notice::{NoticeType, SubPlan, UserNotice};
 
 mod user_state;
 pub use user_state::UserState;
 
+mod welcome;
+pub use welcome::{Created, ISupport, MyInfo, YourHost};
+
 mod whisper;
 pub use whisper::Whisper;
 
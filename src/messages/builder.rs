@@ -0,0 +1,1019 @@
+use super::*;
+use crate::{
+    commands::{check, EncodeError},
+    irc::{escape_value, MessageError},
+    twitch::ChannelName,
+    Encodable, FromIrcMessage, MaybeOwned,
+};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Write as _},
+    io::{Result as IoResult, Write},
+    marker::PhantomData,
+};
+
+const TMI: &str = "tmi.twitch.tv";
+
+/// A builder for the server-side messages in this module
+///
+/// Get one from the message's `builder()` function, e.g. [Privmsg::builder].
+/// Building the message produces a correctly escaped IRC line and parses it
+/// back into the message type, so the built message behaves exactly like one
+/// read from Twitch.
+///
+/// # Example
+/// ```
+/// use twitchchat::{messages::Privmsg, Encodable as _};
+///
+/// let msg = Privmsg::builder()
+///     .name("museun")
+///     .channel("museun")
+///     .data("hello world")
+///     .tag("display-name", "Museun")
+///     .tag("color", "#FF0000")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(msg.channel(), "#museun");
+/// assert_eq!(msg.display_name(), Some("Museun"));
+///
+/// let mut buf = vec![];
+/// msg.encode(&mut buf).unwrap();
+/// assert_eq!(
+///     std::str::from_utf8(&buf).unwrap(),
+///     "@display-name=Museun;color=#FF0000 :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello world\r\n"
+/// );
+/// ```
+///
+/// [Privmsg::builder]: ./struct.Privmsg.html#method.builder
+pub struct MessageBuilder<T> {
+    tags: Vec<(String, String)>,
+    prefix: Option<String>,
+    command: Cow<'static, str>,
+    args: Vec<(&'static str, String)>,
+    data: Option<String>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for MessageBuilder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tags: self.tags.clone(),
+            prefix: self.prefix.clone(),
+            command: self.command.clone(),
+            args: self.args.clone(),
+            data: self.data.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for MessageBuilder<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MessageBuilder")
+            .field("tags", &self.tags)
+            .field("prefix", &self.prefix)
+            .field("command", &self.command)
+            .field("args", &self.args)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<T> MessageBuilder<T> {
+    fn new(command: &'static str, prefix: Option<&str>, args: &[(&'static str, &str)]) -> Self {
+        Self {
+            tags: vec![],
+            prefix: prefix.map(ToString::to_string),
+            command: command.into(),
+            args: args
+                .iter()
+                .map(|&(field, arg)| (field, arg.to_string()))
+                .collect(),
+            data: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the tag `key` to `value`, replacing any previous value
+    ///
+    /// The value is escaped when the message is built.
+    pub fn tag(mut self, key: &str, value: impl Display) -> Self {
+        let value = value.to_string();
+        match self.tags.iter_mut().find(|(k, _)| k == key) {
+            Some((_, old)) => *old = value,
+            None => self.tags.push((key.to_string(), value)),
+        }
+        self
+    }
+
+    /// Set the prefix, the part between the `:` and the command
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix.replace(prefix.to_string());
+        self
+    }
+
+    /// Set the prefix to the one Twitch uses for `login`
+    fn user(self, login: &str) -> Self {
+        self.prefix(&format!("{0}!{0}@{0}.{1}", login, TMI))
+    }
+
+    fn arg(mut self, pos: usize, arg: impl Into<String>) -> Self {
+        self.args[pos].1 = arg.into();
+        self
+    }
+
+    fn channel_arg(self, pos: usize, channel: &str) -> Self {
+        self.arg(pos, ChannelName::from_server(channel).into_string())
+    }
+
+    fn data_part(mut self, data: impl Into<String>) -> Self {
+        self.data.replace(data.into());
+        self
+    }
+
+    fn validate(&self) -> Result<(), EncodeError> {
+        for (key, _) in &self.tags {
+            if key.is_empty() || key.contains(&['=', ';', ' '][..]) {
+                return Err(EncodeError::InvalidName {
+                    field: "tag",
+                    name: key.clone(),
+                });
+            }
+            check::text("tag", key)?;
+        }
+        if let Some(prefix) = &self.prefix {
+            check::name("prefix", prefix)?;
+        }
+        for (field, arg) in &self.args {
+            check::name(field, arg)?;
+        }
+        check::opt_text("data", self.data.as_deref())
+    }
+}
+
+impl<T> MessageBuilder<T>
+where
+    T: FromIrcMessage<'static, Error = MessageError>,
+{
+    /// Build the message
+    ///
+    /// This returns an error if a required part is missing, or if a part
+    /// contains characters that would break the IRC line.
+    /// The [EncodeError] is wrapped in a [MessageError::Custom].
+    ///
+    /// [EncodeError]: ../commands/enum.EncodeError.html
+    /// [MessageError::Custom]: ../irc/enum.MessageError.html#variant.Custom
+    pub fn build(self) -> Result<T, MessageError> {
+        self.validate().map_err(|err| MessageError::Custom {
+            error: Box::new(err),
+        })?;
+        let msg = IrcMessage::parse(MaybeOwned::from(self.to_string()))?;
+        T::from_irc(msg)
+    }
+}
+
+impl<T> Display for MessageBuilder<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (key, value)) in self.tags.iter().enumerate() {
+            let sep = if i == 0 { '@' } else { ';' };
+            write!(f, "{}{}={}", sep, key, escape_value(value))?;
+        }
+        if !self.tags.is_empty() {
+            f.write_char(' ')?;
+        }
+        if let Some(prefix) = &self.prefix {
+            write!(f, ":{} ", prefix)?;
+        }
+        f.write_str(&self.command)?;
+        for (_, arg) in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        if let Some(data) = &self.data {
+            write!(f, " :{}", data)?;
+        }
+        f.write_str("\r\n")
+    }
+}
+
+macro_rules! builder {
+    ($(#[$meta:meta])* $ty:ident => $command:ident, $prefix:expr, [$($field:literal => $default:literal),* $(,)?]) => {
+        impl<'a> $ty<'a> {
+            $(#[$meta])*
+            pub fn builder() -> MessageBuilder<$ty<'static>> {
+                MessageBuilder::new(IrcMessage::$command, $prefix, &[$(($field, $default)),*])
+            }
+        }
+    };
+}
+
+builder!(
+    /// Build an `IrcReady` (`001`) message
+    IrcReady => IRC_READY, Some(TMI), ["username" => ""]
+);
+
+impl MessageBuilder<IrcReady<'static>> {
+    /// The name Twitch gave you
+    pub fn username(self, username: &str) -> Self {
+        self.arg(0, username).data_part("Welcome, GLHF!")
+    }
+}
+
+builder!(
+    /// Build a `Ready` (`376`) message
+    Ready => READY, Some(TMI), ["username" => ""]
+);
+
+impl MessageBuilder<Ready<'static>> {
+    /// The name Twitch gave you
+    pub fn username(self, username: &str) -> Self {
+        self.arg(0, username).data_part(">")
+    }
+}
+
+builder!(
+    /// Build a `CAP` message, this is acknowledged by default
+    Cap => CAP, Some(TMI), ["client" => "*", "acknowledged" => "ACK"]
+);
+
+impl MessageBuilder<Cap<'static>> {
+    /// The capability that was requested
+    pub fn capability(self, capability: &str) -> Self {
+        self.data_part(capability)
+    }
+
+    /// Whether the capability was acknowledged
+    pub fn acknowledged(self, acknowledged: bool) -> Self {
+        self.arg(1, if acknowledged { "ACK" } else { "NAK" })
+    }
+}
+
+builder!(
+    /// Build a `CLEARCHAT` message
+    ClearChat => CLEAR_CHAT, Some(TMI), ["channel" => ""]
+);
+
+impl MessageBuilder<ClearChat<'static>> {
+    /// The channel this happened on
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+
+    /// The user that was purged. If this isn't set, the whole chat was cleared
+    pub fn name(self, name: &str) -> Self {
+        self.data_part(name)
+    }
+}
+
+builder!(
+    /// Build a `CLEARMSG` message
+    ClearMsg => CLEAR_MSG, Some(TMI), ["channel" => ""]
+);
+
+impl MessageBuilder<ClearMsg<'static>> {
+    /// The channel this happened on
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+
+    /// The message that was deleted
+    pub fn message(self, message: &str) -> Self {
+        self.data_part(message)
+    }
+
+    /// The user who sent the deleted message, the `login` tag
+    pub fn login(self, login: &str) -> Self {
+        self.tag("login", login)
+    }
+
+    /// The id of the deleted message, the `target-msg-id` tag
+    pub fn target_msg_id(self, id: &str) -> Self {
+        self.tag("target-msg-id", id)
+    }
+}
+
+builder!(
+    /// Build a `GLOBALUSERSTATE` message
+    GlobalUserState => GLOBAL_USER_STATE, Some(TMI), []
+);
+
+builder!(
+    /// Build a `HOSTTARGET` message, this ends hosting by default
+    HostTarget => HOST_TARGET, Some(TMI), ["source" => ""]
+);
+
+impl MessageBuilder<HostTarget<'static>> {
+    /// The channel doing the hosting
+    pub fn source(self, source: &str) -> Self {
+        self.channel_arg(0, source)
+    }
+
+    /// Start hosting `target`, bringing along `viewers`
+    pub fn start(self, target: &str, viewers: impl Into<Option<usize>>) -> Self {
+        self.host(target, viewers.into())
+    }
+
+    /// Stop hosting, with the number of `viewers` that were brought along
+    pub fn end(self, viewers: impl Into<Option<usize>>) -> Self {
+        self.host("-", viewers.into())
+    }
+
+    fn host(self, target: &str, viewers: Option<usize>) -> Self {
+        match viewers {
+            Some(viewers) => self.data_part(format!("{} {}", target, viewers)),
+            None => self.data_part(target),
+        }
+    }
+}
+
+builder!(
+    /// Build a `JOIN` message
+    Join => JOIN, None, ["channel" => ""]
+);
+
+impl MessageBuilder<Join<'static>> {
+    /// The user that joined
+    pub fn name(self, name: &str) -> Self {
+        self.user(name)
+    }
+
+    /// The channel they joined
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+}
+
+builder!(
+    /// Build a `NOTICE` message, this is sent to `*` by default
+    Notice => NOTICE, Some(TMI), ["channel" => "*"]
+);
+
+impl MessageBuilder<Notice<'static>> {
+    /// The channel this notice was sent to
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+
+    /// The notice itself
+    pub fn message(self, message: &str) -> Self {
+        self.data_part(message)
+    }
+
+    /// The kind of notice, the `msg-id` tag
+    pub fn msg_id(self, msg_id: &str) -> Self {
+        self.tag("msg-id", msg_id)
+    }
+}
+
+builder!(
+    /// Build a `PART` message
+    Part => PART, None, ["channel" => ""]
+);
+
+impl MessageBuilder<Part<'static>> {
+    /// The user that left
+    pub fn name(self, name: &str) -> Self {
+        self.user(name)
+    }
+
+    /// The channel they left
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+}
+
+builder!(
+    /// Build a `PING` message
+    Ping => PING, None, []
+);
+
+impl MessageBuilder<Ping<'static>> {
+    /// The token that should be sent back
+    pub fn token(self, token: &str) -> Self {
+        self.data_part(token)
+    }
+}
+
+builder!(
+    /// Build a `PONG` message
+    Pong => PONG, Some(TMI), ["server" => "tmi.twitch.tv"]
+);
+
+impl MessageBuilder<Pong<'static>> {
+    /// The token from the `PING`
+    pub fn token(self, token: &str) -> Self {
+        self.data_part(token)
+    }
+}
+
+builder!(
+    /// Build a `PRIVMSG` message
+    Privmsg => PRIVMSG, None, ["channel" => ""]
+);
+
+impl MessageBuilder<Privmsg<'static>> {
+    /// The user that sent the message
+    pub fn name(self, name: &str) -> Self {
+        self.user(name)
+    }
+
+    /// The channel the message was sent on
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+
+    /// The message
+    pub fn data(self, data: &str) -> Self {
+        self.data_part(data)
+    }
+
+    /// The message, sent as an action (`/me`)
+    pub fn action(self, data: &str) -> Self {
+        self.data_part(format!("\x01ACTION {}\x01", data))
+    }
+}
+
+builder!(
+    /// Build a `RECONNECT` message
+    Reconnect => RECONNECT, Some(TMI), []
+);
+
+builder!(
+    /// Build a `ROOMSTATE` message
+    RoomState => ROOM_STATE, Some(TMI), ["channel" => ""]
+);
+
+impl MessageBuilder<RoomState<'static>> {
+    /// The channel this state is for
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+}
+
+builder!(
+    /// Build a `USERNOTICE` message
+    UserNotice => USER_NOTICE, Some(TMI), ["channel" => ""]
+);
+
+impl MessageBuilder<UserNotice<'static>> {
+    /// The channel this event happened on
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+
+    /// The message the user attached to the event
+    pub fn message(self, message: &str) -> Self {
+        self.data_part(message)
+    }
+
+    /// The kind of event, the `msg-id` tag
+    pub fn msg_id(self, msg_id: &str) -> Self {
+        self.tag("msg-id", msg_id)
+    }
+}
+
+builder!(
+    /// Build a `USERSTATE` message
+    UserState => USER_STATE, Some(TMI), ["channel" => ""]
+);
+
+impl MessageBuilder<UserState<'static>> {
+    /// The channel this state is for
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(0, channel)
+    }
+}
+
+builder!(
+    /// Build a `WHISPER` message
+    Whisper => WHISPER, None, ["target" => ""]
+);
+
+impl MessageBuilder<Whisper<'static>> {
+    /// The user that sent the whisper
+    pub fn name(self, name: &str) -> Self {
+        self.user(name)
+    }
+
+    /// The user that received the whisper
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target)
+    }
+
+    /// The message
+    pub fn data(self, data: &str) -> Self {
+        self.data_part(data)
+    }
+}
+
+impl<'a> Numeric<'a> {
+    /// Build a numeric reply, this is a `002` by default
+    pub fn builder() -> MessageBuilder<Numeric<'static>> {
+        MessageBuilder::new("002", Some(TMI), &[("target", "")])
+    }
+}
+
+impl MessageBuilder<Numeric<'static>> {
+    /// The numeric code, e.g. `372`
+    ///
+    /// A code above `999` isn't a numeric, so the message won't build.
+    pub fn code(mut self, code: u16) -> Self {
+        self.command = format!("{:03}", code).into();
+        self
+    }
+
+    /// The name the reply was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target)
+    }
+
+    /// Add an argument after the target
+    pub fn param(mut self, param: &str) -> Self {
+        self.args.push(("param", param.to_string()));
+        self
+    }
+
+    /// The trailing message
+    pub fn data(self, data: &str) -> Self {
+        self.data_part(data)
+    }
+}
+
+builder!(
+    /// Build a `353` message, listing the users in a channel
+    Names => NAMES, Some(TMI), ["target" => "", "kind" => "=", "channel" => ""]
+);
+
+impl MessageBuilder<Names<'static>> {
+    /// The name the list was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target)
+    }
+
+    /// The channel the users are in
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(2, channel)
+    }
+
+    /// The names of the users
+    pub fn names(self, names: &[&str]) -> Self {
+        self.data_part(names.join(" "))
+    }
+}
+
+builder!(
+    /// Build a `366` message, ending the list of users in a channel
+    EndOfNames => END_OF_NAMES, Some(TMI), ["target" => "", "channel" => ""]
+);
+
+impl MessageBuilder<EndOfNames<'static>> {
+    /// The name the list was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target).data_part("End of /NAMES list")
+    }
+
+    /// The channel the list was for
+    pub fn channel(self, channel: &str) -> Self {
+        self.channel_arg(1, channel)
+    }
+}
+
+builder!(
+    /// Build a `421` message
+    UnknownCommand => UNKNOWN_COMMAND, Some(TMI), ["target" => "", "command" => ""]
+);
+
+impl MessageBuilder<UnknownCommand<'static>> {
+    /// The name the reply was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target)
+    }
+
+    /// The command that wasn't recognized
+    pub fn command(self, command: &str) -> Self {
+        self.arg(1, command).data_part("Unknown command")
+    }
+}
+
+builder!(
+    /// Build a `002` message
+    YourHost => YOUR_HOST, Some(TMI), ["target" => ""]
+);
+
+impl MessageBuilder<YourHost<'static>> {
+    /// The name the reply was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target).data_part("Your host is tmi.twitch.tv")
+    }
+}
+
+builder!(
+    /// Build a `003` message
+    Created => CREATED, Some(TMI), ["target" => ""]
+);
+
+impl MessageBuilder<Created<'static>> {
+    /// The name the reply was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target).data_part("This server is rather new")
+    }
+}
+
+builder!(
+    /// Build a `004` message
+    MyInfo => MY_INFO, Some(TMI), ["target" => ""]
+);
+
+impl MessageBuilder<MyInfo<'static>> {
+    /// The name the reply was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target).data_part("-")
+    }
+
+    /// Add an argument after the target
+    pub fn param(mut self, param: &str) -> Self {
+        self.args.push(("param", param.to_string()));
+        self
+    }
+}
+
+builder!(
+    /// Build a `005` message
+    ISupport => I_SUPPORT, Some(TMI), ["target" => ""]
+);
+
+impl MessageBuilder<ISupport<'static>> {
+    /// The name the reply was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target)
+            .data_part("are supported by this server")
+    }
+
+    /// Add a supported feature, with an optional value
+    pub fn token(mut self, name: &str, value: Option<&str>) -> Self {
+        let token = match value {
+            Some(value) => format!("{}={}", name, value),
+            None => name.to_string(),
+        };
+        self.args.push(("token", token));
+        self
+    }
+}
+
+builder!(
+    /// Build a `375` message
+    MotdStart => MOTD_START, Some(TMI), ["target" => ""]
+);
+
+impl MessageBuilder<MotdStart<'static>> {
+    /// The name the reply was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target).data_part("-")
+    }
+}
+
+builder!(
+    /// Build a `372` message
+    Motd => MOTD, Some(TMI), ["target" => ""]
+);
+
+impl MessageBuilder<Motd<'static>> {
+    /// The name the reply was sent to
+    pub fn target(self, target: &str) -> Self {
+        self.arg(0, target)
+    }
+
+    /// The line of the message of the day
+    pub fn line(self, line: &str) -> Self {
+        self.data_part(line)
+    }
+}
+
+/// Write `raw`, making sure it ends with a line ending
+fn encode_raw<W>(raw: &str, buf: &mut W) -> IoResult<()>
+where
+    W: Write + ?Sized,
+{
+    buf.write_all(raw.as_bytes())?;
+    if !raw.ends_with("\r\n") {
+        buf.write_all(b"\r\n")?;
+    }
+    Ok(())
+}
+
+macro_rules! encodable {
+    ($($ty:ident)*) => {
+        $(
+            impl<'a> Encodable for $ty<'a> {
+                fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+                where
+                    W: Write + ?Sized,
+                {
+                    encode_raw(self.raw(), buf)
+                }
+            }
+        )*
+    };
+}
+
+encodable! {
+    IrcReady Ready Cap ClearChat ClearMsg GlobalUserState HostTarget Join
+    Notice Part Ping Pong Privmsg Reconnect RoomState UserNotice UserState Whisper
+    Numeric Names EndOfNames UnknownCommand YourHost Created MyInfo ISupport MotdStart Motd
+}
+
+impl<'a> Encodable for IrcMessage<'a> {
+    fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+    where
+        W: Write + ?Sized,
+    {
+        encode_raw(self.get_raw(), buf)
+    }
+}
+
+impl<'a> Encodable for Commands<'a> {
+    fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+    where
+        W: Write + ?Sized,
+    {
+        encode_raw(self.raw(), buf)
+    }
+}
+
+impl<T> Encodable for MessageBuilder<T> {
+    fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+    where
+        W: Write + ?Sized,
+    {
+        self.validate()?;
+        write!(buf, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{irc::parse_one, IntoOwned as _};
+
+    fn encode(enc: impl Encodable) -> String {
+        let mut buf = vec![];
+        enc.encode(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    // build it, encode it, then parse it again
+    fn round_trip<T>(builder: MessageBuilder<T>) -> T
+    where
+        T: FromIrcMessage<'static, Error = MessageError> + Encodable,
+    {
+        let line = builder.to_string();
+        let msg = builder.build().unwrap();
+        assert_eq!(encode(&msg), line);
+
+        let (_, parsed) = parse_one(&line).unwrap();
+        T::from_irc(parsed.into_owned()).unwrap()
+    }
+
+    #[test]
+    fn privmsg() {
+        let msg = round_trip(
+            Privmsg::builder()
+                .name("Museun")
+                .channel("Museun")
+                .data("hello world")
+                .tag("display-name", "Museun")
+                .tag("color", "#FF0000")
+                .tag("id", "abc"),
+        );
+        assert_eq!(msg.name(), "museun");
+        assert_eq!(msg.channel(), "#museun");
+        assert_eq!(msg.data(), "hello world");
+        assert_eq!(msg.display_name(), Some("Museun"));
+        assert_eq!(msg.tags().get("color"), Some("#FF0000"));
+
+        let msg = round_trip(
+            Privmsg::builder()
+                .name("museun")
+                .channel("#museun")
+                .action("waves"),
+        );
+        assert!(msg.is_action());
+        assert_eq!(msg.data(), "waves");
+    }
+
+    #[test]
+    fn tags_are_escaped() {
+        let builder = UserNotice::builder()
+            .channel("museun")
+            .msg_id("sub")
+            .tag("system-msg", "museun subscribed; at Tier 1.\\");
+        assert_eq!(
+            builder.to_string(),
+            "@msg-id=sub;system-msg=museun\\ssubscribed\\:\\sat\\sTier\\s1.\\\\ \
+             :tmi.twitch.tv USERNOTICE #museun\r\n"
+        );
+
+        let msg = round_trip(builder);
+        assert_eq!(
+            msg.tags().get_unescaped("system-msg").unwrap(),
+            "museun subscribed; at Tier 1.\\"
+        );
+
+        let msg = round_trip(
+            UserNotice::builder()
+                .channel("museun")
+                .msg_id("sub")
+                .tag("system-msg", "a\r\nb"),
+        );
+        assert_eq!(msg.tags().get("system-msg"), Some("a\\r\\nb"));
+        assert_eq!(msg.tags().get_unescaped("system-msg").unwrap(), "a\r\nb");
+    }
+
+    #[test]
+    fn tags_are_replaced() {
+        let builder = Privmsg::builder().tag("color", "red").tag("color", "blue");
+        assert!(builder.to_string().starts_with("@color=blue PRIVMSG"));
+    }
+
+    #[test]
+    fn every_message() {
+        let msg = round_trip(IrcReady::builder().username("justinfan1234"));
+        assert_eq!(msg.username(), "justinfan1234");
+
+        let msg = round_trip(Ready::builder().username("justinfan1234"));
+        assert_eq!(msg.username(), "justinfan1234");
+
+        let msg = round_trip(Cap::builder().capability("twitch.tv/tags"));
+        assert_eq!(msg.capability(), Capability::Acknowledged("twitch.tv/tags"));
+        let msg = round_trip(Cap::builder().capability("foo").acknowledged(false));
+        assert_eq!(msg.capability(), Capability::NotAcknowledged("foo"));
+
+        let msg = round_trip(ClearChat::builder().channel("museun"));
+        assert_eq!(msg.channel(), "#museun");
+        assert!(msg.name().is_none());
+
+        let msg = round_trip(
+            ClearMsg::builder()
+                .channel("museun")
+                .login("shaken_bot")
+                .target_msg_id("abc")
+                .message("bye"),
+        );
+        assert_eq!(msg.login().unwrap(), "shaken_bot");
+        assert_eq!(msg.target_msg_id(), Some("abc"));
+        assert_eq!(msg.message(), Some("bye"));
+
+        let msg = round_trip(GlobalUserState::builder().tag("user-id", 1234));
+        assert_eq!(msg.user_id(), Some("1234"));
+
+        let msg = round_trip(HostTarget::builder().source("museun").start("shaken", 10));
+        assert_eq!(msg.viewers(), Some(10));
+        assert_eq!(
+            msg.host_target_kind(),
+            HostTargetKind::Start { target: "shaken" }
+        );
+        let msg = round_trip(HostTarget::builder().source("museun").end(None));
+        assert_eq!(msg.host_target_kind(), HostTargetKind::End);
+
+        let msg = round_trip(Join::builder().name("museun").channel("shaken"));
+        assert_eq!(msg.name(), "museun");
+        assert_eq!(msg.channel(), "#shaken");
+
+        let msg = round_trip(Part::builder().name("museun").channel("shaken"));
+        assert_eq!(msg.name(), "museun");
+        assert_eq!(msg.channel(), "#shaken");
+
+        let msg = round_trip(
+            Notice::builder()
+                .msg_id("msg_banned")
+                .message("You are permanently banned from talking in museun."),
+        );
+        assert!(msg.channel().is_none());
+        assert_eq!(msg.msg_id(), Some(MessageId::MsgBanned));
+
+        let msg = round_trip(Ping::builder().token("1234"));
+        assert_eq!(msg.token(), "1234");
+        let msg = round_trip(Pong::builder().token("1234"));
+        assert_eq!(msg.token(), "1234");
+
+        round_trip(Reconnect::builder());
+
+        let msg = round_trip(RoomState::builder().channel("museun").tag("slow", 30));
+        assert_eq!(msg.is_slow_mode(), Some(30));
+
+        let msg = round_trip(UserState::builder().channel("museun"));
+        assert_eq!(msg.channel(), "#museun");
+
+        let msg = round_trip(
+            Whisper::builder()
+                .name("museun")
+                .target("shaken_bot")
+                .data("hi"),
+        );
+        assert_eq!(msg.name(), "museun");
+        assert_eq!(msg.data(), "hi");
+
+        let msg = round_trip(
+            Numeric::builder()
+                .code(4)
+                .target("museun")
+                .param("tmi.twitch.tv")
+                .data("-"),
+        );
+        assert_eq!(msg.code(), 4);
+        assert_eq!(msg.raw(), ":tmi.twitch.tv 004 museun tmi.twitch.tv :-\r\n");
+        assert_eq!(msg.params().collect::<Vec<_>>(), vec!["tmi.twitch.tv"]);
+        assert!(Numeric::builder()
+            .code(1000)
+            .target("museun")
+            .build()
+            .is_err());
+
+        let msg = round_trip(
+            Names::builder()
+                .target("museun")
+                .channel("museun")
+                .names(&["museun", "shaken_bot"]),
+        );
+        assert_eq!(msg.channel(), "#museun");
+        assert_eq!(
+            msg.names().collect::<Vec<_>>(),
+            vec!["museun", "shaken_bot"]
+        );
+
+        let msg = round_trip(EndOfNames::builder().target("museun").channel("museun"));
+        assert_eq!(msg.channel(), "#museun");
+
+        let msg = round_trip(UnknownCommand::builder().target("museun").command("WHO"));
+        assert_eq!(msg.command(), "WHO");
+        assert_eq!(msg.message(), Some("Unknown command"));
+
+        let msg = round_trip(YourHost::builder().target("museun"));
+        assert_eq!(msg.message(), Some("Your host is tmi.twitch.tv"));
+        let msg = round_trip(Created::builder().target("museun"));
+        assert_eq!(msg.target(), "museun");
+        let msg = round_trip(MyInfo::builder().target("museun").param("tmi.twitch.tv"));
+        assert_eq!(msg.params().collect::<Vec<_>>(), vec!["tmi.twitch.tv"]);
+
+        let msg = round_trip(
+            ISupport::builder()
+                .target("museun")
+                .token("CHANTYPES", Some("#"))
+                .token("EXCEPTS", None),
+        );
+        assert_eq!(
+            msg.raw(),
+            ":tmi.twitch.tv 005 museun CHANTYPES=# EXCEPTS :are supported by this server\r\n"
+        );
+        assert_eq!(
+            msg.tokens().collect::<Vec<_>>(),
+            vec![("CHANTYPES", Some("#")), ("EXCEPTS", None)]
+        );
+
+        let msg = round_trip(MotdStart::builder().target("museun"));
+        assert_eq!(msg.message(), Some("-"));
+        let msg = round_trip(Motd::builder().target("museun").line("hello"));
+        assert_eq!(msg.line(), Some("hello"));
+    }
+
+    #[test]
+    fn commands() {
+        let msg = Ping::builder().token("1234").build().unwrap();
+        let all = Commands::from(msg);
+        assert_eq!(encode(&all), "PING :1234\r\n");
+
+        let (_, raw) = parse_one("FOO bar\r\n").unwrap();
+        assert_eq!(encode(raw), "FOO bar\r\n");
+    }
+
+    #[test]
+    fn invalid() {
+        let err = Privmsg::builder()
+            .name("museun")
+            .channel("museun")
+            .data("hi\r\nPRIVMSG #museun :injected")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, MessageError::Custom { .. }));
+
+        let err = Privmsg::builder().name("museun").data("hi").build();
+        assert!(err.is_err());
+
+        let err = Privmsg::builder()
+            .channel("museun")
+            .data("hi")
+            .tag("bad key", "value")
+            .build();
+        assert!(err.is_err());
+
+        let err = Join::builder().name("museun").channel("#foo bar");
+        assert!(err.clone().build().is_err());
+        assert_eq!(
+            EncodeError::from_io(&err.encode(&mut vec![]).unwrap_err()),
+            Some(&EncodeError::InvalidName {
+                field: "channel",
+                name: "#foo bar".to_string(),
+            })
+        );
+    }
+}
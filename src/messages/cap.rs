@@ -1,6 +1,7 @@
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 
 /// A parsed Capability
+#[non_exhaustive]
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub enum Capability<'a> {
@@ -14,26 +15,71 @@ pub enum Capability<'a> {
         /// The name of the requested capability
         &'a str,
     ),
+    /// The server supports this Capability, in reply to a `CAP LS`
+    Available(
+        /// The name of the capability
+        &'a str,
+    ),
 }
 
-/// Acknowledgement (or not) on a **CAPS** request
+/// Acknowledgement (or not) on a **CAPS** request, or the capabilities the server supports
+///
+/// A single message can contain several capabilities, e.g. when several were
+/// requested at once. Use [capabilities] to get all of them.
+///
+/// [capabilities]: ./struct.Cap.html#method.capabilities
 #[derive(Clone, PartialEq)]
 pub struct Cap<'a> {
     raw: MaybeOwned<'a>,
     capability: MaybeOwnedIndex,
-    acknowledged: bool,
+    subcommand: MaybeOwnedIndex,
+    more: bool,
 }
 
 impl<'a> Cap<'a> {
     raw!();
+    str_field!(
+        /// The subcommand, e.g. `ACK`, `NAK` or `LS`
+        subcommand
+    );
 
     /// The parsed capability
+    ///
+    /// If the message has several capabilities, this contains all of them.
     pub fn capability(&self) -> Capability<'_> {
-        let cap = &self.raw[self.capability];
-        if self.acknowledged {
-            Capability::Acknowledged(cap)
-        } else {
-            Capability::NotAcknowledged(cap)
+        self.to_capability(&self.raw[self.capability])
+    }
+
+    /// Each of the capabilities in this message
+    ///
+    /// ```
+    /// # use twitchchat::{messages::{Cap, Capability}, irc::parse_one, FromIrcMessage};
+    /// let input = ":tmi.twitch.tv CAP * ACK :twitch.tv/tags twitch.tv/commands\r\n";
+    /// let cap = Cap::from_irc(parse_one(input).unwrap().1).unwrap();
+    /// assert_eq!(
+    ///     cap.capabilities().collect::<Vec<_>>(),
+    ///     vec![
+    ///         Capability::Acknowledged("twitch.tv/tags"),
+    ///         Capability::Acknowledged("twitch.tv/commands"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn capabilities(&self) -> impl Iterator<Item = Capability<'_>> + '_ {
+        self.raw[self.capability]
+            .split_ascii_whitespace()
+            .map(move |cap| self.to_capability(cap))
+    }
+
+    /// Whether the server will send more `LS` lines after this one
+    pub fn has_more(&self) -> bool {
+        self.more
+    }
+
+    fn to_capability<'b>(&self, cap: &'b str) -> Capability<'b> {
+        match self.subcommand() {
+            "ACK" | "LIST" => Capability::Acknowledged(cap),
+            "LS" | "NEW" => Capability::Available(cap),
+            _ => Capability::NotAcknowledged(cap),
         }
     }
 }
@@ -42,13 +88,12 @@ impl<'a> FromIrcMessage<'a> for Cap<'a> {
     type Error = MessageError;
 
     fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
-        const ACK: &str = "ACK";
-
         msg.expect_command(IrcMessage::CAP)?;
 
         let this = Self {
             capability: msg.expect_data_index()?,
-            acknowledged: msg.expect_arg(1)? == ACK,
+            subcommand: msg.expect_arg_index(1)?,
+            more: msg.nth_arg(2) == Some("*"),
             raw: msg.raw,
         };
 
@@ -61,12 +106,21 @@ impl<'a> FromIrcMessage<'a> for Cap<'a> {
 into_owned!(Cap {
     raw,
     capability,
-    acknowledged
+    subcommand,
+    more
 });
 
-impl_custom_debug!(Cap { raw, capability });
+impl_custom_debug!(Cap {
+    raw,
+    capability,
+    subcommand
+});
 
-serde_struct!(Cap { raw, capability });
+serde_struct!(Cap {
+    raw,
+    capability,
+    subcommand
+});
 
 #[cfg(test)]
 mod tests {
@@ -96,6 +150,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cap_several() {
+        let input = ":tmi.twitch.tv CAP * NAK :twitch.tv/tags twitch.tv/foo\r\n";
+        let cap = Cap::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+        assert_eq!(cap.subcommand(), "NAK");
+        assert_eq!(
+            cap.capabilities().collect::<Vec<_>>(),
+            vec![
+                Capability::NotAcknowledged("twitch.tv/tags"),
+                Capability::NotAcknowledged("twitch.tv/foo")
+            ]
+        );
+    }
+
+    #[test]
+    fn cap_ls() {
+        let input = ":tmi.twitch.tv CAP * LS * :twitch.tv/tags twitch.tv/commands\r\n\
+                     :tmi.twitch.tv CAP * LS :twitch.tv/membership\r\n";
+        let caps = parse(input)
+            .map(|msg| Cap::from_irc(msg.unwrap()).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(caps[0].has_more());
+        assert!(!caps[1].has_more());
+        let available = caps
+            .iter()
+            .flat_map(|cap| cap.capabilities())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            available,
+            vec![
+                Capability::Available("twitch.tv/tags"),
+                Capability::Available("twitch.tv/commands"),
+                Capability::Available("twitch.tv/membership"),
+            ]
+        );
+    }
+
     #[test]
     fn cap_failed() {
         let input = ":tmi.twitch.tv CAP * NAK :foobar\r\n";
@@ -15,11 +15,11 @@ impl<'a> ClearChat<'a> {
     raw!();
     tags!();
 
-    str_field!(
+    channel_field!(
         /// The channel this event happened on
         channel
     );
-    opt_str_field!(
+    opt_login_field!(
         /// The user, if any, that was being purged
         name
     );
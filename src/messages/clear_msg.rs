@@ -1,4 +1,4 @@
-use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+use crate::{irc::*, twitch::Login, MaybeOwned, MaybeOwnedIndex, Validator};
 
 /// When a single message has been removed from a channel.
 ///
@@ -14,7 +14,7 @@ pub struct ClearMsg<'a> {
 impl<'a> ClearMsg<'a> {
     raw!();
     tags!();
-    str_field!(
+    channel_field!(
         /// The channel this event happened on
         channel
     );
@@ -24,8 +24,8 @@ impl<'a> ClearMsg<'a> {
     );
 
     /// Name of the user who sent the message
-    pub fn login(&self) -> Option<&str> {
-        self.tags().get("login")
+    pub fn login(&self) -> Option<Login<'_>> {
+        self.tags().get("login").map(Login::from_server)
     }
 
     /// UUID of the message
@@ -45,6 +45,26 @@ pub enum Commands<'a> {
     UserState(UserState<'a>),
     /// A Whisper event occured
     Whisper(Whisper<'a>),
+    /// A Names event occured
+    Names(Names<'a>),
+    /// An EndOfNames event occured
+    EndOfNames(EndOfNames<'a>),
+    /// An UnknownCommand event occured
+    UnknownCommand(UnknownCommand<'a>),
+    /// A YourHost event occured
+    YourHost(YourHost<'a>),
+    /// A Created event occured
+    Created(Created<'a>),
+    /// A MyInfo event occured
+    MyInfo(MyInfo<'a>),
+    /// An ISupport event occured
+    ISupport(ISupport<'a>),
+    /// A MotdStart event occured
+    MotdStart(MotdStart<'a>),
+    /// A Motd event occured
+    Motd(Motd<'a>),
+    /// Any other numeric reply occured
+    Numeric(Numeric<'a>),
 }
 
 impl<'a> Commands<'a> {
@@ -70,6 +90,16 @@ impl<'a> Commands<'a> {
             Self::UserNotice(msg) => msg.raw(),
             Self::UserState(msg) => msg.raw(),
             Self::Whisper(msg) => msg.raw(),
+            Self::Names(msg) => msg.raw(),
+            Self::EndOfNames(msg) => msg.raw(),
+            Self::UnknownCommand(msg) => msg.raw(),
+            Self::YourHost(msg) => msg.raw(),
+            Self::Created(msg) => msg.raw(),
+            Self::MyInfo(msg) => msg.raw(),
+            Self::ISupport(msg) => msg.raw(),
+            Self::MotdStart(msg) => msg.raw(),
+            Self::Motd(msg) => msg.raw(),
+            Self::Numeric(msg) => msg.raw(),
         }
     }
 }
@@ -98,6 +128,16 @@ impl<'a> IntoOwned<'a> for Commands<'a> {
             Self::UserNotice(s) => Commands::UserNotice(s.into_owned()),
             Self::UserState(s) => Commands::UserState(s.into_owned()),
             Self::Whisper(s) => Commands::Whisper(s.into_owned()),
+            Self::Names(s) => Commands::Names(s.into_owned()),
+            Self::EndOfNames(s) => Commands::EndOfNames(s.into_owned()),
+            Self::UnknownCommand(s) => Commands::UnknownCommand(s.into_owned()),
+            Self::YourHost(s) => Commands::YourHost(s.into_owned()),
+            Self::Created(s) => Commands::Created(s.into_owned()),
+            Self::MyInfo(s) => Commands::MyInfo(s.into_owned()),
+            Self::ISupport(s) => Commands::ISupport(s.into_owned()),
+            Self::MotdStart(s) => Commands::MotdStart(s.into_owned()),
+            Self::Motd(s) => Commands::Motd(s.into_owned()),
+            Self::Numeric(s) => Commands::Numeric(s.into_owned()),
         }
     }
 }
@@ -112,6 +152,16 @@ impl<'a> FromIrcMessage<'a> for Commands<'a> {
             };
         }
 
+        // these used to be raw messages, so keep them raw if they're malformed
+        macro_rules! numeric {
+            ($ident:ident) => {
+                match $ident::from_irc(msg.clone()) {
+                    Ok(numeric) => Self::$ident(numeric),
+                    Err(..) => Self::Raw(msg),
+                }
+            };
+        }
+
         use IrcMessage as M;
         let this = match msg.get_command() {
             M::IRC_READY => map!(IrcReady),
@@ -132,6 +182,16 @@ impl<'a> FromIrcMessage<'a> for Commands<'a> {
             M::USER_NOTICE => map!(UserNotice),
             M::USER_STATE => map!(UserState),
             M::WHISPER => map!(Whisper),
+            M::NAMES => numeric!(Names),
+            M::END_OF_NAMES => numeric!(EndOfNames),
+            M::UNKNOWN_COMMAND => numeric!(UnknownCommand),
+            M::YOUR_HOST => numeric!(YourHost),
+            M::CREATED => numeric!(Created),
+            M::MY_INFO => numeric!(MyInfo),
+            M::I_SUPPORT => numeric!(ISupport),
+            M::MOTD_START => numeric!(MotdStart),
+            M::MOTD => numeric!(Motd),
+            _ if msg.is_numeric() => numeric!(Numeric),
             _ => Self::Raw(IrcMessage::from_irc(msg).expect("infallible conversion")),
         };
 
@@ -160,6 +220,16 @@ impl<'a> FromIrcMessage<'a> for Commands<'a> {
             Self::UserNotice(msg) => msg.into_inner(),
             Self::UserState(msg) => msg.into_inner(),
             Self::Whisper(msg) => msg.into_inner(),
+            Self::Names(msg) => msg.into_inner(),
+            Self::EndOfNames(msg) => msg.into_inner(),
+            Self::UnknownCommand(msg) => msg.into_inner(),
+            Self::YourHost(msg) => msg.into_inner(),
+            Self::Created(msg) => msg.into_inner(),
+            Self::MyInfo(msg) => msg.into_inner(),
+            Self::ISupport(msg) => msg.into_inner(),
+            Self::MotdStart(msg) => msg.into_inner(),
+            Self::Motd(msg) => msg.into_inner(),
+            Self::Numeric(msg) => msg.into_inner(),
         }
     }
 }
@@ -196,6 +266,16 @@ from_other! {
     UserNotice
     UserState
     Whisper
+    Names
+    EndOfNames
+    UnknownCommand
+    YourHost
+    Created
+    MyInfo
+    ISupport
+    MotdStart
+    Motd
+    Numeric
 }
 
 #[cfg(test)]
@@ -215,6 +295,60 @@ mod tests {
         let input = ":test!test@test PRIVMSG #museun :this is a test\r\n";
         let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
         let all = Commands::from_irc(msg).unwrap();
-        assert!(matches!(all, Commands::Privmsg{..}));
+        assert!(matches!(all, Commands::Privmsg { .. }));
+    }
+
+    #[test]
+    fn numerics() {
+        fn parse(input: &str) -> Commands<'_> {
+            let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+            Commands::from_irc(msg).unwrap()
+        }
+
+        let all = parse(":tmi.twitch.tv 001 museun :Welcome, GLHF!\r\n");
+        assert!(matches!(all, Commands::IrcReady(..)));
+        let all = parse(":tmi.twitch.tv 376 museun :>\r\n");
+        assert!(matches!(all, Commands::Ready(..)));
+
+        let all = parse(":museun.tmi.twitch.tv 353 museun = #museun :museun\r\n");
+        assert!(matches!(all, Commands::Names(..)));
+        let all = parse(":museun.tmi.twitch.tv 366 museun #museun :End of /NAMES list\r\n");
+        assert!(matches!(all, Commands::EndOfNames(..)));
+        let all = parse(":tmi.twitch.tv 421 museun WHO :Unknown command\r\n");
+        assert!(matches!(all, Commands::UnknownCommand(..)));
+
+        let all = parse(":tmi.twitch.tv 002 museun :Your host is tmi.twitch.tv\r\n");
+        assert!(matches!(all, Commands::YourHost(..)));
+        let all = parse(":tmi.twitch.tv 003 museun :This server is rather new\r\n");
+        assert!(matches!(all, Commands::Created(..)));
+        let all = parse(":tmi.twitch.tv 004 museun :-\r\n");
+        assert!(matches!(all, Commands::MyInfo(..)));
+        let all = parse(":tmi.twitch.tv 005 museun CHANTYPES=# :are supported by this server\r\n");
+        assert!(matches!(all, Commands::ISupport(..)));
+        let all = parse(":tmi.twitch.tv 375 museun :-\r\n");
+        assert!(matches!(all, Commands::MotdStart(..)));
+        let all = parse(":tmi.twitch.tv 372 museun :You are in a maze of twisty passages.\r\n");
+        assert!(matches!(all, Commands::Motd(..)));
+
+        let all = parse(":tmi.twitch.tv 042 museun :-\r\n");
+        assert!(matches!(all, Commands::Numeric(..)));
+
+        let all = parse(":tmi.twitch.tv FOO museun\r\n");
+        assert!(matches!(all, Commands::Raw(..)));
+    }
+
+    #[test]
+    fn malformed_numerics() {
+        for input in &[
+            ":tmi.twitch.tv 002\r\n",
+            ":tmi.twitch.tv 372\r\n",
+            ":tmi.twitch.tv 421 museun\r\n",
+            ":tmi.twitch.tv 353 museun\r\n",
+            ":tmi.twitch.tv 366 museun\r\n",
+        ] {
+            let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+            let all = Commands::from_irc(msg).unwrap();
+            assert!(matches!(all, Commands::Raw(..)), "{}", input);
+        }
     }
 }
@@ -33,6 +33,11 @@ impl<'a> GlobalUserState<'a> {
             .unwrap_or_default()
     }
 
+    /// All of the badges, and their metadata, that you have
+    pub fn badge_set(&self) -> BadgeSet<'_> {
+        BadgeSet::from_tags(&self.tags())
+    }
+
     /// Your user-id -- only available if you have TAGs enabled
     pub fn user_id(&self) -> Option<&str> {
         self.user_id.as_deref()
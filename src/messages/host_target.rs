@@ -24,7 +24,7 @@ pub struct HostTarget<'a> {
 
 impl<'a> HostTarget<'a> {
     raw!();
-    str_field!(
+    channel_field!(
         /// Source channel (the one doing the hosting).
         source
     );
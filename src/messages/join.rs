@@ -12,11 +12,11 @@ pub struct Join<'a> {
 
 impl<'a> Join<'a> {
     raw!();
-    str_field!(
+    login_field!(
         /// Name of the user that joined the channel
         name
     );
-    str_field!(
+    channel_field!(
         /// Channel which they joined
         channel
     );
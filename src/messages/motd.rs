@@ -0,0 +1,144 @@
+use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+
+/// The start of the message of the day -- `375`
+///
+/// This is followed by one or more [Motd] lines, and then [Ready].
+///
+/// [Motd]: ./struct.Motd.html
+/// [Ready]: ./struct.Ready.html
+#[derive(Clone, PartialEq)]
+pub struct MotdStart<'a> {
+    raw: MaybeOwned<'a>,
+    target: MaybeOwnedIndex,
+    message: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> MotdStart<'a> {
+    raw!();
+    str_field!(
+        /// The name the reply was sent to, this is your own name
+        target
+    );
+    opt_str_field!(
+        /// The trailing message, Twitch only sends `-`
+        message
+    );
+}
+
+impl<'a> FromIrcMessage<'a> for MotdStart<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::MOTD_START)?;
+
+        let this = Self {
+            target: msg.expect_arg_index(0)?,
+            message: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(MotdStart {
+    raw,
+    target,
+    message,
+});
+impl_custom_debug!(MotdStart {
+    raw,
+    target,
+    message,
+});
+serde_struct!(MotdStart {
+    raw,
+    target,
+    message,
+});
+
+/// A line of the message of the day -- `372`
+#[derive(Clone, PartialEq)]
+pub struct Motd<'a> {
+    raw: MaybeOwned<'a>,
+    target: MaybeOwnedIndex,
+    line: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> Motd<'a> {
+    raw!();
+    str_field!(
+        /// The name the reply was sent to, this is your own name
+        target
+    );
+    opt_str_field!(
+        /// The line of the message of the day
+        line
+    );
+}
+
+impl<'a> FromIrcMessage<'a> for Motd<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::MOTD)?;
+
+        let this = Self {
+            target: msg.expect_arg_index(0)?,
+            line: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(Motd { raw, target, line });
+impl_custom_debug!(Motd { raw, target, line });
+serde_struct!(Motd { raw, target, line });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn motd_serde() {
+        let input = ":tmi.twitch.tv 375 shaken_bot :-\r\n";
+        crate::serde::round_trip_json::<MotdStart>(input);
+        crate::serde::round_trip_rmp::<MotdStart>(input);
+
+        let input = ":tmi.twitch.tv 372 shaken_bot :You are in a maze of twisty passages.\r\n";
+        crate::serde::round_trip_json::<Motd>(input);
+        crate::serde::round_trip_rmp::<Motd>(input);
+    }
+
+    #[test]
+    fn motd_start() {
+        let input = ":tmi.twitch.tv 375 shaken_bot :-\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = MotdStart::from_irc(msg).unwrap();
+            assert_eq!(msg.target(), "shaken_bot");
+            assert_eq!(msg.message(), Some("-"));
+        }
+    }
+
+    #[test]
+    fn motd() {
+        let input = ":tmi.twitch.tv 372 shaken_bot :You are in a maze of twisty passages.\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Motd::from_irc(msg).unwrap();
+            assert_eq!(msg.target(), "shaken_bot");
+            assert_eq!(msg.line(), Some("You are in a maze of twisty passages."));
+        }
+
+        let input = ":tmi.twitch.tv 372\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            assert!(Motd::from_irc(msg).is_err());
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+
+/// A list of users in a channel
+///
+/// This is sent after you join a channel, with the `membership` capability
+/// enabled. Larger channels are split across several of these, followed by
+/// an [EndOfNames].
+///
+/// [EndOfNames]: ./struct.EndOfNames.html
+#[derive(Clone, PartialEq)]
+pub struct Names<'a> {
+    raw: MaybeOwned<'a>,
+    channel: MaybeOwnedIndex,
+    names: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> Names<'a> {
+    raw!();
+    channel_field!(
+        /// Channel the users are in
+        channel
+    );
+
+    /// The names of the users
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.names
+            .map(|index| &self.raw[index])
+            .unwrap_or_default()
+            .split_ascii_whitespace()
+    }
+}
+
+impl<'a> FromIrcMessage<'a> for Names<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::NAMES)?;
+
+        let this = Self {
+            channel: msg.expect_arg_index(2)?,
+            names: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(Names {
+    raw,
+    channel,
+    names,
+});
+impl_custom_debug!(Names { raw, channel });
+serde_struct!(Names { raw, channel });
+
+/// The end of the list of users in a channel
+///
+/// This follows the [Names] messages for a channel.
+///
+/// [Names]: ./struct.Names.html
+#[derive(Clone, PartialEq)]
+pub struct EndOfNames<'a> {
+    raw: MaybeOwned<'a>,
+    channel: MaybeOwnedIndex,
+}
+
+impl<'a> EndOfNames<'a> {
+    raw!();
+    channel_field!(
+        /// Channel the list was for
+        channel
+    );
+}
+
+impl<'a> FromIrcMessage<'a> for EndOfNames<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::END_OF_NAMES)?;
+
+        let this = Self {
+            channel: msg.expect_arg_index(1)?,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(EndOfNames { raw, channel });
+impl_custom_debug!(EndOfNames { raw, channel });
+serde_struct!(EndOfNames { raw, channel });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn names_serde() {
+        let input = ":shaken_bot.tmi.twitch.tv 353 shaken_bot = #museun :museun shaken_bot\r\n";
+        crate::serde::round_trip_json::<Names>(input);
+        crate::serde::round_trip_rmp::<Names>(input);
+
+        let input = ":shaken_bot.tmi.twitch.tv 366 shaken_bot #museun :End of /NAMES list\r\n";
+        crate::serde::round_trip_json::<EndOfNames>(input);
+        crate::serde::round_trip_rmp::<EndOfNames>(input);
+    }
+
+    #[test]
+    fn names() {
+        let input = ":shaken_bot.tmi.twitch.tv 353 shaken_bot = #museun :museun shaken_bot\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Names::from_irc(msg).unwrap();
+            assert_eq!(msg.channel(), "#museun");
+            assert_eq!(
+                msg.names().collect::<Vec<_>>(),
+                vec!["museun", "shaken_bot"]
+            );
+        }
+
+        let input = ":shaken_bot.tmi.twitch.tv 353 shaken_bot = #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Names::from_irc(msg).unwrap();
+            assert_eq!(msg.names().count(), 0);
+        }
+    }
+
+    #[test]
+    fn end_of_names() {
+        let input = ":shaken_bot.tmi.twitch.tv 366 shaken_bot #museun :End of /NAMES list\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = EndOfNames::from_irc(msg).unwrap();
+            assert_eq!(msg.channel(), "#museun");
+        }
+    }
+}
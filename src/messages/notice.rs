@@ -12,10 +12,15 @@ pub struct Notice<'a> {
 impl<'a> Notice<'a> {
     raw!();
     tags!();
-    str_field!(
-        /// The channel this event happened on
-        channel
-    );
+    /// The channel this event happened on
+    ///
+    /// This is `None` for the notices that aren't about a channel, which are sent to `*`.
+    pub fn channel(&self) -> Option<crate::twitch::ChannelName<'_>> {
+        match &self.raw[self.channel] {
+            "*" => None,
+            channel => Some(crate::twitch::ChannelName::from_server(channel)),
+        }
+    }
     str_field!(
         /// The message from the server
         message
@@ -597,8 +602,14 @@ mod tests {
         let input = ":tmi.twitch.tv NOTICE #museun :This room is no longer in slow mode.\r\n";
         for msg in parse(input).map(|s| s.unwrap()) {
             let msg = Notice::from_irc(msg).unwrap();
-            assert_eq!(msg.channel(), "#museun");
+            assert_eq!(msg.channel().unwrap(), "#museun");
             assert_eq!(msg.message(), "This room is no longer in slow mode.");
         }
+
+        let input = ":tmi.twitch.tv NOTICE * :Login authentication failed\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Notice::from_irc(msg).unwrap();
+            assert!(msg.channel().is_none());
+        }
     }
 }
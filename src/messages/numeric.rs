@@ -0,0 +1,147 @@
+use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+
+/// A numeric reply from the server, e.g. `042`
+///
+/// Any numeric can be read as this. The numerics with their own type, such
+/// as [IrcReady], [Motd] or [Names], aren't turned into this by [Commands].
+///
+/// ```
+/// # use twitchchat::{irc::parse_one, messages::Numeric, FromIrcMessage as _};
+/// let input = ":tmi.twitch.tv 372 shaken_bot :You are in a maze of twisty passages.\r\n";
+/// let (_, msg) = parse_one(input).unwrap();
+/// let msg = Numeric::from_irc(msg).unwrap();
+/// assert_eq!(msg.code(), 372);
+/// assert_eq!(msg.target(), "shaken_bot");
+/// assert_eq!(msg.data(), Some("You are in a maze of twisty passages."));
+/// ```
+///
+/// [IrcReady]: ./struct.IrcReady.html
+/// [Motd]: ./struct.Motd.html
+/// [Names]: ./struct.Names.html
+/// [Commands]: ./enum.Commands.html
+#[derive(Clone, PartialEq)]
+pub struct Numeric<'a> {
+    raw: MaybeOwned<'a>,
+    code: MaybeOwnedIndex,
+    target: MaybeOwnedIndex,
+    args: MaybeOwnedIndex,
+    data: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> Numeric<'a> {
+    raw!();
+    str_field!(
+        /// The name the reply was sent to, this is usually your own name
+        target
+    );
+    opt_str_field!(
+        /// The trailing message, if there was one
+        data
+    );
+
+    /// The numeric code of this reply
+    pub fn code(&self) -> u16 {
+        self.raw[self.code]
+            .parse()
+            .expect("a numeric must be 3 digits")
+    }
+
+    /// The arguments after the target
+    pub fn params(&self) -> impl Iterator<Item = &str> + '_ {
+        self.raw[self.args].split_ascii_whitespace().skip(1)
+    }
+}
+
+impl<'a> FromIrcMessage<'a> for Numeric<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        if !msg.is_numeric() {
+            return Err(MessageError::InvalidCommand {
+                expected: "a numeric".to_string(),
+                got: msg.get_command().to_string(),
+            });
+        }
+
+        let this = Self {
+            code: msg.command,
+            target: msg.expect_arg_index(0)?,
+            args: msg.args.expect("an arg was parsed"),
+            data: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(Numeric {
+    raw,
+    code,
+    target,
+    args,
+    data,
+});
+impl_custom_debug!(Numeric {
+    raw,
+    code,
+    target,
+    data,
+});
+serde_struct!(Numeric {
+    raw,
+    code,
+    target,
+    data,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn numeric_serde() {
+        let input = ":tmi.twitch.tv 002 shaken_bot :Your host is tmi.twitch.tv\r\n";
+        crate::serde::round_trip_json::<Numeric>(input);
+        crate::serde::round_trip_rmp::<Numeric>(input);
+    }
+
+    #[test]
+    fn numeric() {
+        let input = ":tmi.twitch.tv 004 shaken_bot tmi.twitch.tv 1.0 :-\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Numeric::from_irc(msg).unwrap();
+            assert_eq!(msg.code(), 4);
+            assert_eq!(msg.target(), "shaken_bot");
+            assert_eq!(
+                msg.params().collect::<Vec<_>>(),
+                vec!["tmi.twitch.tv", "1.0"]
+            );
+            assert_eq!(msg.data(), Some("-"));
+        }
+
+        let input = ":tmi.twitch.tv 375 shaken_bot\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Numeric::from_irc(msg).unwrap();
+            assert_eq!(msg.code(), 375);
+            assert_eq!(msg.params().count(), 0);
+            assert_eq!(msg.data(), None);
+        }
+    }
+
+    #[test]
+    fn not_numeric() {
+        for input in &[
+            ":tmi.twitch.tv PING :1234\r\n",
+            ":tmi.twitch.tv 1234 shaken_bot\r\n",
+            ":tmi.twitch.tv 00a shaken_bot\r\n",
+        ] {
+            for msg in parse(input).map(|s| s.unwrap()) {
+                assert!(Numeric::from_irc(msg).is_err(), "{}", input);
+            }
+        }
+    }
+}
@@ -12,11 +12,11 @@ pub struct Part<'a> {
 
 impl<'a> Part<'a> {
     raw!();
-    str_field!(
+    login_field!(
         /// Name of the user that left the channel
         name
     );
-    str_field!(
+    channel_field!(
         /// Channel which they left
         channel
     );
@@ -1,7 +1,8 @@
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 
 use crate::twitch::{
-    parse_badges, parse_badges_iter, parse_emotes, Badge, BadgeInfo, BadgeKind, Color, Emotes,
+    parse_badge_info, parse_badges, parse_badges_iter, parse_emotes, parse_flags, Badge, BadgeInfo,
+    BadgeKind, BadgeSet, Color, Emotes, Flags,
 };
 
 /// Some PRIVMSGs are considered 'CTCP' (client-to-client protocol)
@@ -19,6 +20,48 @@ pub enum Ctcp<'a> {
     },
 }
 
+/// The message that a [`Privmsg`](./struct.Privmsg.html) was replying to.
+///
+/// This is retrieved via [`Privmsg::reply_parent`](./struct.Privmsg.html#method.reply_parent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct ReplyParent<'a> {
+    /// The id (UUID) of the message being replied to
+    pub msg_id: &'a str,
+    /// The id of the user who sent the message being replied to
+    pub user_id: Option<u64>,
+    /// The login of the user who sent the message being replied to
+    pub login: Option<&'a str>,
+    /// The display name of the user who sent the message being replied to
+    pub display_name: Option<&'a str>,
+    /// The (unescaped) body of the message being replied to
+    pub body: Option<String>,
+    /// The id (UUID) of the message that started this reply thread
+    pub thread_parent_msg_id: Option<&'a str>,
+    /// The login of the user who started this reply thread
+    pub thread_parent_login: Option<&'a str>,
+}
+
+/// A paid (Hype Chat) message that was pinned to the channel.
+///
+/// This is retrieved via [`Privmsg::pinned_chat`](./struct.Privmsg.html#method.pinned_chat)
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct PinnedChat<'a> {
+    /// The amount paid, in the currency's smallest unit
+    pub amount: u64,
+    /// The ISO 4217 code of the currency
+    pub currency: &'a str,
+    /// The number of decimal places used by the currency.
+    ///
+    /// e.g. an `amount` of `500` with an `exponent` of `2` is `5.00`
+    pub exponent: u32,
+    /// The level of the Hype Chat, from `1` to `10`, if known
+    pub level: Option<u8>,
+    /// Whether this was sent by the system, rather than the user
+    pub is_system_message: bool,
+}
+
 /// Message sent by a user
 #[derive(Clone, PartialEq)]
 pub struct Privmsg<'a> {
@@ -33,11 +76,11 @@ pub struct Privmsg<'a> {
 impl<'a> Privmsg<'a> {
     raw!();
     tags!();
-    str_field!(
+    login_field!(
         /// User who sent this messages
         name
     );
-    str_field!(
+    channel_field!(
         /// Channel this message was sent on
         channel
     );
@@ -69,7 +112,7 @@ impl<'a> Privmsg<'a> {
     pub fn badge_info(&'a self) -> Vec<BadgeInfo<'a>> {
         self.tags()
             .get("badge-info")
-            .map(parse_badges)
+            .map(parse_badge_info)
             .unwrap_or_default()
     }
 
@@ -81,6 +124,13 @@ impl<'a> Privmsg<'a> {
             .unwrap_or_default()
     }
 
+    /// All of the badges, and their metadata, attached to this message
+    ///
+    /// This parses the tags once, so prefer this for multiple lookups.
+    pub fn badge_set(&self) -> BadgeSet<'_> {
+        BadgeSet::from_tags(&self.tags())
+    }
+
     /// How many bits were attached to this message
     pub fn bits(&self) -> Option<u64> {
         self.tags().get_parsed("bits")
@@ -124,7 +174,7 @@ impl<'a> Privmsg<'a> {
 
     /// Whether the user sending this message was a vip
     pub fn is_vip(&self) -> bool {
-        self.contains_badge(BadgeKind::Broadcaster)
+        self.contains_badge(BadgeKind::VIP)
     }
 
     /// Whether the user sending this message was a susbcriber
@@ -180,6 +230,83 @@ impl<'a> Privmsg<'a> {
         self.tags().get("msg-id")
     }
 
+    /// Whether this is the first message the user has sent in this channel
+    pub fn is_first_message(&self) -> bool {
+        self.tags().get_as_bool("first-msg")
+    }
+
+    /// Whether this user is a returning chatter
+    pub fn is_returning_chatter(&self) -> bool {
+        self.tags().get_as_bool("returning-chatter")
+    }
+
+    /// Whether this message was composed only of emotes
+    pub fn is_emote_only(&self) -> bool {
+        self.tags().get_as_bool("emote-only")
+    }
+
+    /// A unique token that the sending client attached to this message
+    pub fn client_nonce(&self) -> Option<&str> {
+        self.tags().get("client-nonce")
+    }
+
+    /// AutoMod classifications attached to this message
+    pub fn flags(&self) -> Vec<Flags> {
+        self.tags()
+            .get("flags")
+            .map(parse_flags)
+            .unwrap_or_default()
+    }
+
+    /// The paid (Hype Chat) information, if this message was pinned
+    pub fn pinned_chat(&self) -> Option<PinnedChat<'_>> {
+        let tags = self.tags();
+        let level = match tags.get("pinned-chat-paid-level") {
+            Some("ONE") => Some(1),
+            Some("TWO") => Some(2),
+            Some("THREE") => Some(3),
+            Some("FOUR") => Some(4),
+            Some("FIVE") => Some(5),
+            Some("SIX") => Some(6),
+            Some("SEVEN") => Some(7),
+            Some("EIGHT") => Some(8),
+            Some("NINE") => Some(9),
+            Some("TEN") => Some(10),
+            _ => None,
+        };
+
+        let pinned = PinnedChat {
+            amount: tags.get_parsed("pinned-chat-paid-amount")?,
+            currency: tags.get("pinned-chat-paid-currency")?,
+            exponent: tags.get_parsed("pinned-chat-paid-exponent").unwrap_or(0),
+            level,
+            is_system_message: tags.get_as_bool("pinned-chat-paid-is-system-message"),
+        };
+        Some(pinned)
+    }
+
+    /// The message this message was replying to, if it was a reply
+    ///
+    /// This requires the `TAGS` capability
+    pub fn reply_parent(&self) -> Option<ReplyParent<'_>> {
+        let tags = self.tags();
+        let parent = ReplyParent {
+            msg_id: tags.get("reply-parent-msg-id")?,
+            user_id: tags.get_parsed("reply-parent-user-id"),
+            login: tags.get("reply-parent-user-login"),
+            display_name: tags.get("reply-parent-display-name"),
+            body: tags.get_unescaped("reply-parent-msg-body"),
+            thread_parent_msg_id: tags.get("reply-thread-parent-msg-id"),
+            thread_parent_login: tags.get("reply-thread-parent-user-login"),
+        };
+        Some(parent)
+    }
+
+    /// Whether this message was a reply to another message
+    pub fn is_reply(&self) -> bool {
+        self.tags().get("reply-parent-msg-id").is_some()
+    }
+
     fn contains_badge(&self, badge: BadgeKind<'_>) -> bool {
         self.tags()
             .get("badges")
@@ -351,6 +478,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn privmsg_badges() {
+        let input = "@badge-info=subscriber/17;badges=vip/1,subscriber/3012,bits/100 :test!user@host PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.is_vip());
+            assert!(msg.is_subscriber());
+            assert!(!msg.is_broadcaster());
+
+            let set = msg.badge_set();
+            assert_eq!(set.len(), 3);
+            assert_eq!(set.bits(), Some(100));
+            assert_eq!(set.subscriber().unwrap().months, 17);
+
+            assert_eq!(msg.badge_info()[0].months(), Some(17));
+        }
+    }
+
+    #[test]
+    fn privmsg_reply_parent() {
+        let input = "@reply-parent-display-name=Shaken_Bot;reply-parent-msg-body=hello\\smuseun!\\:);reply-parent-msg-id=1b136720-3a9a-4805-ab60-8c083e9f6fd2;reply-parent-user-id=241015868;reply-parent-user-login=shaken_bot;reply-thread-parent-msg-id=0e4fd8b8-d3bb-4b2c-8de8-c8a7e1e0b2c1;reply-thread-parent-user-login=museun :test!user@host PRIVMSG #museun :@shaken_bot hi\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.is_reply());
+
+            let parent = msg.reply_parent().unwrap();
+            assert_eq!(parent.msg_id, "1b136720-3a9a-4805-ab60-8c083e9f6fd2");
+            assert_eq!(parent.user_id, Some(241015868));
+            assert_eq!(parent.login, Some("shaken_bot"));
+            assert_eq!(parent.display_name, Some("Shaken_Bot"));
+            assert_eq!(parent.body.as_deref(), Some("hello museun!;)"));
+            assert_eq!(
+                parent.thread_parent_msg_id,
+                Some("0e4fd8b8-d3bb-4b2c-8de8-c8a7e1e0b2c1")
+            );
+            assert_eq!(parent.thread_parent_login, Some("museun"));
+        }
+
+        let input = ":test!user@host PRIVMSG #museun :not a reply\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(!msg.is_reply());
+            assert!(msg.reply_parent().is_none());
+        }
+    }
+
+    #[test]
+    fn privmsg_metadata_flags() {
+        let input = "@first-msg=1;returning-chatter=0;emote-only=1;client-nonce=b0d4a5b2c1;flags=0-5:A.3/P.6 :test!user@host PRIVMSG #museun :Kappa\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.is_first_message());
+            assert!(!msg.is_returning_chatter());
+            assert!(msg.is_emote_only());
+            assert_eq!(msg.client_nonce(), Some("b0d4a5b2c1"));
+
+            let flags = msg.flags();
+            assert_eq!(flags.len(), 1);
+            assert_eq!(flags[0].range, 0..5);
+            assert_eq!(flags[0].categories.len(), 2);
+            assert!(msg.pinned_chat().is_none());
+        }
+
+        let input = ":test!user@host PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(!msg.is_first_message());
+            assert!(!msg.is_returning_chatter());
+            assert!(!msg.is_emote_only());
+            assert!(msg.client_nonce().is_none());
+            assert!(msg.flags().is_empty());
+        }
+    }
+
+    #[test]
+    fn privmsg_pinned_chat() {
+        let input = "@pinned-chat-paid-amount=500;pinned-chat-paid-canonical-amount=5;pinned-chat-paid-currency=USD;pinned-chat-paid-exponent=2;pinned-chat-paid-is-system-message=0;pinned-chat-paid-level=ONE :test!user@host PRIVMSG #museun :hype\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let pinned = msg.pinned_chat().unwrap();
+            assert_eq!(pinned.amount, 500);
+            assert_eq!(pinned.currency, "USD");
+            assert_eq!(pinned.exponent, 2);
+            assert_eq!(pinned.level, Some(1));
+            assert!(!pinned.is_system_message);
+        }
+    }
+
     #[test]
     fn privmsg_community_rewards() {
         let input = "@custom-reward-id=abc-123-foo;msg-id=highlighted-message :test!user@host PRIVMSG #museun :Notice me!\r\n";
@@ -40,7 +40,7 @@ impl<'a> FromIrcMessage<'a> for RoomState<'a> {
 impl<'a> RoomState<'a> {
     raw!();
     tags!();
-    str_field!(
+    channel_field!(
         /// The channel that this event is happening on
         channel
     );
@@ -0,0 +1,85 @@
+use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+
+/// The server didn't recognize a command you sent
+///
+/// Twitch only supports a handful of IRC commands, anything else, e.g. a typo
+/// in a [raw] command, is answered with this. The runner also reports these
+/// as a [SendFailure::UnknownCommand].
+///
+/// [raw]: ../commands/fn.raw.html
+/// [SendFailure::UnknownCommand]: ../runner/enum.SendFailure.html#variant.UnknownCommand
+#[derive(Clone, PartialEq)]
+pub struct UnknownCommand<'a> {
+    raw: MaybeOwned<'a>,
+    command: MaybeOwnedIndex,
+    message: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> UnknownCommand<'a> {
+    raw!();
+    str_field!(
+        /// The command that was sent
+        command
+    );
+    opt_str_field!(
+        /// The reason the server gave, e.g. `Unknown command`
+        message
+    );
+}
+
+impl<'a> FromIrcMessage<'a> for UnknownCommand<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::UNKNOWN_COMMAND)?;
+
+        let this = Self {
+            command: msg.expect_arg_index(1)?,
+            message: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(UnknownCommand {
+    raw,
+    command,
+    message,
+});
+impl_custom_debug!(UnknownCommand {
+    raw,
+    command,
+    message,
+});
+serde_struct!(UnknownCommand {
+    raw,
+    command,
+    message,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn unknown_command_serde() {
+        let input = ":tmi.twitch.tv 421 shaken_bot WHO :Unknown command\r\n";
+        crate::serde::round_trip_json::<UnknownCommand>(input);
+        crate::serde::round_trip_rmp::<UnknownCommand>(input);
+    }
+
+    #[test]
+    fn unknown_command() {
+        let input = ":tmi.twitch.tv 421 shaken_bot WHO :Unknown command\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UnknownCommand::from_irc(msg).unwrap();
+            assert_eq!(msg.command(), "WHO");
+            assert_eq!(msg.message(), Some("Unknown command"));
+        }
+    }
+}
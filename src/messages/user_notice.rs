@@ -1,4 +1,6 @@
-use crate::twitch::{parse_badges, parse_emotes, Badge, BadgeInfo, Color, Emotes};
+use crate::twitch::{
+    parse_badge_info, parse_badges, parse_emotes, Badge, BadgeInfo, BadgeSet, Color, Emotes, Login,
+};
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 
 /// A paid subscription ot the channel
@@ -65,7 +67,7 @@ pub struct UserNotice<'a> {
 impl<'a> UserNotice<'a> {
     raw!();
     tags!();
-    str_field!(
+    channel_field!(
         /// The channel that this event is happening on
         channel
     );
@@ -80,7 +82,7 @@ impl<'a> UserNotice<'a> {
     pub fn badge_info(&'a self) -> Vec<BadgeInfo<'a>> {
         self.tags()
             .get("badge-info")
-            .map(parse_badges)
+            .map(parse_badge_info)
             .unwrap_or_default()
     }
 
@@ -92,6 +94,13 @@ impl<'a> UserNotice<'a> {
             .unwrap_or_default()
     }
 
+    /// All of the badges, and their metadata, attached to this message
+    ///
+    /// This parses the tags once, so prefer this for multiple lookups.
+    pub fn badge_set(&self) -> BadgeSet<'_> {
+        BadgeSet::from_tags(&self.tags())
+    }
+
     /// The user's color, if set
     pub fn color(&self) -> Option<Color> {
         self.tags().get_parsed("color")
@@ -118,8 +127,8 @@ impl<'a> UserNotice<'a> {
     }
 
     /// The name of the user who sent this notice
-    pub fn login(&self) -> Option<&str> {
-        self.tags().get("login")
+    pub fn login(&self) -> Option<Login<'_>> {
+        self.tags().get("login").map(Login::from_server)
     }
 
     /// Whether this user is a moderator
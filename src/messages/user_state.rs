@@ -1,4 +1,6 @@
-use crate::twitch::{parse_badges, parse_emotes, Badge, BadgeInfo, Color, Emotes};
+use crate::twitch::{
+    parse_badge_info, parse_badges, parse_emotes, Badge, BadgeInfo, BadgeSet, Color, Emotes,
+};
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 
 /// Identifies a user's chat settings or properties (e.g., chat color)..
@@ -12,7 +14,7 @@ pub struct UserState<'a> {
 impl<'a> UserState<'a> {
     raw!();
     tags!();
-    str_field!(
+    channel_field!(
         /// Channel this event happened on
         channel
     );
@@ -24,7 +26,7 @@ impl<'a> UserState<'a> {
     pub fn badge_info(&self) -> Vec<BadgeInfo<'_>> {
         self.tags()
             .get("badge-info")
-            .map(parse_badges)
+            .map(parse_badge_info)
             .unwrap_or_default()
     }
 
@@ -36,6 +38,13 @@ impl<'a> UserState<'a> {
             .unwrap_or_default()
     }
 
+    /// All of the badges, and their metadata, attached to this message
+    ///
+    /// This parses the tags once, so prefer this for multiple lookups.
+    pub fn badge_set(&self) -> BadgeSet<'_> {
+        BadgeSet::from_tags(&self.tags())
+    }
+
     /// The user's color, if set
     pub fn color(&self) -> Option<Color> {
         self.tags().get_parsed("color")
@@ -0,0 +1,335 @@
+use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+
+/// The server you're connected to -- `002`
+///
+/// This is sent right after [IrcReady].
+///
+/// [IrcReady]: ./struct.IrcReady.html
+#[derive(Clone, PartialEq)]
+pub struct YourHost<'a> {
+    raw: MaybeOwned<'a>,
+    target: MaybeOwnedIndex,
+    message: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> YourHost<'a> {
+    raw!();
+    str_field!(
+        /// The name the reply was sent to, this is your own name
+        target
+    );
+    opt_str_field!(
+        /// The message, e.g. `Your host is tmi.twitch.tv`
+        message
+    );
+}
+
+impl<'a> FromIrcMessage<'a> for YourHost<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::YOUR_HOST)?;
+
+        let this = Self {
+            target: msg.expect_arg_index(0)?,
+            message: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(YourHost {
+    raw,
+    target,
+    message,
+});
+impl_custom_debug!(YourHost {
+    raw,
+    target,
+    message,
+});
+serde_struct!(YourHost {
+    raw,
+    target,
+    message,
+});
+
+/// When the server was created -- `003`
+#[derive(Clone, PartialEq)]
+pub struct Created<'a> {
+    raw: MaybeOwned<'a>,
+    target: MaybeOwnedIndex,
+    message: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> Created<'a> {
+    raw!();
+    str_field!(
+        /// The name the reply was sent to, this is your own name
+        target
+    );
+    opt_str_field!(
+        /// The message, e.g. `This server is rather new`
+        message
+    );
+}
+
+impl<'a> FromIrcMessage<'a> for Created<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::CREATED)?;
+
+        let this = Self {
+            target: msg.expect_arg_index(0)?,
+            message: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(Created {
+    raw,
+    target,
+    message,
+});
+impl_custom_debug!(Created {
+    raw,
+    target,
+    message,
+});
+serde_struct!(Created {
+    raw,
+    target,
+    message,
+});
+
+/// Information about the server -- `004`
+///
+/// Twitch doesn't fill this in, it only sends `-` as the message.
+#[derive(Clone, PartialEq)]
+pub struct MyInfo<'a> {
+    raw: MaybeOwned<'a>,
+    target: MaybeOwnedIndex,
+    args: MaybeOwnedIndex,
+    message: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> MyInfo<'a> {
+    raw!();
+    str_field!(
+        /// The name the reply was sent to, this is your own name
+        target
+    );
+    opt_str_field!(
+        /// The trailing message, if there was one
+        message
+    );
+
+    /// The arguments after the target, e.g. the server name and version
+    pub fn params(&self) -> impl Iterator<Item = &str> + '_ {
+        self.raw[self.args].split_ascii_whitespace().skip(1)
+    }
+}
+
+impl<'a> FromIrcMessage<'a> for MyInfo<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::MY_INFO)?;
+
+        let this = Self {
+            target: msg.expect_arg_index(0)?,
+            args: msg.args.expect("an arg was parsed"),
+            message: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(MyInfo {
+    raw,
+    target,
+    args,
+    message,
+});
+impl_custom_debug!(MyInfo {
+    raw,
+    target,
+    message,
+});
+serde_struct!(MyInfo {
+    raw,
+    target,
+    message,
+});
+
+/// The features the server supports -- `005`
+///
+/// Each token is either a name, e.g. `EXCEPTS`, or a `name=value` pair, e.g. `CHANTYPES=#`.
+#[derive(Clone, PartialEq)]
+pub struct ISupport<'a> {
+    raw: MaybeOwned<'a>,
+    target: MaybeOwnedIndex,
+    args: MaybeOwnedIndex,
+    message: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> ISupport<'a> {
+    raw!();
+    str_field!(
+        /// The name the reply was sent to, this is your own name
+        target
+    );
+    opt_str_field!(
+        /// The trailing message, e.g. `are supported by this server`
+        message
+    );
+
+    /// The supported features, split into their name and optional value
+    pub fn tokens(&self) -> impl Iterator<Item = (&str, Option<&str>)> + '_ {
+        self.raw[self.args]
+            .split_ascii_whitespace()
+            .skip(1)
+            .map(|token| match token.find('=') {
+                Some(pos) => (&token[..pos], Some(&token[pos + 1..])),
+                None => (token, None),
+            })
+    }
+}
+
+impl<'a> FromIrcMessage<'a> for ISupport<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        msg.expect_command(IrcMessage::I_SUPPORT)?;
+
+        let this = Self {
+            target: msg.expect_arg_index(0)?,
+            args: msg.args.expect("an arg was parsed"),
+            message: msg.data,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(ISupport {
+    raw,
+    target,
+    args,
+    message,
+});
+impl_custom_debug!(ISupport {
+    raw,
+    target,
+    message,
+});
+serde_struct!(ISupport {
+    raw,
+    target,
+    message,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn welcome_serde() {
+        let input = ":tmi.twitch.tv 002 shaken_bot :Your host is tmi.twitch.tv\r\n";
+        crate::serde::round_trip_json::<YourHost>(input);
+        crate::serde::round_trip_rmp::<YourHost>(input);
+
+        let input = ":tmi.twitch.tv 003 shaken_bot :This server is rather new\r\n";
+        crate::serde::round_trip_json::<Created>(input);
+        crate::serde::round_trip_rmp::<Created>(input);
+
+        let input = ":tmi.twitch.tv 004 shaken_bot :-\r\n";
+        crate::serde::round_trip_json::<MyInfo>(input);
+        crate::serde::round_trip_rmp::<MyInfo>(input);
+
+        let input = ":tmi.twitch.tv 005 shaken_bot CHANTYPES=# :are supported by this server\r\n";
+        crate::serde::round_trip_json::<ISupport>(input);
+        crate::serde::round_trip_rmp::<ISupport>(input);
+    }
+
+    #[test]
+    fn your_host() {
+        let input = ":tmi.twitch.tv 002 shaken_bot :Your host is tmi.twitch.tv\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = YourHost::from_irc(msg).unwrap();
+            assert_eq!(msg.target(), "shaken_bot");
+            assert_eq!(msg.message(), Some("Your host is tmi.twitch.tv"));
+        }
+    }
+
+    #[test]
+    fn created() {
+        let input = ":tmi.twitch.tv 003 shaken_bot :This server is rather new\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Created::from_irc(msg).unwrap();
+            assert_eq!(msg.target(), "shaken_bot");
+            assert_eq!(msg.message(), Some("This server is rather new"));
+        }
+    }
+
+    #[test]
+    fn my_info() {
+        let input = ":tmi.twitch.tv 004 shaken_bot :-\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = MyInfo::from_irc(msg).unwrap();
+            assert_eq!(msg.target(), "shaken_bot");
+            assert_eq!(msg.params().count(), 0);
+            assert_eq!(msg.message(), Some("-"));
+        }
+    }
+
+    #[test]
+    fn i_support() {
+        let input =
+            ":tmi.twitch.tv 005 shaken_bot CHANTYPES=# EXCEPTS :are supported by this server\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = ISupport::from_irc(msg).unwrap();
+            assert_eq!(msg.target(), "shaken_bot");
+            assert_eq!(
+                msg.tokens().collect::<Vec<_>>(),
+                vec![("CHANTYPES", Some("#")), ("EXCEPTS", None)]
+            );
+            assert_eq!(msg.message(), Some("are supported by this server"));
+        }
+    }
+
+    #[test]
+    fn missing_target() {
+        for input in &[
+            ":tmi.twitch.tv 002\r\n",
+            ":tmi.twitch.tv 003\r\n",
+            ":tmi.twitch.tv 004\r\n",
+            ":tmi.twitch.tv 005\r\n",
+        ] {
+            for msg in parse(input).map(|s| s.unwrap()) {
+                assert!(YourHost::from_irc(msg.clone()).is_err());
+                assert!(Created::from_irc(msg.clone()).is_err());
+                assert!(MyInfo::from_irc(msg.clone()).is_err());
+                assert!(ISupport::from_irc(msg).is_err());
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::twitch::{
-    parse_badges, parse_badges_iter, parse_emotes, Badge, BadgeKind, Color, Emotes,
+    parse_badges, parse_badges_iter, parse_emotes, Badge, BadgeKind, BadgeSet, Color, Emotes,
 };
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 
@@ -15,7 +15,7 @@ pub struct Whisper<'a> {
 impl<'a> Whisper<'a> {
     raw!();
     tags!();
-    str_field!(
+    login_field!(
         /// User who sent this messages
         name
     );
@@ -50,6 +50,13 @@ impl<'a> Whisper<'a> {
             .unwrap_or_default()
     }
 
+    /// All of the badges, and their metadata, attached to this message
+    ///
+    /// This parses the tags once, so prefer this for multiple lookups.
+    pub fn badge_set(&self) -> BadgeSet<'_> {
+        BadgeSet::from_tags(&self.tags())
+    }
+
     /// Emotes attached to this message
     pub fn emotes(&self) -> Vec<Emotes> {
         self.tags()
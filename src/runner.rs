@@ -35,10 +35,31 @@ mod error;
 pub use error::Error;
 
 mod rate_limit;
+pub use rate_limit::{
+    DuplicateStrategy, DUPLICATE_SUFFIX, DUPLICATE_WINDOW, JOINS_PER_WINDOW, JOIN_WINDOW,
+};
+
 mod timeout;
 
+mod config;
+pub use config::{RunnerConfig, RunnerConfigBuilder, RunnerConfigError};
+
 mod channel;
 pub use channel::Channel;
 
+mod failure;
+pub use failure::SendFailure;
+
+mod command;
+
+mod whisper;
+pub use whisper::{WHISPERS_PER_MINUTE, WHISPERS_PER_SECOND, WHISPER_RECIPIENTS_PER_DAY};
+
+mod conversation;
+pub use conversation::{Conversation, Conversations};
+
+mod stats;
+pub use stats::{ChannelStats, RunnerStats};
+
 #[doc(inline)]
 pub use crate::util::NotifyHandle;
@@ -1,28 +1,34 @@
 use crate::{
-    channel::Receiver,
+    channel::{Receiver, Sender, TrySendError},
     commands,
     connector::Connector,
     decoder::{AsyncDecoder, DecodeError},
     encoder::{AsyncEncoder, Encodable},
     messages::{Capability, Commands, MessageId},
-    rate_limit::{RateClass, RateLimit},
-    twitch::UserConfig,
+    rate_limit::RateLimit,
+    twitch::{ChannelName, TokenProvider, UserConfig},
     util::{Notify, NotifyHandle},
-    writer::{AsyncWriter, MpscWriter},
+    writer::{AsyncWriter, MpscWriter, Outgoing, Priority},
     FromIrcMessage,
 };
 
 use super::{
     channel::Channels,
-    timeout::{TimeoutState, RATE_LIMIT_WINDOW, TIMEOUT, WINDOW},
-    Capabilities, Channel, Error, Identity, Status, StepResult,
+    command::CommandQueue,
+    rate_limit::{JOINS_PER_WINDOW, JOIN_WINDOW},
+    stats::{Counted, Counters},
+    timeout::TimeoutState,
+    whisper::WhisperQueue,
+    Capabilities, Channel, DuplicateStrategy, Error, Identity, RunnerConfig, RunnerStats,
+    SendFailure, Status, StepResult,
 };
 
 use futures_lite::{AsyncRead, AsyncWrite, AsyncWriteExt, Stream};
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, VecDeque},
     pin::Pin,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 /// An asynchronous runner
@@ -31,9 +37,14 @@ pub struct AsyncRunner {
     pub identity: Identity,
 
     channels: Channels,
+    whispers: WhisperQueue,
+    commands: CommandQueue,
 
     activity_rx: Receiver<()>,
-    writer_rx: Receiver<Box<[u8]>>,
+    lanes_rx: Receiver<(Priority, Outgoing)>,
+
+    failures_tx: Sender<SendFailure>,
+    failures_rx: Receiver<SendFailure>,
 
     notify: Notify,
     // why don't we use this?
@@ -46,8 +57,13 @@ pub struct AsyncRunner {
 
     writer: AsyncWriter<MpscWriter>,
     global_rate_limit: RateLimit,
+    join_rate_limit: RateLimit,
 
     missed_messages: VecDeque<Commands<'static>>,
+
+    counters: Counters,
+
+    config: RunnerConfig,
 }
 
 impl std::fmt::Debug for AsyncRunner {
@@ -61,6 +77,23 @@ impl AsyncRunner {
     ///
     /// This returns the Runner with your identity set.    
     pub async fn connect<C>(connector: C, user_config: &UserConfig) -> Result<Self, Error>
+    where
+        C: Connector,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        Self::connect_with_config(connector, user_config, &RunnerConfig::default()).await
+    }
+
+    /// Connect with the provided connector, the provided UserConfig and the provided [RunnerConfig]
+    ///
+    /// This returns the Runner with your identity set.
+    ///
+    /// [RunnerConfig]: ./struct.RunnerConfig.html
+    pub async fn connect_with_config<C>(
+        connector: C,
+        user_config: &UserConfig,
+        config: &RunnerConfig,
+    ) -> Result<Self, Error>
     where
         C: Connector,
         for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
@@ -69,15 +102,23 @@ impl AsyncRunner {
         let mut stream = { connector }.connect().await?;
         log::debug!("connection established");
 
+        let counters = Counters::default();
+
         log::debug!("registering");
         let mut buf = vec![];
         commands::register(user_config).encode(&mut buf)?;
         stream.write_all(&buf).await?;
+        counters
+            .bytes_sent
+            .fetch_add(buf.len() as u64, std::sync::atomic::Ordering::Relaxed);
         log::debug!("registered");
 
         let read = async_dup::Arc::new(stream);
         let write = read.clone();
 
+        let read = Counted::new(read, counters.bytes_received.clone());
+        let write = Counted::new(write, counters.bytes_sent.clone());
+
         let read: Box<dyn AsyncRead + Send + Sync + Unpin> = Box::new(read);
         let write: Box<dyn AsyncWrite + Send + Sync + Unpin> = Box::new(write);
 
@@ -94,24 +135,36 @@ impl AsyncRunner {
         )
         .await?;
         log::debug!("connection is ready: {:?}", identity);
+        limit_missed_messages(&mut missed_messages, config.missed_messages_limit);
 
-        let (writer_tx, writer_rx) = crate::channel::unbounded();
         let (notify, notify_handle) = Notify::new();
         let (activity_tx, activity_rx) = crate::channel::bounded(32);
+        let (failures_tx, failures_rx) = crate::channel::bounded(32);
+        let (lanes_tx, lanes_rx) = channel_with_capacity(config.writer_capacity);
 
-        let writer = AsyncWriter::new(MpscWriter::new(writer_tx), activity_tx);
+        // everything written is queued in the lanes
+        let writer =
+            AsyncWriter::new(MpscWriter::lanes(lanes_tx.clone()), activity_tx).with_lanes(lanes_tx);
 
         let timeout_state = TimeoutState::Start;
-        let channels = Channels::default();
+        let channels = Channels {
+            rate_class: config.rate_class,
+            ..Channels::default()
+        };
 
-        let global_rate_limit = RateLimit::from_class(RateClass::Regular);
+        let global_rate_limit = RateLimit::from_class(config.rate_class);
 
         Ok(Self {
             identity,
             channels,
+            whispers: WhisperQueue::default(),
+            commands: CommandQueue::default(),
 
             activity_rx,
-            writer_rx,
+            lanes_rx,
+
+            failures_tx,
+            failures_rx,
 
             notify,
             notify_handle,
@@ -123,11 +176,50 @@ impl AsyncRunner {
 
             writer,
             global_rate_limit,
+            join_rate_limit: RateLimit::full(JOINS_PER_WINDOW, JOIN_WINDOW),
 
             missed_messages,
+
+            counters,
+
+            config: config.clone(),
         })
     }
 
+    /// Connect with the provided connector and [RunnerConfig], asking the [TokenProvider] for a token
+    ///
+    /// The provider is asked for a token each time this is called, so a new
+    /// token will be used when you reconnect. The token in `user_config` is
+    /// ignored. Anonymous configs don't use the provider.
+    ///
+    /// This returns the Runner with your identity set.
+    ///
+    /// [RunnerConfig]: ./struct.RunnerConfig.html
+    /// [TokenProvider]: ../twitch/trait.TokenProvider.html
+    pub async fn connect_with_token_provider<C, P>(
+        connector: C,
+        user_config: &UserConfig,
+        config: &RunnerConfig,
+        provider: &P,
+    ) -> Result<Self, Error>
+    where
+        C: Connector,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+        P: TokenProvider + ?Sized,
+    {
+        if user_config.is_anonymous() {
+            return Self::connect_with_config(connector, user_config, config).await;
+        }
+
+        log::debug!("getting a token from the provider");
+        let token = provider.token().await.map_err(Error::TokenUnavailable)?;
+        let user_config = UserConfig {
+            token,
+            ..user_config.clone()
+        };
+        Self::connect_with_config(connector, &user_config, config).await
+    }
+
     /// Check whether you're on this channel
     pub fn is_on_channel(&self, channel: &str) -> bool {
         self.channels.is_on(channel)
@@ -145,6 +237,70 @@ impl AsyncRunner {
         self.writer.clone()
     }
 
+    /// Get a snapshot of what this runner has been doing
+    ///
+    /// With the `metrics` feature enabled, you can record this with [RunnerStats::record_metrics].
+    ///
+    /// [RunnerStats::record_metrics]: ./struct.RunnerStats.html#method.record_metrics
+    pub fn stats(&self) -> RunnerStats {
+        use std::sync::atomic::Ordering;
+
+        let queues = {
+            let mut stats = self.channels.queue_stats();
+            stats += self.whispers.stats;
+            stats += self.commands.stats;
+            stats
+        };
+
+        RunnerStats {
+            messages_received: self.counters.messages_received,
+            messages_sent: queues.sent,
+            bytes_received: self.counters.bytes_received.load(Ordering::Relaxed),
+            bytes_sent: self.counters.bytes_sent.load(Ordering::Relaxed),
+            global_tokens: self.global_rate_limit.get_available_tokens(),
+            rate_limit_hits: queues.rate_limit_hits + self.counters.global_rate_limit_hits,
+            time_waiting: queues.waited,
+            last_ping_rtt: self.counters.last_ping_rtt,
+            channels: self
+                .channels
+                .map
+                .iter()
+                .map(|(name, channel)| (name.clone(), channel.stats()))
+                .collect(),
+        }
+    }
+
+    /// Get a receiver for messages that Twitch didn't deliver, or that expired in the queue
+    ///
+    /// Only the most recent failures are kept if nothing is receiving them.
+    pub fn send_failures(&self) -> Receiver<SendFailure> {
+        self.failures_rx.clone()
+    }
+
+    /// Drop every message waiting to be sent to `channel`, returning how many were dropped
+    ///
+    /// This is useful when you've been timed out on the channel.
+    pub fn clear_queue(&mut self, channel: &str) -> usize {
+        let channel = commands::Channel(channel).to_string();
+        self.channels
+            .get_mut(&channel)
+            .map(Channel::clear_queue)
+            .unwrap_or_default()
+    }
+
+    /// Set how sending the same message twice in a row is handled
+    ///
+    /// This applies to every channel, including ones joined later. Use
+    /// [Channel::set_duplicate_strategy] to change it for a single channel.
+    ///
+    /// [Channel::set_duplicate_strategy]: ./struct.Channel.html#method.set_duplicate_strategy
+    pub fn set_duplicate_strategy(&mut self, strategy: DuplicateStrategy) {
+        self.channels.duplicates = strategy;
+        for channel in self.channels.map.values_mut() {
+            channel.set_duplicate_strategy(strategy);
+        }
+    }
+
     /// Get a handle that you can trigger a normal 'quit'.
     ///
     /// You can also do `AsyncWriter::quit`.
@@ -152,90 +308,187 @@ impl AsyncRunner {
         self.notify_handle.clone()
     }
 
-    /// Join `channel` and wait for it to complete    
+    /// Join `channel` and wait for it to complete
+    ///
+    /// This waits for up to the `join_timeout` from the [RunnerConfig].
+    ///
+    /// [RunnerConfig]: ./struct.RunnerConfig.html
     pub async fn join(&mut self, channel: &str) -> Result<(), Error> {
-        if self.is_on_channel(channel) {
+        let timeout = self.config.join_timeout;
+        self.join_with_timeout(channel, timeout).await
+    }
+
+    /// Join `channel` and wait up to `timeout` for it to complete
+    ///
+    /// This waits for the join rate limit before sending the `JOIN`, the
+    /// `timeout` starts once it was sent. If `timeout` is `None`, this waits
+    /// until Twitch responds.
+    pub async fn join_with_timeout(
+        &mut self,
+        channel: &str,
+        timeout: impl Into<Option<Duration>>,
+    ) -> Result<(), Error> {
+        let channel = validate_channel(channel)?;
+        if self.is_on_channel(&channel) {
             return Err(Error::AlreadyOnChannel {
-                channel: channel.to_string(),
+                channel: channel.into_string(),
             });
         }
 
         log::debug!("joining '{}'", channel);
-        self.encoder.encode(commands::join(channel)).await?;
-
-        let channel = crate::commands::Channel(channel).to_string();
-        log::debug!("waiting for a response");
+        let channel = channel.into_string();
+        let mut unsent = VecDeque::from(vec![channel.clone()]);
+        let mut pending = HashMap::new();
+        let mut results = HashMap::new();
+        let mut missed = VecDeque::new();
+
+        let outcome = self
+            .join_batches(
+                &mut unsent,
+                &mut pending,
+                &mut results,
+                &mut missed,
+                timeout.into(),
+            )
+            .await;
+        self.extend_missed_messages(missed);
+        outcome?;
 
-        let mut queue = VecDeque::new();
+        results.remove(&channel).unwrap_or(Ok(()))?;
+        log::debug!("joined '{}'", channel);
 
-        let status = self
-            .wait_for(&mut queue, |msg, this| match msg {
-                // check to see if it was us that joined the channel
-                Commands::Join(msg) => {
-                    Ok(msg.channel() == channel && msg.name() == this.identity.username())
-                }
+        Ok(())
+    }
 
-                // check to see if we were banned
-                Commands::Notice(msg) if matches!(msg.msg_id(), Some(MessageId::MsgBanned)) => {
-                    Err(Error::BannedFromChannel {
-                        channel: msg.channel().to_string(),
-                    })
+    /// Join many channels, returning the result for each of them
+    ///
+    /// The channels are joined in batches of comma-separated `JOIN #a,#b,#c`
+    /// lines. Each channel counts against the join rate limit, so this waits
+    /// when it runs out of joins. Each channel waits for up to the
+    /// `join_timeout` from the [RunnerConfig] after it was sent.
+    ///
+    /// The results are in the order the channels were provided, with duplicates removed.
+    ///
+    /// This only returns an error if the connection failed.
+    ///
+    /// [RunnerConfig]: ./struct.RunnerConfig.html
+    pub async fn join_many<'a, I>(
+        &mut self,
+        channels: I,
+    ) -> Result<Vec<(String, Result<(), Error>)>, Error>
+    where
+        I: IntoIterator<Item = &'a str> + Send,
+        I::IntoIter: Send,
+    {
+        let mut order = vec![];
+        let mut results = HashMap::new();
+        let mut unsent = VecDeque::new();
+
+        for channel in channels {
+            let channel = match validate_channel(channel) {
+                Ok(channel) => channel.into_string(),
+                Err(err) => {
+                    let channel = channel.to_string();
+                    if !order.contains(&channel) {
+                        results.insert(channel.clone(), Err(err));
+                        order.push(channel);
+                    }
+                    continue;
                 }
-
-                _ => Ok(false),
-            })
-            .await?;
-
-        if let Some(status) = status {
-            match status {
-                Status::Quit | Status::Eof => return Err(Error::UnexpectedEof),
-                _ => unimplemented!(),
+            };
+            if order.contains(&channel) {
+                continue;
             }
+            if self.is_on_channel(&channel) {
+                let err = Error::AlreadyOnChannel {
+                    channel: channel.clone(),
+                };
+                results.insert(channel.clone(), Err(err));
+            } else {
+                unsent.push_back(channel.clone());
+            }
+            order.push(channel);
         }
 
-        self.missed_messages.extend(queue);
-
-        log::debug!("joined '{}'", channel);
-
-        Ok(())
+        let mut pending = HashMap::new();
+        let mut missed = VecDeque::new();
+
+        let timeout = self.config.join_timeout;
+        let outcome = self
+            .join_batches(
+                &mut unsent,
+                &mut pending,
+                &mut results,
+                &mut missed,
+                timeout,
+            )
+            .await;
+        self.extend_missed_messages(missed);
+        outcome?;
+
+        Ok(order
+            .into_iter()
+            .map(|channel| {
+                let result = results.remove(&channel).unwrap_or(Ok(()));
+                (channel, result)
+            })
+            .collect())
     }
 
     /// Part `channel` and wait for it to complete
+    ///
+    /// This waits for up to the `part_timeout` from the [RunnerConfig].
+    ///
+    /// [RunnerConfig]: ./struct.RunnerConfig.html
     pub async fn part(&mut self, channel: &str) -> Result<(), Error> {
-        if !self.is_on_channel(channel) {
+        let timeout = self.config.part_timeout;
+        self.part_with_timeout(channel, timeout).await
+    }
+
+    /// Part `channel` and wait up to `timeout` for it to complete
+    ///
+    /// If `timeout` is `None`, this waits until Twitch responds.
+    pub async fn part_with_timeout(
+        &mut self,
+        channel: &str,
+        timeout: impl Into<Option<Duration>>,
+    ) -> Result<(), Error> {
+        let channel = validate_channel(channel)?;
+        if !self.is_on_channel(&channel) {
             return Err(Error::NotOnChannel {
-                channel: channel.to_string(),
+                channel: channel.into_string(),
             });
         }
 
         log::debug!("leaving '{}'", channel);
-        self.encoder.encode(commands::part(channel)).await?;
+        self.encoder.encode(commands::part(&channel)).await?;
 
-        let channel = crate::commands::Channel(channel).to_string();
         log::debug!("waiting for a response");
 
         let mut queue = VecDeque::new();
-
         let status = self
-            .wait_for(&mut queue, |msg, this| match msg {
+            .wait_for(&mut queue, timeout.into(), |msg, this| match msg {
                 // check to see if it was us that left the channel
                 Commands::Part(msg) => {
                     Ok(msg.channel() == channel && msg.name() == this.identity.username())
                 }
                 _ => Ok(false),
             })
-            .await?;
-
-        if let Some(status) = status {
-            match status {
-                Status::Quit | Status::Eof => return Err(Error::UnexpectedEof),
-                _ => unimplemented!(),
+            .await;
+        self.extend_missed_messages(queue);
+
+        match status? {
+            Waited::Found => {}
+            Waited::Stopped => return Err(Error::UnexpectedEof),
+            Waited::TimedOut => {
+                log::warn!("timed out leaving '{}'", channel);
+                return Err(Error::PartTimedOut {
+                    channel: channel.into_string(),
+                });
             }
         }
         log::debug!("left '{}'", channel);
 
-        self.missed_messages.extend(queue);
-
         Ok(())
     }
 
@@ -249,7 +502,7 @@ impl AsyncRunner {
                 StepResult::Status(Status::Quit) => {
                     if let Left(_notified) = self.notify.wait().now_or_never().await {
                         // close everything
-                        self.writer_rx.close();
+                        self.lanes_rx.close();
                         self.activity_rx.close();
 
                         // and then drain any remaining items
@@ -272,6 +525,16 @@ impl AsyncRunner {
 
     /// Single step the loop. This is useful for testing.
     pub async fn step(&mut self) -> Result<StepResult<'static>, Error> {
+        self.step_until(None).await
+    }
+
+    /// Single step the loop, giving up on waiting at `deadline`
+    ///
+    /// Only the waiting is cut short, a write that was started is always finished.
+    async fn step_until(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<StepResult<'static>, Error> {
         use crate::util::*;
         use crate::IntoOwned as _;
 
@@ -279,13 +542,22 @@ impl AsyncRunner {
             return Ok(StepResult::Status(Status::Message(msg)));
         }
 
+        // wake up to deliver the sent commands that weren't unknown
+        let wake = match (deadline, self.commands.next_settle()) {
+            (Some(deadline), Some(settle)) => Some(deadline.min(settle)),
+            (deadline, settle) => deadline.or(settle),
+        };
+
         let select = self
             .decoder
             .read_message()
             .either(self.activity_rx.recv())
-            .either(self.writer_rx.recv())
+            .either(self.lanes_rx.recv())
             .either(self.notify.wait())
-            .either(super::timeout::next_delay())
+            .either(
+                super::timeout::next_delay(self.config.ping_interval)
+                    .either(super::timeout::wait_until(wake)),
+            )
             .await;
 
         match select {
@@ -304,6 +576,13 @@ impl AsyncRunner {
 
                 self.timeout_state = TimeoutState::activity();
 
+                self.counters.messages_received += 1;
+                if let Some(ch) = msg.nth_arg(0) {
+                    if let Some(ch) = self.channels.get_mut(ch) {
+                        ch.received += 1;
+                    }
+                }
+
                 let all = Commands::from_irc(msg) //
                     .expect("msg identity conversion should be upheld")
                     .into_owned();
@@ -317,35 +596,18 @@ impl AsyncRunner {
                 self.timeout_state = TimeoutState::activity();
             }
 
-            Left(Left(Right(Some(write_data)))) => {
-                // TODO provide a 'bytes' flavored parser
-                let msg = std::str::from_utf8(&*write_data).map_err(Error::InvalidUtf8)?;
-                let msg = crate::irc::IrcMessage::parse(crate::MaybeOwned::Borrowed(msg))
-                    .expect("encoder should produce valid IRC messages");
-
-                if let crate::irc::IrcMessage::PRIVMSG = msg.get_command() {
-                    if let Some(ch) = msg.nth_arg(0) {
-                        if !self.channels.is_on(ch) {
-                            self.channels.add(ch)
-                        }
-
-                        let ch = self.channels.get_mut(ch).unwrap();
-                        if ch.rated_limited_at.map(|s| s.elapsed()) > Some(RATE_LIMIT_WINDOW) {
-                            ch.reset_rate_limit();
-                        }
-
-                        ch.rate_limited.enqueue(write_data)
-                    }
-                }
-            }
+            Left(Left(Right(Some((priority, msg))))) => self.enqueue_write(priority, msg)?,
 
             Left(Right(_notified)) => return Ok(StepResult::Status(Status::Quit)),
 
-            Right(_timeout) => {
+            Right(Right(_deadline)) => {}
+
+            Right(Left(_timeout)) => {
                 log::info!("idle connection detected, sending a ping");
                 let ts = timestamp().to_string();
                 self.encoder.encode(commands::ping(&ts)).await?;
                 self.timeout_state = TimeoutState::waiting_for_pong();
+                self.counters.ping_sent_at.replace(Instant::now());
             }
 
             _ => {
@@ -355,22 +617,25 @@ impl AsyncRunner {
 
         match self.timeout_state {
             TimeoutState::WaitingForPong(dt) => {
-                if dt.elapsed() > TIMEOUT {
+                if dt.elapsed() > self.config.pong_timeout {
                     log::warn!("PING timeout detected, exiting");
                     return Err(Error::TimedOut);
                 }
             }
             TimeoutState::Activity(dt) => {
-                if dt.elapsed() > WINDOW {
+                if dt.elapsed() > self.config.ping_interval {
                     log::warn!("idle connectiond detected, sending a PING");
                     let ts = timestamp().to_string();
-                    self.encoder.encode(crate::commands::ping(&ts)).await?;
+                    self.encoder.encode(commands::ping(&ts)).await?;
                     self.timeout_state = TimeoutState::waiting_for_pong();
+                    self.counters.ping_sent_at.replace(Instant::now());
                 }
             }
             TimeoutState::Start => {}
         }
 
+        self.commands.settle(Instant::now());
+
         log::trace!("draining messages");
         self.drain_queued_messages().await?;
 
@@ -393,40 +658,67 @@ impl AsyncRunner {
                 self.timeout_state = TimeoutState::activity();
             }
 
-            Pong(..) if matches!(self.timeout_state, WaitingForPong {..}) => {
-                self.timeout_state = TimeoutState::activity()
+            Pong(..) => {
+                if let Some(sent) = self.counters.ping_sent_at.take() {
+                    self.counters.last_ping_rtt.replace(sent.elapsed());
+                }
+                if matches!(self.timeout_state, WaitingForPong { .. }) {
+                    self.timeout_state = TimeoutState::activity()
+                }
             }
 
             Join(msg) if msg.name() == self.identity.username() => {
                 log::debug!("starting tracking channel for '{}'", msg.channel());
-                self.channels.add(msg.channel());
+                self.channels.add(&msg.channel());
             }
 
             Part(msg) if msg.name() == self.identity.username() => {
                 log::debug!("stopping tracking of channel '{}'", msg.channel());
-                self.channels.remove(msg.channel());
+                self.channels.remove(&msg.channel());
             }
 
             RoomState(msg) => {
                 if let Some(dur) = msg.is_slow_mode() {
-                    if let Some(ch) = self.channels.get_mut(msg.channel()) {
+                    if let Some(ch) = self.channels.get_mut(&msg.channel()) {
                         ch.enable_slow_mode(dur)
                     }
                 }
             }
 
+            // notices that aren't about a channel, e.g. a failed login, are handled elsewhere
             Notice(msg) => {
-                let ch = self.channels.get_mut(msg.channel());
-                match (msg.msg_id(), ch) {
-                    // we should enable slow mode
-                    (Some(MessageId::SlowOn), Some(ch)) => ch.enable_slow_mode(30),
-                    // we should disable slow mode
-                    (Some(MessageId::SlowOff), Some(ch)) => ch.disable_slow_mode(),
-                    // we've been rate limited on the channel
-                    (Some(MessageId::MsgRatelimit), Some(ch)) => ch.set_rate_limited(),
-                    // we cannot join/send to the channel because we're banned
-                    (Some(MessageId::MsgBanned), ..) => self.channels.remove(msg.channel()),
-                    _ => {}
+                if let Some(channel) = msg.channel() {
+                    let ch = self.channels.get_mut(&channel);
+                    match (msg.msg_id(), ch) {
+                        // we should enable slow mode
+                        (Some(MessageId::SlowOn), Some(ch)) => ch.enable_slow_mode(30),
+                        // we should disable slow mode
+                        (Some(MessageId::SlowOff), Some(ch)) => ch.disable_slow_mode(),
+                        // we've been rate limited on the channel
+                        (Some(MessageId::MsgRatelimit), Some(ch)) => ch.set_rate_limited(),
+                        // we cannot join/send to the channel because we're banned
+                        (Some(MessageId::MsgBanned), ..) => self.channels.remove(&channel),
+                        // twitch dropped our message
+                        (Some(MessageId::MsgDuplicate), ..) => {
+                            self.report_failure(SendFailure::Duplicate {
+                                channel: channel.into_string(),
+                            })
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            UnknownCommand(msg) => {
+                // find the line that caused it, so its receipt fails too
+                let sent = self.commands.unknown(msg.command());
+                let failure = SendFailure::UnknownCommand {
+                    command: msg.command().to_string(),
+                    line: sent.as_ref().map(|(line, _)| line.clone()),
+                };
+                self.report_failure(failure.clone());
+                if let Some((_, sent)) = sent {
+                    sent.failed(failure)
                 }
             }
 
@@ -439,75 +731,289 @@ impl AsyncRunner {
     }
 }
 
+/// Why [AsyncRunner::wait_for] stopped waiting
+enum Waited {
+    /// The message was found
+    Found,
+    /// The runner stopped before it was found
+    Stopped,
+    /// It wasn't found before the timeout
+    TimedOut,
+}
+
 impl AsyncRunner {
     async fn wait_for<F>(
         &mut self,
         missed: &mut VecDeque<Commands<'static>>,
+        timeout: Option<Duration>,
         func: F,
-    ) -> Result<Option<Status<'static>>, Error>
+    ) -> Result<Waited, Error>
     where
         F: Fn(&Commands<'static>, &Self) -> Result<bool, Error> + Send + Sync,
     {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
         loop {
-            match self.step().await? {
+            if matches!(deadline, Some(deadline) if deadline <= Instant::now()) {
+                break Ok(Waited::TimedOut);
+            }
+            match self.step_until(deadline).await? {
                 StepResult::Status(Status::Message(msg)) => {
                     if func(&msg, self)? {
-                        break Ok(None);
+                        break Ok(Waited::Found);
                     }
                     missed.push_back(msg);
                 }
-                StepResult::Status(d) => return Ok(Some(d)),
+                StepResult::Status(..) => break Ok(Waited::Stopped),
                 StepResult::Nothing => continue,
             }
         }
     }
 
+    fn enqueue_write(&mut self, priority: Priority, write_data: Outgoing) -> Result<(), Error> {
+        // TODO provide a 'bytes' flavored parser
+        let msg = std::str::from_utf8(&write_data.data).map_err(Error::InvalidUtf8)?;
+        let msg = crate::irc::IrcMessage::parse(crate::MaybeOwned::Borrowed(msg))
+            .expect("encoder should produce valid IRC messages");
+
+        if let crate::irc::IrcMessage::PRIVMSG = msg.get_command() {
+            // whispers have their own rate limits
+            let recipient = msg
+                .nth_arg(0)
+                .zip(msg.get_data())
+                .and_then(|(target, data)| super::whisper::recipient(target, data))
+                .map(ToString::to_string);
+
+            if let Some(recipient) = recipient {
+                self.whispers.enqueue(&recipient, write_data)
+            } else if let Some(ch) = msg.nth_arg(0) {
+                if !self.channels.is_on(ch) {
+                    self.channels.add(ch)
+                }
+
+                let ch = self.channels.get_mut(ch).unwrap();
+                if ch.rated_limited_at.map(|s| s.elapsed()) > Some(self.config.rate_limit_window) {
+                    ch.reset_rate_limit();
+                }
+
+                ch.rate_limited.enqueue(priority, write_data)
+            } else {
+                write_data.failed(SendFailure::Dropped)
+            }
+        } else {
+            let command = msg.get_command().to_string();
+            self.commands.enqueue(command, write_data)
+        }
+
+        Ok(())
+    }
+
+    async fn join_batches(
+        &mut self,
+        unsent: &mut VecDeque<String>,
+        pending: &mut HashMap<String, Option<Instant>>,
+        results: &mut HashMap<String, Result<(), Error>>,
+        missed: &mut VecDeque<Commands<'static>>,
+        timeout: Option<Duration>,
+    ) -> Result<(), Error> {
+        loop {
+            // send as many joins as the rate limit allows
+            let refill = self.send_join_batches(unsent, pending, timeout).await?;
+
+            let now = Instant::now();
+            pending.retain(|channel, deadline| match deadline {
+                Some(deadline) if *deadline <= now => {
+                    log::warn!("timed out joining '{}'", channel);
+                    let err = Error::JoinTimedOut {
+                        channel: channel.clone(),
+                    };
+                    results.insert(channel.clone(), Err(err));
+                    false
+                }
+                _ => true,
+            });
+
+            if unsent.is_empty() && pending.is_empty() {
+                break Ok(());
+            }
+
+            // wake up for the next refill, or the next deadline
+            let wake = pending
+                .values()
+                .flatten()
+                .copied()
+                .chain(refill.map(|refill| now + refill))
+                .min();
+
+            let msg = match self.step_until(wake).await? {
+                StepResult::Status(Status::Message(msg)) => msg,
+                StepResult::Status(..) => break Err(Error::UnexpectedEof),
+                StepResult::Nothing => continue,
+            };
+
+            let resolved = match &msg {
+                Commands::Join(msg) if msg.name() == self.identity.username() => {
+                    Some((msg.channel(), Ok(())))
+                }
+                Commands::Notice(msg) => {
+                    join_failure(msg).map(|(channel, err)| (channel, Err(err)))
+                }
+                _ => None,
+            };
+
+            match resolved {
+                Some((channel, result)) if pending.remove(channel.as_str()).is_some() => {
+                    results.insert(channel.into_string(), result);
+                }
+                _ => missed.push_back(msg),
+            }
+        }
+    }
+
+    /// Send batched `JOIN`s, returning how long until more can be sent
+    async fn send_join_batches(
+        &mut self,
+        unsent: &mut VecDeque<String>,
+        pending: &mut HashMap<String, Option<Instant>>,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Duration>, Error> {
+        // IRC lines are limited to 512 bytes, including the \r\n
+        const MAX_LINE: usize = 510;
+
+        while !unsent.is_empty() {
+            let available = match self.join_rate_limit.consume(0) {
+                Ok(0) | Err(..) => return Ok(self.join_rate_limit.consume(1).err()),
+                Ok(available) => available,
+            };
+
+            let mut line = String::from("JOIN ");
+            let mut batch = vec![];
+            while let Some(channel) = unsent.front() {
+                let full = batch.len() as u64 == available
+                    || (!batch.is_empty() && line.len() + 1 + channel.len() > MAX_LINE);
+                if full {
+                    break;
+                }
+                if !batch.is_empty() {
+                    line.push(',');
+                }
+                line.push_str(channel);
+                batch.extend(unsent.pop_front());
+            }
+
+            log::debug!("joining {} channels", batch.len());
+            let _ = self.join_rate_limit.consume(batch.len() as u64);
+            self.encoder.encode(commands::raw(&line)).await?;
+
+            let deadline = timeout.map(|timeout| Instant::now() + timeout);
+            pending.extend(batch.into_iter().map(|channel| (channel, deadline)));
+        }
+
+        Ok(None)
+    }
+
+    fn extend_missed_messages(&mut self, msgs: VecDeque<Commands<'static>>) {
+        self.missed_messages.extend(msgs);
+        limit_missed_messages(&mut self.missed_messages, self.config.missed_messages_limit);
+    }
+
+    fn report_failure(&self, failure: SendFailure) {
+        log::warn!("send failure: {}", failure);
+        if let Err(TrySendError::Full(failure)) = self.failures_tx.try_send(failure) {
+            // drop the oldest failure so the newest one is kept
+            let _ = self.failures_rx.try_recv();
+            let _ = self.failures_tx.try_send(failure);
+        }
+    }
+
     fn available_queued_messages(&self) -> usize {
         self.channels
             .map
             .values()
             .map(|s| s.rate_limited.queue.len())
-            .sum()
+            .sum::<usize>()
+            + self.whispers.len()
+            + self.commands.len()
+    }
+
+    fn drop_expired_messages(&mut self) {
+        let now = Instant::now();
+
+        let mut expired = vec![];
+        for channel in self.channels.map.values_mut() {
+            let msgs = channel.rate_limited.queue.remove_expired(now);
+            expired.extend(msgs.into_iter().map(|msg| (channel.name.clone(), msg)));
+        }
+        let msgs = self.whispers.remove_expired(now);
+        expired.extend(msgs.into_iter().map(|msg| ("jtv".to_string(), msg)));
+
+        for (channel, msg) in expired {
+            let failure = SendFailure::Expired { channel };
+            self.report_failure(failure.clone());
+            msg.failed(failure);
+        }
     }
 
     async fn drain_queued_messages(&mut self) -> std::io::Result<()> {
+        // expired messages are dropped instead of sent
+        self.drop_expired_messages();
+
         let enc = &mut self.encoder;
         let limit = &mut self.global_rate_limit.get_available_tokens();
 
-        let start = *limit;
+        // drain the higher priorities of every channel first
+        'lanes: for &priority in Priority::ALL.iter() {
+            // for each channel, try to take up to 'limit' tokens
+            for channel in self.channels.map.values_mut() {
+                if channel.rated_limited_at.map(|s| s.elapsed())
+                    > Some(self.config.rate_limit_window)
+                {
+                    channel.reset_rate_limit();
+                }
 
-        // for each channel, try to take up to 'limit' tokens
-        for channel in self.channels.map.values_mut() {
-            if channel.rated_limited_at.map(|s| s.elapsed()) > Some(RATE_LIMIT_WINDOW) {
-                channel.reset_rate_limit();
-            }
+                let start = *limit;
 
-            // drain until we're out of messages, or tokens
-            channel
-                .rate_limited
-                .drain_until_blocked(&channel.name, limit, enc)
-                .await?;
+                // drain until we're out of messages, or tokens
+                channel
+                    .rate_limited
+                    .drain_until_blocked(&channel.name, priority, limit, enc)
+                    .await?;
 
-            let left = std::cmp::max(start, *limit);
-            let right = std::cmp::min(start, *limit);
+                // and throttle the global one, even if every token was used
+                match self.global_rate_limit.consume(start - *limit) {
+                    // use the new remaining amount of tokens
+                    Ok(rem) => *limit = rem,
 
-            let diff = left - right;
+                    // we're globally rate limited, so just return
+                    Err(..) => {
+                        log::warn!(target: "twitchchat::rate_limit", "global rate limit hit while draining '{}'", &channel.name);
+                        self.counters.global_rate_limit_hits += 1;
+                        break 'lanes;
+                    }
+                }
 
-            if *limit == 0 {
-                log::warn!(target: "twitchchat::rate_limit", "global rate limit hit while draining '{}'", &channel.name);
-                break;
+                if *limit == 0 {
+                    log::warn!(target: "twitchchat::rate_limit", "global rate limit hit while draining '{}'", &channel.name);
+                    self.counters.global_rate_limit_hits += 1;
+                    break 'lanes;
+                }
             }
+        }
 
-            // and throttle the global one
-            match self.global_rate_limit.consume(diff) {
-                // use the new remaining amount of tokens
-                Ok(rem) => *limit = rem,
+        if *limit > 0 && !self.whispers.is_empty() {
+            let start = *limit;
+            self.whispers.drain_until_blocked(limit, enc).await?;
+            if self.global_rate_limit.consume(start - *limit).is_err() {
+                log::warn!(target: "twitchchat::rate_limit", "global rate limit hit while draining whispers");
+                self.counters.global_rate_limit_hits += 1;
+            }
+        }
 
-                // we're globally rate limited, so just return
-                Err(..) => {
-                    log::warn!(target: "twitchchat::rate_limit", "global rate limit hit while draining '{}'", &channel.name);
-                    break;
-                }
+        if *limit > 0 && !self.commands.is_empty() {
+            let start = *limit;
+            self.commands.drain_until_blocked(limit, enc).await?;
+            if self.global_rate_limit.consume(start - *limit).is_err() {
+                log::warn!(target: "twitchchat::rate_limit", "global rate limit hit while draining commands");
+                self.counters.global_rate_limit_hits += 1;
             }
         }
 
@@ -528,7 +1034,6 @@ impl AsyncRunner {
 
         let is_anonymous = user_config.is_anonymous();
 
-        let mut looking_for: HashSet<_> = user_config.capabilities.iter().collect();
         let mut caps = Capabilities::default();
         let mut our_name = None;
 
@@ -564,34 +1069,26 @@ impl AsyncRunner {
                     }
                 }
 
-                Cap(msg) => match msg.capability() {
-                    Capability::Acknowledged(name) => {
-                        use crate::twitch::Capability as Cap;
-
-                        let cap = match Cap::maybe_from_str(name) {
-                            Some(cap) => cap,
-                            // Twitch sent us an unknown capability
-                            None => {
-                                caps.unknown.insert(name.to_string());
-                                continue;
+                Cap(msg) => {
+                    let mut required = vec![];
+                    for cap in msg.capabilities() {
+                        match cap {
+                            Capability::Acknowledged(name) => caps.acknowledge(name),
+                            // optional capabilities are best-effort
+                            Capability::NotAcknowledged(name) => {
+                                if user_config.capabilities.contains(&name.into()) {
+                                    required.push(name.to_string());
+                                }
+                                caps.denied.insert(name.to_string());
                             }
-                        };
-
-                        *match cap {
-                            Cap::Tags => &mut caps.tags,
-                            Cap::Membership => &mut caps.membership,
-                            Cap::Commands => &mut caps.commands,
-                        } = true;
-
-                        looking_for.remove(&cap);
+                            _ => {}
+                        }
                     }
 
-                    Capability::NotAcknowledged(name) => {
-                        return Err(Error::InvalidCap {
-                            cap: name.to_string(),
-                        })
+                    if !required.is_empty() {
+                        return Err(Error::InvalidCap { caps: required });
                     }
-                },
+                }
 
                 GlobalUserState(msg) => {
                     break Identity::Full {
@@ -610,6 +1107,13 @@ impl AsyncRunner {
                 // already
                 Ping(msg) => encoder.encode(commands::pong(msg.token())).await?,
 
+                // Twitch rejected our token and will close the connection
+                Notice(msg) if is_authentication_failure(msg.message()) => {
+                    return Err(Error::AuthenticationFailed {
+                        message: msg.message().to_string(),
+                    })
+                }
+
                 _ => {}
             };
         };
@@ -618,6 +1122,50 @@ impl AsyncRunner {
     }
 }
 
+/// Make sure `channel` is a valid channel name, normalizing it
+fn validate_channel(channel: &str) -> Result<ChannelName<'_>, Error> {
+    ChannelName::new(channel).map_err(|error| Error::InvalidChannel {
+        channel: channel.to_string(),
+        error,
+    })
+}
+
+/// Whether this notice means we couldn't join its channel
+fn join_failure<'a>(msg: &'a crate::messages::Notice<'_>) -> Option<(ChannelName<'a>, Error)> {
+    let channel = msg.channel()?;
+    let err = match msg.msg_id()? {
+        MessageId::MsgBanned => Error::BannedFromChannel {
+            channel: channel.to_string(),
+        },
+        MessageId::MsgChannelSuspended => Error::ChannelSuspended {
+            channel: channel.to_string(),
+        },
+        _ => return None,
+    };
+    Some((channel, err))
+}
+
+fn channel_with_capacity<T>(capacity: Option<usize>) -> (Sender<T>, Receiver<T>) {
+    match capacity {
+        Some(capacity) => crate::channel::bounded(capacity.max(1)),
+        None => crate::channel::unbounded(),
+    }
+}
+
+fn limit_missed_messages(missed: &mut VecDeque<Commands<'static>>, limit: Option<usize>) {
+    let limit = match limit {
+        Some(limit) if missed.len() > limit => limit,
+        _ => return,
+    };
+    log::warn!("dropping {} missed messages", missed.len() - limit);
+    missed.drain(..missed.len() - limit);
+}
+
+fn is_authentication_failure(message: &str) -> bool {
+    const FAILURES: &[&str] = &["Login authentication failed", "Improperly formatted auth"];
+    FAILURES.iter().any(|failure| message.starts_with(failure))
+}
+
 impl Stream for AsyncRunner {
     type Item = Commands<'static>;
 
@@ -635,3 +1183,544 @@ impl Stream for AsyncRunner {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{twitch::NameError, IntoOwned as _};
+    use futures_lite::future::block_on;
+    use std::{
+        io::Read as _,
+        sync::{Arc, Mutex},
+    };
+
+    /// A connector that replays `input` and then never has anything else to read
+    #[derive(Clone)]
+    struct Scripted {
+        input: &'static str,
+        output: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Scripted {
+        fn new(input: &'static str) -> Self {
+            Self {
+                input,
+                output: Arc::default(),
+            }
+        }
+
+        fn output(&self) -> String {
+            String::from_utf8(self.output.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Connector for Scripted {
+        type Output = ScriptedStream;
+
+        fn connect(&mut self) -> crate::BoxedFuture<std::io::Result<Self::Output>> {
+            let stream = ScriptedStream {
+                input: Mutex::new(std::io::Cursor::new(self.input.as_bytes())),
+                output: self.output.clone(),
+            };
+            Box::pin(async move { Ok(stream) })
+        }
+    }
+
+    struct ScriptedStream {
+        input: Mutex<std::io::Cursor<&'static [u8]>>,
+        output: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl AsyncRead for &ScriptedStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            match self.input.lock().unwrap().read(buf)? {
+                0 => Poll::Pending,
+                n => Poll::Ready(Ok(n)),
+            }
+        }
+    }
+
+    impl AsyncWrite for &ScriptedStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.output.lock().unwrap().extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncRead for ScriptedStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut &*self).poll_read(cx, buf)
+        }
+    }
+
+    impl AsyncWrite for ScriptedStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut &*self).poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut &*self).poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut &*self).poll_close(cx)
+        }
+    }
+
+    fn connect(connector: &Scripted, config: RunnerConfig) -> AsyncRunner {
+        let user_config = UserConfig::builder().anonymous().build().unwrap();
+        let fut = AsyncRunner::connect_with_config(connector.clone(), &user_config, &config);
+        block_on(fut).unwrap()
+    }
+
+    const READY: &str = ":tmi.twitch.tv 376 justinfan1234 :>\r\n";
+
+    #[test]
+    fn join_timeout() {
+        let connector = Scripted::new(READY);
+        let mut runner = connect(&connector, RunnerConfig::default());
+
+        let timeout = Duration::from_millis(10);
+        match block_on(runner.join_with_timeout("museun", timeout)) {
+            Err(Error::JoinTimedOut { channel }) => assert_eq!(channel, "#museun"),
+            err => panic!("expected a timeout, got: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn join_waits_for_rate_limit() {
+        let connector = Scripted::new(READY);
+        let mut runner = connect(&connector, RunnerConfig::default());
+        runner.join_rate_limit = RateLimit::full(1, Duration::from_millis(50));
+
+        let timeout = Duration::from_millis(10);
+        let start = Instant::now();
+        for channel in &["a", "b"] {
+            let res = block_on(runner.join_with_timeout(channel, timeout));
+            assert!(matches!(res, Err(Error::JoinTimedOut { .. })));
+        }
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(connector.output().ends_with("JOIN #a\r\nJOIN #b\r\n"));
+    }
+
+    #[test]
+    fn token_provider() {
+        use crate::twitch::{FileToken, StaticToken, TokenError};
+
+        let token = format!("oauth:{}", "a".repeat(30));
+        let user_config = UserConfig::builder()
+            .name("museun")
+            .token(format!("oauth:{}", "b".repeat(30)))
+            .build()
+            .unwrap();
+        let config = RunnerConfig::default();
+
+        let connector = Scripted::new(":tmi.twitch.tv 376 museun :>\r\n");
+        let provider = StaticToken::new(token.clone());
+        let fut = AsyncRunner::connect_with_token_provider(
+            connector.clone(),
+            &user_config,
+            &config,
+            &provider,
+        );
+        block_on(fut).unwrap();
+        assert!(connector
+            .output()
+            .starts_with(&format!("PASS {}\r\n", token)));
+
+        let provider = FileToken::new("this/file/does/not/exist");
+        let fut = AsyncRunner::connect_with_token_provider(
+            connector.clone(),
+            &user_config,
+            &config,
+            &provider,
+        );
+        match block_on(fut) {
+            Err(Error::TokenUnavailable(TokenError::Io { source, .. })) => {
+                assert_eq!(source.kind(), std::io::ErrorKind::NotFound)
+            }
+            Err(err) => panic!("expected the token to be unavailable, got: {:?}", err),
+            Ok(..) => panic!("expected the token to be unavailable"),
+        }
+    }
+
+    #[test]
+    fn optional_capabilities() {
+        use crate::twitch::Capability;
+
+        let connector = Scripted::new(concat!(
+            ":tmi.twitch.tv CAP * ACK :twitch.tv/tags twitch.tv/commands\r\n",
+            ":tmi.twitch.tv CAP * NAK :twitch.tv/foo\r\n",
+            ":tmi.twitch.tv CAP * ACK :twitch.tv/bar\r\n",
+            ":tmi.twitch.tv 376 justinfan1234 :>\r\n",
+        ));
+        let (foo, bar) = (
+            Capability::Custom("twitch.tv/foo".into()),
+            Capability::Custom("twitch.tv/bar".into()),
+        );
+        let user_config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[Capability::Tags, Capability::Commands])
+            .optional_capabilities(&[foo.clone(), bar.clone()])
+            .build()
+            .unwrap();
+        let fut = AsyncRunner::connect(connector.clone(), &user_config);
+        let runner = block_on(fut).unwrap();
+
+        assert!(connector.output().starts_with(concat!(
+            "CAP REQ :twitch.tv/tags twitch.tv/commands\r\n",
+            "CAP REQ :twitch.tv/bar\r\n",
+            "CAP REQ :twitch.tv/foo\r\n",
+        )));
+
+        let caps = match runner.identity {
+            Identity::Anonymous { caps } => caps,
+            identity => panic!("expected an anonymous identity, got: {:?}", identity),
+        };
+        assert!(caps.tags && caps.commands && !caps.membership);
+        assert!(caps.is_enabled(&bar));
+        assert!(!caps.is_enabled(&foo));
+        assert!(caps.denied.contains("twitch.tv/foo"));
+    }
+
+    #[test]
+    fn required_capabilities_denied() {
+        use crate::twitch::Capability;
+
+        let connector = Scripted::new(concat!(
+            ":tmi.twitch.tv CAP * NAK :twitch.tv/tags twitch.tv/foo\r\n",
+            ":tmi.twitch.tv 376 justinfan1234 :>\r\n",
+        ));
+        let user_config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[Capability::Tags, Capability::Custom("twitch.tv/foo".into())])
+            .build()
+            .unwrap();
+
+        match block_on(AsyncRunner::connect(connector, &user_config)) {
+            Err(Error::InvalidCap { caps }) => {
+                assert_eq!(caps, vec!["twitch.tv/tags", "twitch.tv/foo"])
+            }
+            Err(err) => panic!("expected InvalidCap, got: {:?}", err),
+            Ok(..) => panic!("expected InvalidCap"),
+        }
+    }
+
+    #[test]
+    fn join_normalizes_channel() {
+        let connector = Scripted::new(concat!(
+            ":tmi.twitch.tv 376 justinfan1234 :>\r\n",
+            ":justinfan1234!justinfan1234@justinfan1234.tmi.twitch.tv JOIN #museun\r\n",
+        ));
+        let mut runner = connect(&connector, RunnerConfig::default());
+
+        block_on(runner.join("Museun")).unwrap();
+        assert!(connector.output().ends_with("JOIN #museun\r\n"));
+
+        for channel in &["museun", "#museun", "#MUSEUN"] {
+            assert!(runner.is_on_channel(channel));
+            assert!(runner.get_channel_mut(channel).is_some());
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let connector = Scripted::new(concat!(
+            ":tmi.twitch.tv 376 justinfan1234 :>\r\n",
+            ":justinfan1234!justinfan1234@justinfan1234.tmi.twitch.tv JOIN #museun\r\n",
+            ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n",
+        ));
+        let mut runner = connect(&connector, RunnerConfig::default());
+        block_on(runner.join("museun")).unwrap();
+
+        loop {
+            match block_on(runner.step()).unwrap() {
+                StepResult::Status(Status::Message(Commands::Privmsg(..))) => break,
+                StepResult::Status(Status::Message(..)) | StepResult::Nothing => continue,
+                res => panic!("expected a privmsg, got: {:?}", res),
+            }
+        }
+
+        let mut writer = runner.writer();
+        block_on(writer.encode(commands::privmsg("#museun", "hi"))).unwrap();
+        while !connector.output().ends_with("PRIVMSG #museun :hi\r\n") {
+            let _ = block_on(runner.step()).unwrap();
+        }
+
+        let stats = runner.stats();
+        assert_eq!(stats.messages_sent, 1);
+        assert_eq!(stats.bytes_sent, connector.output().len() as u64);
+        assert_eq!(stats.bytes_received, connector.input.len() as u64);
+
+        let channel = &stats.channels["#museun"];
+        assert_eq!(channel.messages_received, 1);
+        assert_eq!(channel.messages_sent, 1);
+        assert_eq!(channel.queued, 0);
+    }
+
+    #[test]
+    fn global_rate_limit_is_debited() {
+        let connector = Scripted::new(READY);
+        let mut runner = connect(&connector, RunnerConfig::default());
+        let cap = runner.global_rate_limit.get_available_tokens();
+
+        // use every token on a single channel
+        let mut writer = runner.writer();
+        for i in 0..cap {
+            block_on(writer.encode(commands::privmsg("#museun", &i.to_string()))).unwrap();
+        }
+        let last = format!("PRIVMSG #museun :{}\r\n", cap - 1);
+        while !connector.output().ends_with(&last) {
+            let _ = block_on(runner.step()).unwrap();
+        }
+
+        assert_eq!(runner.global_rate_limit.get_available_tokens(), 0);
+    }
+
+    #[test]
+    fn join_invalid_channel() {
+        let connector = Scripted::new(READY);
+        let mut runner = connect(&connector, RunnerConfig::default());
+
+        match block_on(runner.join("#foo bar")) {
+            Err(Error::InvalidChannel { channel, error }) => {
+                assert_eq!(channel, "#foo bar");
+                assert_eq!(error, NameError::InvalidCharacter { character: ' ' });
+            }
+            err => panic!("expected an invalid channel, got: {:?}", err),
+        }
+        assert!(!connector.output().contains("JOIN"));
+    }
+
+    #[test]
+    fn join_suspended() {
+        let connector = Scripted::new(concat!(
+            ":tmi.twitch.tv 376 justinfan1234 :>\r\n",
+            "@msg-id=msg_channel_suspended :tmi.twitch.tv NOTICE #museun :This channel does not exist or has been suspended.\r\n",
+        ));
+        let mut runner = connect(&connector, RunnerConfig::default());
+
+        match block_on(runner.join("museun")) {
+            Err(Error::ChannelSuspended { channel }) => assert_eq!(channel, "#museun"),
+            err => panic!("expected a suspended channel, got: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn join_many() {
+        let connector = Scripted::new(concat!(
+            ":tmi.twitch.tv 376 justinfan1234 :>\r\n",
+            ":justinfan1234!justinfan1234@justinfan1234.tmi.twitch.tv JOIN #a\r\n",
+            "@msg-id=msg_channel_suspended :tmi.twitch.tv NOTICE #b :This channel does not exist or has been suspended.\r\n",
+        ));
+        let config = RunnerConfig::builder()
+            .join_timeout(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        let mut runner = connect(&connector, config);
+
+        let results = block_on(runner.join_many(vec!["a", "#b", "c", "#a", "d d"])).unwrap();
+        let channels = results
+            .iter()
+            .map(|(ch, _)| ch.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(channels, vec!["#a", "#b", "#c", "d d"]);
+
+        assert!(results[0].1.is_ok());
+        assert!(matches!(results[1].1, Err(Error::ChannelSuspended { .. })));
+        assert!(matches!(results[2].1, Err(Error::JoinTimedOut { .. })));
+        assert!(matches!(results[3].1, Err(Error::InvalidChannel { .. })));
+
+        assert!(runner.is_on_channel("#a"));
+        assert!(connector.output().ends_with("JOIN #a,#b,#c\r\n"));
+
+        let results = block_on(runner.join_many(vec!["#a"])).unwrap();
+        assert!(matches!(results[0].1, Err(Error::AlreadyOnChannel { .. })));
+    }
+
+    #[test]
+    fn join_many_rate_limit() {
+        let connector = Scripted::new(READY);
+        let mut runner = connect(&connector, RunnerConfig::default());
+        runner.join_rate_limit = RateLimit::full(2, Duration::from_millis(20));
+
+        let timeout = Duration::from_millis(10);
+        runner.config.join_timeout.replace(timeout);
+
+        let results = block_on(runner.join_many(vec!["a", "b", "c"])).unwrap();
+        assert_eq!(results.len(), 3);
+
+        let output = connector.output();
+        assert!(output.contains("JOIN #a,#b\r\n"));
+        assert!(output.ends_with("JOIN #c\r\n"));
+    }
+
+    #[test]
+    fn unknown_command() {
+        let connector = Scripted::new(concat!(
+            ":tmi.twitch.tv 376 justinfan1234 :>\r\n",
+            ":tmi.twitch.tv 421 justinfan1234 WHO :Unknown command\r\n",
+        ));
+        let mut runner = connect(&connector, RunnerConfig::default());
+        let failures = runner.send_failures();
+
+        // the ready message is replayed first
+        let _ = block_on(runner.step()).unwrap();
+        match block_on(runner.step()).unwrap() {
+            StepResult::Status(Status::Message(Commands::UnknownCommand(msg))) => {
+                assert_eq!(msg.command(), "WHO")
+            }
+            res => panic!("expected an unknown command, got: {:?}", res),
+        }
+        assert_eq!(
+            failures.try_recv().unwrap(),
+            SendFailure::UnknownCommand {
+                command: "WHO".to_string(),
+                line: None,
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_command_receipt() {
+        use crate::messages::UnknownCommand;
+
+        let connector = Scripted::new(READY);
+        let mut runner = connect(&connector, RunnerConfig::default());
+        let failures = runner.send_failures();
+        let mut writer = runner.writer();
+
+        let who = block_on(writer.encode_with_receipt(commands::raw("WHO #museun"))).unwrap();
+        while !connector.output().ends_with("WHO #museun\r\n") {
+            let _ = block_on(runner.step()).unwrap();
+        }
+
+        let msg = UnknownCommand::builder()
+            .target("justinfan1234")
+            .command("WHO")
+            .build()
+            .unwrap();
+        block_on(runner.check_messages(&msg.into())).unwrap();
+
+        let failure = SendFailure::UnknownCommand {
+            command: "WHO".to_string(),
+            line: Some("WHO #museun".to_string()),
+        };
+        assert_eq!(block_on(who).unwrap_err(), failure);
+        assert_eq!(failures.try_recv().unwrap(), failure);
+
+        // a known command is delivered once a 421 can no longer arrive for it
+        let mut names =
+            block_on(writer.encode_with_receipt(commands::raw("NAMES #museun"))).unwrap();
+        let sent = Instant::now();
+        let res = loop {
+            let _ = block_on(runner.step()).unwrap();
+            if let Some(res) = block_on(futures_lite::future::poll_once(&mut names)) {
+                break res;
+            }
+        };
+        assert!(res.is_ok());
+        assert!(sent.elapsed() >= Duration::from_secs(1));
+        assert!(!connector.output().contains("PING"));
+        assert!(failures.try_recv().is_none());
+    }
+
+    #[test]
+    fn malformed_numeric() {
+        let connector = Scripted::new(concat!(
+            ":tmi.twitch.tv 002\r\n",
+            ":tmi.twitch.tv 376 justinfan1234 :>\r\n",
+            ":tmi.twitch.tv 421 justinfan1234\r\n",
+        ));
+        let mut runner = connect(&connector, RunnerConfig::default());
+
+        for _ in 0..3 {
+            match block_on(runner.step()).unwrap() {
+                StepResult::Status(Status::Message(Commands::Raw(msg))) => {
+                    assert!(msg.is_numeric())
+                }
+                StepResult::Status(Status::Message(Commands::Ready(..))) => {}
+                res => panic!("expected a raw message, got: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn missed_messages_limit() {
+        let ping = |n: usize| {
+            let input = format!("PING :{}\r\n", n);
+            let (_, msg) = crate::irc::parse_one(&input).unwrap();
+            Commands::from_irc(msg).unwrap().into_owned()
+        };
+
+        let mut missed: VecDeque<_> = (0..5).map(ping).collect();
+        limit_missed_messages(&mut missed, None);
+        assert_eq!(missed.len(), 5);
+
+        limit_missed_messages(&mut missed, Some(2));
+        let tokens = missed
+            .iter()
+            .map(|msg| match msg {
+                Commands::Ping(msg) => msg.token().to_string(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(tokens, vec!["3", "4"]);
+    }
+
+    #[test]
+    fn authentication_failed() {
+        let config = UserConfig::builder()
+            .name("foo")
+            .token(format!("oauth:{}", "a".repeat(30)))
+            .build()
+            .unwrap();
+
+        let inputs = &[
+            (
+                ":tmi.twitch.tv NOTICE * :Login authentication failed\r\n",
+                "Login authentication failed",
+            ),
+            (
+                ":tmi.twitch.tv NOTICE * :Improperly formatted auth\r\n",
+                "Improperly formatted auth",
+            ),
+        ];
+
+        for (input, expected) in inputs {
+            let mut decoder = AsyncDecoder::new(futures_lite::io::Cursor::new(input.as_bytes()));
+            let mut encoder = AsyncEncoder::new(vec![]);
+            let mut missed = VecDeque::new();
+
+            let fut = AsyncRunner::wait_for_ready(&mut decoder, &mut encoder, &config, &mut missed);
+            match block_on(fut) {
+                Err(Error::AuthenticationFailed { message }) => assert_eq!(message, *expected),
+                err => panic!("expected authentication failure, got: {:?}", err),
+            }
+        }
+    }
+}
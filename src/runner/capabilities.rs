@@ -1,3 +1,4 @@
+use crate::twitch::Capability;
 use std::collections::HashSet;
 
 /// Capabiltiies Twitch acknowledged.
@@ -9,6 +10,34 @@ pub struct Capabilities {
     pub commands: bool,
     /// You have the [tags](https://dev.twitch.tv/docs/irc/tags) capability
     pub tags: bool,
-    /// A set of unknown capabilities Twitch sent to use
+    /// A set of other capabilities Twitch acknowledged, e.g. [custom] ones
+    ///
+    /// [custom]: ../twitch/enum.Capability.html#variant.Custom
     pub unknown: HashSet<String>,
+    /// A set of optional capabilities Twitch denied
+    pub denied: HashSet<String>,
+}
+
+impl Capabilities {
+    /// Whether Twitch acknowledged this capability
+    pub fn is_enabled(&self, cap: &Capability) -> bool {
+        match cap {
+            Capability::Membership => self.membership,
+            Capability::Tags => self.tags,
+            Capability::Commands => self.commands,
+            cap => self.unknown.contains(cap.as_str()),
+        }
+    }
+
+    pub(crate) fn acknowledge(&mut self, name: &str) {
+        match Capability::from(name) {
+            Capability::Membership => self.membership = true,
+            Capability::Tags => self.tags = true,
+            Capability::Commands => self.commands = true,
+            _ => {
+                self.unknown.insert(name.to_string());
+            }
+        }
+        self.denied.remove(name);
+    }
 }
@@ -1,9 +1,13 @@
-use super::rate_limit::{PreviousRate, RateLimitedEncoder};
-use crate::rate_limit::{RateClass, RateLimit};
-use std::{
-    collections::{HashMap, VecDeque},
-    time::Duration,
+use super::{
+    rate_limit::{DuplicateStrategy, Lanes, PreviousRate, RateLimitedEncoder},
+    stats::QueueStats,
+    ChannelStats, SendFailure,
 };
+use crate::{
+    rate_limit::{RateClass, RateLimit},
+    twitch::ChannelName,
+};
+use std::{collections::HashMap, time::Duration};
 
 /// A channel that you are on.
 ///
@@ -18,6 +22,8 @@ pub struct Channel {
     pub(crate) rate_limited: RateLimitedEncoder,
     pub(crate) previous: Option<PreviousRate>,
     pub(crate) rated_limited_at: Option<std::time::Instant>,
+    pub(crate) received: u64,
+    pub(crate) rate_class: RateClass,
 }
 
 impl std::fmt::Debug for Channel {
@@ -27,17 +33,23 @@ impl std::fmt::Debug for Channel {
 }
 
 impl Channel {
-    pub(crate) fn new(name: String) -> Self {
-        let rate_limit = RateLimit::from_class(RateClass::Regular);
+    pub(crate) fn new(name: String, duplicates: DuplicateStrategy, rate_class: RateClass) -> Self {
+        let rate_limit = RateLimit::from_class(rate_class);
         let rate_limited = RateLimitedEncoder {
             rate_limit,
-            queue: VecDeque::new(),
+            queue: Lanes::default(),
+            duplicates,
+            last: None,
+            time_to_live: None,
+            stats: QueueStats::default(),
         };
         Self {
             name,
             rate_limited,
             previous: None,
             rated_limited_at: None,
+            received: 0,
+            rate_class,
         }
     }
 
@@ -47,6 +59,37 @@ impl Channel {
         self.rated_limited_at.take();
     }
 
+    /// Set how sending the same message twice in a row is handled for this channel
+    pub fn set_duplicate_strategy(&mut self, strategy: DuplicateStrategy) {
+        self.rate_limited.duplicates = strategy;
+    }
+
+    /// Set how long messages can wait in this channel's queue before they are dropped
+    ///
+    /// This applies to messages that don't have their own time-to-live.
+    pub fn set_time_to_live(&mut self, ttl: impl Into<Option<Duration>>) {
+        self.rate_limited.time_to_live = ttl.into();
+    }
+
+    /// Drop every message waiting in this channel's queue, returning how many were dropped
+    pub fn clear_queue(&mut self) -> usize {
+        self.rate_limited.queue.fail_all(SendFailure::Cleared {
+            channel: self.name.clone(),
+        })
+    }
+
+    /// Get a snapshot of what this channel has been doing
+    pub fn stats(&self) -> ChannelStats {
+        let encoder = &self.rate_limited;
+        ChannelStats {
+            messages_received: self.received,
+            messages_sent: encoder.stats.sent,
+            queued: encoder.queue.len(),
+            available_tokens: encoder.rate_limit.get_available_tokens(),
+            rate_limit_hits: encoder.stats.rate_limit_hits,
+        }
+    }
+
     /// Mark this channel as being under slow mode for `duration`
     pub fn enable_slow_mode(&mut self, duration: u64) {
         let rate = &mut self.rate_limited.rate_limit;
@@ -60,7 +103,8 @@ impl Channel {
 
     /// Mark this channel as not being in slow mode
     pub fn disable_slow_mode(&mut self) {
-        let PreviousRate { cap, period } = self.previous.take().unwrap_or_default();
+        let PreviousRate { cap, period } =
+            self.previous.take().unwrap_or_else(|| self.default_rate());
         let rate = &mut self.rate_limited.rate_limit;
         rate.set_cap(cap);
         rate.set_period(period);
@@ -74,37 +118,63 @@ impl Channel {
 
     /// Reset to the default rate class
     pub fn reset_rate_limit(&mut self) {
-        let PreviousRate { cap, period } = self.previous.take().unwrap_or_default();
+        let PreviousRate { cap, period } =
+            self.previous.take().unwrap_or_else(|| self.default_rate());
         self.rate_limited.rate_limit = RateLimit::full(cap, period);
         self.rated_limited_at.take();
     }
+
+    fn default_rate(&self) -> PreviousRate {
+        PreviousRate::from_class(self.rate_class)
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct Channels {
     pub map: HashMap<String, Channel>,
+    pub duplicates: DuplicateStrategy,
+    pub rate_class: RateClass,
+    // what the channels we've left did
+    pub retired: QueueStats,
 }
 
 impl Channels {
+    // channels are keyed by their normalized '#name'
     pub fn is_on(&self, name: &str) -> bool {
-        self.map.contains_key(name)
+        self.map
+            .contains_key(ChannelName::from_server(name).as_str())
     }
 
     pub fn get_mut(&mut self, name: &str) -> Option<&mut Channel> {
-        self.map.get_mut(name)
+        self.map.get_mut(ChannelName::from_server(name).as_str())
     }
 
     pub fn add(&mut self, name: &str) {
+        let name = ChannelName::from_server(name);
         // we already have this channel (there was a sync issue)
-        if self.map.contains_key(name) {
+        if self.map.contains_key(name.as_str()) {
             return;
         }
 
-        let channel = Channel::new(name.to_string());
-        self.map.insert(name.to_string(), channel);
+        let channel = Channel::new(name.to_string(), self.duplicates, self.rate_class);
+        self.map.insert(name.into_string(), channel);
     }
 
     pub fn remove(&mut self, name: &str) {
-        self.map.remove(name);
+        let name = ChannelName::from_server(name);
+        if let Some(mut channel) = self.map.remove(name.as_str()) {
+            self.retired += channel.rate_limited.stats;
+            channel.rate_limited.queue.fail_all(SendFailure::Parted {
+                channel: channel.name,
+            });
+        }
+    }
+
+    /// The queue stats of every channel, including the ones we've left
+    pub fn queue_stats(&self) -> QueueStats {
+        self.map.values().fold(self.retired, |mut stats, channel| {
+            stats += channel.rate_limited.stats;
+            stats
+        })
     }
 }
@@ -0,0 +1,200 @@
+use super::stats::QueueStats;
+use crate::writer::Outgoing;
+use futures_lite::{AsyncWrite, AsyncWriteExt};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+// how long a sent command can still be answered with a `421`
+const UNKNOWN_WINDOW: Duration = Duration::from_secs(2);
+
+// how many sent commands are kept around for a `421`
+const MAX_UNCONFIRMED: usize = 32;
+
+/// A queue for written lines that aren't a `PRIVMSG`, e.g. a `raw` command
+///
+/// Twitch answers a command it doesn't know with a `421`. A sent command is
+/// kept for a short window so a `421` can be matched to it, and then it is
+/// considered delivered.
+#[derive(Default)]
+pub struct CommandQueue {
+    queue: VecDeque<(String, Outgoing)>,
+    unconfirmed: VecDeque<Unconfirmed>,
+    pub(crate) stats: QueueStats,
+}
+
+struct Unconfirmed {
+    command: String,
+    sent_at: Instant,
+    msg: Outgoing,
+}
+
+impl CommandQueue {
+    pub fn enqueue(&mut self, command: String, msg: Outgoing) {
+        self.queue.push_back((command, msg));
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub async fn drain_until_blocked<W>(
+        &mut self,
+        limit: &mut u64,
+        sink: &mut W,
+    ) -> std::io::Result<()>
+    where
+        W: AsyncWrite + Send + Sync + Unpin + ?Sized,
+    {
+        while *limit > 0 {
+            let (command, msg) = match self.queue.pop_front() {
+                Some(front) => front,
+                None => break,
+            };
+            *limit -= 1;
+
+            log::trace!(
+                target: "twitchchat::encoder",
+                "> {}",
+                std::str::from_utf8(&msg.data).unwrap().escape_debug()
+            );
+            sink.write_all(&msg.data).await?;
+            self.stats.sent += 1;
+            self.stats.waited += msg.queued_at.elapsed();
+
+            if self.unconfirmed.len() == MAX_UNCONFIRMED {
+                if let Some(oldest) = self.unconfirmed.pop_front() {
+                    oldest.msg.delivered()
+                }
+            }
+            self.unconfirmed.push_back(Unconfirmed {
+                command,
+                sent_at: Instant::now(),
+                msg,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Deliver every command that wasn't answered with a `421` in time
+    pub fn settle(&mut self, now: Instant) {
+        while let Some(front) = self.unconfirmed.front() {
+            if front.sent_at + UNKNOWN_WINDOW > now {
+                break;
+            }
+            if let Some(unconfirmed) = self.unconfirmed.pop_front() {
+                unconfirmed.msg.delivered()
+            }
+        }
+    }
+
+    /// When the oldest sent command should be settled
+    pub fn next_settle(&self) -> Option<Instant> {
+        self.unconfirmed
+            .front()
+            .map(|unconfirmed| unconfirmed.sent_at + UNKNOWN_WINDOW)
+    }
+
+    /// Take the oldest unconfirmed command named `command`, returning its line
+    pub fn unknown(&mut self, command: &str) -> Option<(String, Outgoing)> {
+        let pos = self
+            .unconfirmed
+            .iter()
+            .position(|unconfirmed| unconfirmed.command.eq_ignore_ascii_case(command))?;
+        let unconfirmed = self.unconfirmed.remove(pos)?;
+
+        let line = String::from_utf8_lossy(&unconfirmed.msg.data);
+        let line = line.trim_end_matches("\r\n").to_string();
+        Some((line, unconfirmed.msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{runner::SendFailure, writer::receipt};
+    use futures_lite::future::block_on;
+
+    fn enqueue(queue: &mut CommandQueue, command: &str, line: &str) -> crate::writer::Receipt {
+        let (tx, rx) = receipt();
+        let mut msg = Outgoing::new(line.as_bytes().into());
+        msg.receipt.replace(tx);
+        queue.enqueue(command.to_string(), msg);
+        rx
+    }
+
+    fn drain(queue: &mut CommandQueue) -> String {
+        let mut out = vec![];
+        block_on(queue.drain_until_blocked(&mut 100, &mut out)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn settled() {
+        let mut queue = CommandQueue::default();
+        let who = enqueue(&mut queue, "WHO", "WHO #museun\r\n");
+        assert_eq!(drain(&mut queue), "WHO #museun\r\n");
+        assert!(queue.is_empty());
+
+        // it can still be answered with a 421
+        queue.settle(Instant::now());
+        assert_eq!(queue.unconfirmed.len(), 1);
+
+        let at = queue.next_settle().unwrap();
+        queue.settle(at);
+        assert!(queue.unconfirmed.is_empty());
+        assert!(queue.next_settle().is_none());
+        assert!(block_on(who).is_ok());
+    }
+
+    #[test]
+    fn unknown() {
+        let mut queue = CommandQueue::default();
+        let first = enqueue(&mut queue, "WHO", "WHO #museun\r\n");
+        let second = enqueue(&mut queue, "WHO", "WHO #shaken_bot\r\n");
+        drain(&mut queue);
+
+        // twitch answers in order, so the oldest one is unknown
+        let (line, msg) = queue.unknown("who").unwrap();
+        assert_eq!(line, "WHO #museun");
+        msg.failed(SendFailure::Dropped);
+        assert_eq!(block_on(first).unwrap_err(), SendFailure::Dropped);
+
+        assert!(queue.unknown("NAMES").is_none());
+
+        queue.settle(Instant::now() + UNKNOWN_WINDOW);
+        assert!(block_on(second).is_ok());
+    }
+
+    #[test]
+    fn limited() {
+        let mut queue = CommandQueue::default();
+        enqueue(&mut queue, "WHO", "WHO #museun\r\n");
+        enqueue(&mut queue, "WHO", "WHO #shaken_bot\r\n");
+
+        let mut out = vec![];
+        block_on(queue.drain_until_blocked(&mut 1, &mut out)).unwrap();
+        assert_eq!(out, b"WHO #museun\r\n");
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn capped() {
+        let mut queue = CommandQueue::default();
+        let oldest = enqueue(&mut queue, "WHO", "WHO #museun\r\n");
+        for _ in 0..MAX_UNCONFIRMED {
+            enqueue(&mut queue, "WHO", "WHO #shaken_bot\r\n");
+        }
+        drain(&mut queue);
+
+        // the oldest one was delivered to make room
+        assert_eq!(queue.unconfirmed.len(), MAX_UNCONFIRMED);
+        assert!(block_on(oldest).is_ok());
+    }
+}
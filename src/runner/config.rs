@@ -0,0 +1,215 @@
+use super::timeout::{RATE_LIMIT_WINDOW, TIMEOUT, WINDOW};
+use crate::rate_limit::RateClass;
+use std::time::Duration;
+
+/**
+Configuration for the [AsyncRunner]
+
+The defaults are what [AsyncRunner::connect] uses.
+
+# example using a builder
+```
+# use twitchchat::{rate_limit::RateClass, runner::RunnerConfig};
+# use std::time::Duration;
+// for a flaky connection
+let config = RunnerConfig::builder()
+    .ping_interval(Duration::from_secs(15))
+    .pong_timeout(Duration::from_secs(20))
+    .join_timeout(Duration::from_secs(10))
+    .rate_class(RateClass::Known)
+    .build()
+    .unwrap();
+
+assert_eq!(config.ping_interval, Duration::from_secs(15));
+assert_eq!(config.part_timeout, None);
+```
+
+[AsyncRunner]: ./struct.AsyncRunner.html
+[AsyncRunner::connect]: ./struct.AsyncRunner.html#method.connect
+*/
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct RunnerConfig {
+    /// How long the connection can be idle before we send a PING
+    pub ping_interval: Duration,
+    /// How long to wait for a PONG before the connection is considered dead
+    pub pong_timeout: Duration,
+    /// How long to wait after being rate limited on a channel before resetting its rate limit
+    pub rate_limit_window: Duration,
+    /// How long to wait for Twitch to confirm a JOIN. `None` waits forever.
+    pub join_timeout: Option<Duration>,
+    /// How long to wait for Twitch to confirm a PART. `None` waits forever.
+    pub part_timeout: Option<Duration>,
+    /// How many messages the writer can buffer. `None` is unbounded.
+    ///
+    /// When it is full, the async writes wait and the sync writes fail with `WouldBlock`.
+    pub writer_capacity: Option<usize>,
+    /// How many messages received while waiting on something are kept. `None` is unbounded.
+    ///
+    /// When the limit is reached, the oldest messages are dropped.
+    pub missed_messages_limit: Option<usize>,
+    /// The `RateClass` used for new channels and the global rate limit
+    pub rate_class: RateClass,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval: WINDOW,
+            pong_timeout: TIMEOUT,
+            rate_limit_window: RATE_LIMIT_WINDOW,
+            join_timeout: None,
+            part_timeout: None,
+            writer_capacity: None,
+            missed_messages_limit: None,
+            rate_class: RateClass::Regular,
+        }
+    }
+}
+
+impl RunnerConfig {
+    /// Create a builder to make a [RunnerConfig]
+    ///
+    /// [RunnerConfig]: ./struct.RunnerConfig.html
+    pub fn builder() -> RunnerConfigBuilder {
+        RunnerConfigBuilder::default()
+    }
+}
+
+/// Runner config error returned by the [RunnerConfigBuilder]
+///
+/// [RunnerConfigBuilder]: ./struct.RunnerConfigBuilder.html
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RunnerConfigError {
+    /// The PING interval was zero
+    InvalidPingInterval,
+    /// The PONG timeout was zero
+    InvalidPongTimeout,
+    /// The writer capacity was zero
+    InvalidWriterCapacity,
+}
+
+impl std::fmt::Display for RunnerConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPingInterval => f.write_str("the ping interval must not be zero"),
+            Self::InvalidPongTimeout => f.write_str("the pong timeout must not be zero"),
+            Self::InvalidWriterCapacity => f.write_str("the writer capacity must not be zero"),
+        }
+    }
+}
+
+impl std::error::Error for RunnerConfigError {}
+
+/// Builder for making a [RunnerConfig]
+///
+/// [RunnerConfig]: ./struct.RunnerConfig.html
+#[derive(Default, Debug)]
+pub struct RunnerConfigBuilder {
+    config: RunnerConfig,
+}
+
+impl RunnerConfigBuilder {
+    /// How long the connection can be idle before we send a PING
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.config.ping_interval = interval;
+        self
+    }
+
+    /// How long to wait for a PONG before the connection is considered dead
+    pub fn pong_timeout(mut self, timeout: Duration) -> Self {
+        self.config.pong_timeout = timeout;
+        self
+    }
+
+    /// How long to wait after being rate limited on a channel before resetting its rate limit
+    pub fn rate_limit_window(mut self, window: Duration) -> Self {
+        self.config.rate_limit_window = window;
+        self
+    }
+
+    /// How long to wait for Twitch to confirm a JOIN
+    pub fn join_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.config.join_timeout = timeout.into();
+        self
+    }
+
+    /// How long to wait for Twitch to confirm a PART
+    pub fn part_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.config.part_timeout = timeout.into();
+        self
+    }
+
+    /// How many messages the writer can buffer
+    ///
+    /// Once it is full, the async writes (e.g. `AsyncWriter::encode`) wait for
+    /// the runner to catch up, and the sync writes (e.g. `AsyncWriter::encode_sync`
+    /// or `std::io::Write`) return an error of kind `WouldBlock`.
+    pub fn writer_capacity(mut self, capacity: impl Into<Option<usize>>) -> Self {
+        self.config.writer_capacity = capacity.into();
+        self
+    }
+
+    /// How many messages received while waiting on something are kept
+    pub fn missed_messages_limit(mut self, limit: impl Into<Option<usize>>) -> Self {
+        self.config.missed_messages_limit = limit.into();
+        self
+    }
+
+    /// The `RateClass` used for new channels and the global rate limit
+    pub fn rate_class(mut self, rate_class: RateClass) -> Self {
+        self.config.rate_class = rate_class;
+        self
+    }
+
+    /// Tries to build the RunnerConfig
+    ///
+    /// This returns an [error] if the ping interval, pong timeout or writer capacity are zero.
+    ///
+    /// [error]: ./enum.RunnerConfigError.html
+    pub fn build(self) -> Result<RunnerConfig, RunnerConfigError> {
+        let config = self.config;
+        if config.ping_interval == Duration::from_secs(0) {
+            return Err(RunnerConfigError::InvalidPingInterval);
+        }
+        if config.pong_timeout == Duration::from_secs(0) {
+            return Err(RunnerConfigError::InvalidPongTimeout);
+        }
+        if config.writer_capacity == Some(0) {
+            return Err(RunnerConfigError::InvalidWriterCapacity);
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let config = RunnerConfig::builder().build().unwrap();
+        assert_eq!(config.ping_interval, WINDOW);
+        assert_eq!(config.pong_timeout, TIMEOUT);
+        assert_eq!(config.rate_limit_window, RATE_LIMIT_WINDOW);
+        assert_eq!(config.join_timeout, None);
+        assert_eq!(config.part_timeout, None);
+        assert_eq!(config.writer_capacity, None);
+        assert_eq!(config.missed_messages_limit, None);
+        assert!(matches!(config.rate_class, RateClass::Regular));
+    }
+
+    #[test]
+    fn invalid() {
+        let zero = Duration::from_secs(0);
+        let err = RunnerConfig::builder().ping_interval(zero).build();
+        assert_eq!(err.unwrap_err(), RunnerConfigError::InvalidPingInterval);
+
+        let err = RunnerConfig::builder().pong_timeout(zero).build();
+        assert_eq!(err.unwrap_err(), RunnerConfigError::InvalidPongTimeout);
+
+        let err = RunnerConfig::builder().writer_capacity(0).build();
+        assert_eq!(err.unwrap_err(), RunnerConfigError::InvalidWriterCapacity);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    channel::{Receiver, Sender, TrySendError},
+    messages::{Commands, Whisper},
+    Encodable,
+};
+
+use futures_lite::Stream;
+use std::{
+    collections::HashMap,
+    io::Write,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/**
+Routes incoming whispers to per-user [Conversation]s
+
+# example
+```
+# use twitchchat::{messages::Commands, runner::Conversations, FromIrcMessage as _};
+let mut conversations = Conversations::new();
+let museun = conversations.conversation("museun");
+
+let input = ":museun!museun@museun.tmi.twitch.tv WHISPER shaken_bot :hello\r\n";
+let (_, msg) = twitchchat::irc::parse_one(input).unwrap();
+let msg = Commands::from_irc(msg).unwrap();
+
+// whispers from museun go to the conversation
+assert!(conversations.route(msg).is_none());
+
+let whisper = museun.try_recv().unwrap();
+assert_eq!(whisper.data(), "hello");
+```
+
+[Conversation]: ./struct.Conversation.html
+*/
+#[derive(Debug, Default)]
+pub struct Conversations {
+    map: HashMap<String, Sender<Whisper<'static>>>,
+}
+
+impl Conversations {
+    /// Create an empty set of conversations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a conversation with `user`
+    ///
+    /// This replaces any previous conversation with `user`.
+    pub fn conversation(&mut self, user: &str) -> Conversation {
+        let user = user.to_lowercase();
+        let (tx, rx) = crate::channel::unbounded();
+        self.map.insert(user.clone(), tx);
+        Conversation { user, rx }
+    }
+
+    /// Whether there is an active conversation with `user`
+    pub fn is_active(&self, user: &str) -> bool {
+        self.map.contains_key(&*user.to_lowercase())
+    }
+
+    /// Route this message to its conversation
+    ///
+    /// This returns the message back if it wasn't a whisper from a user you're having a conversation with.
+    pub fn route(&mut self, msg: Commands<'static>) -> Option<Commands<'static>> {
+        let whisper = match msg {
+            Commands::Whisper(whisper) => whisper,
+            msg => return Some(msg),
+        };
+
+        let tx = match self.map.get(whisper.name().as_str()) {
+            Some(tx) => tx,
+            None => return Some(Commands::Whisper(whisper)),
+        };
+
+        match tx.try_send(whisper) {
+            Ok(()) => None,
+            // the conversation was dropped, so stop tracking it
+            Err(TrySendError::Closed(whisper)) | Err(TrySendError::Full(whisper)) => {
+                self.map.remove(whisper.name().as_str());
+                Some(Commands::Whisper(whisper))
+            }
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// A stream of whispers from a single user
+    ///
+    /// This is created by [Conversations::conversation].
+    ///
+    /// [Conversations::conversation]: ./struct.Conversations.html#method.conversation
+    #[derive(Debug)]
+    pub struct Conversation {
+        user: String,
+        #[pin]
+        rx: Receiver<Whisper<'static>>,
+    }
+}
+
+impl Conversation {
+    /// The user this conversation is with
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    /// Asynchronously receive the next whisper
+    ///
+    /// If this returns None, the [Conversations] was dropped
+    ///
+    /// [Conversations]: ./struct.Conversations.html
+    pub async fn recv(&self) -> Option<Whisper<'static>> {
+        self.rx.recv().await
+    }
+
+    /// Synchronously receive the next whisper, if one is available
+    pub fn try_recv(&self) -> Option<Whisper<'static>> {
+        self.rx.try_recv()
+    }
+
+    /// Whisper `data` to the user of this conversation
+    pub fn reply<W>(&self, writer: &mut W, data: &str) -> std::io::Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        crate::commands::whisper(&self.user, data).encode(writer)?;
+        writer.flush()
+    }
+}
+
+impl Stream for Conversation {
+    type Item = Whisper<'static>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().rx.poll_next(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{irc::IrcMessage, FromIrcMessage as _, IntoOwned as _};
+
+    fn parse(input: &str) -> Commands<'static> {
+        let msg = IrcMessage::parse(crate::MaybeOwned::Borrowed(input)).unwrap();
+        Commands::from_irc(msg).unwrap().into_owned()
+    }
+
+    fn whisper(user: &str, data: &str) -> Commands<'static> {
+        parse(&format!(
+            ":{0}!{0}@{0}.tmi.twitch.tv WHISPER shaken_bot :{1}\r\n",
+            user, data
+        ))
+    }
+
+    #[test]
+    fn route() {
+        let mut conversations = Conversations::new();
+        let museun = conversations.conversation("Museun");
+        assert_eq!(museun.user(), "museun");
+        assert!(conversations.is_active("museun"));
+
+        assert!(conversations.route(whisper("museun", "hello")).is_none());
+        assert!(conversations.route(whisper("someone", "hi")).is_some());
+        assert!(conversations
+            .route(parse(
+                ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n"
+            ))
+            .is_some());
+
+        assert_eq!(museun.try_recv().unwrap().data(), "hello");
+        assert!(museun.try_recv().is_none());
+    }
+
+    #[test]
+    fn dropped_conversation() {
+        let mut conversations = Conversations::new();
+        drop(conversations.conversation("museun"));
+
+        assert!(conversations.route(whisper("museun", "hello")).is_some());
+        assert!(!conversations.is_active("museun"));
+    }
+
+    #[test]
+    fn reply() {
+        let mut conversations = Conversations::new();
+        let museun = conversations.conversation("museun");
+
+        let mut out = vec![];
+        museun.reply(&mut out, "hello world").unwrap();
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "PRIVMSG jtv :/w museun hello world\r\n"
+        );
+    }
+}
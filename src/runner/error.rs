@@ -1,4 +1,7 @@
-use crate::{DecodeError, MessageError};
+use crate::{
+    twitch::{NameError, TokenError},
+    DecodeError, MessageError,
+};
 
 /// An error Returned by a Runner
 #[derive(Debug)]
@@ -9,10 +12,17 @@ pub enum Error {
     InvalidUtf8(std::str::Utf8Error),
     /// We could not parse a message -- this should never happen
     ParsingFailure(MessageError),
-    /// You requested a capability and Twitch rejected it
+    /// You requested capabilities and Twitch rejected some that weren't optional
     InvalidCap {
-        /// The capability name
-        cap: String,
+        /// The names of the rejected capabilities
+        caps: Vec<String>,
+    },
+    /// The channel name isn't a valid Twitch channel
+    InvalidChannel {
+        /// The channel name
+        channel: String,
+        /// Why it was invalid
+        error: NameError,
     },
     /// You're already on that channel
     AlreadyOnChannel {
@@ -24,13 +34,37 @@ pub enum Error {
         /// The channel name
         channel: String,
     },
+    /// Twitch didn't confirm the JOIN in time
+    JoinTimedOut {
+        /// The channel name
+        channel: String,
+    },
+    /// Twitch didn't confirm the PART in time
+    PartTimedOut {
+        /// The channel name
+        channel: String,
+    },
     /// You could not join this channel, you were banned prior.
     BannedFromChannel {
         /// The channel name
         channel: String,
     },
+    /// Twitch rejected the token you provided during registration
+    AuthenticationFailed {
+        /// The notice Twitch sent
+        message: String,
+    },
+    /// A token could not be retrieved from the token provider
+    TokenUnavailable(TokenError),
     /// Your connection timed out.
     TimedOut,
+    /// You could not join this channel, it is suspended or doesn't exist.
+    ///
+    /// Twitch reports both of these the same way.
+    ChannelSuspended {
+        /// The channel name
+        channel: String,
+    },
     /// Twitch restarted the server, you should reconnect.
     ShouldReconnect,
     /// An unexpected EOF was found -- this means the connectionc losed abnormally.
@@ -43,13 +77,27 @@ impl std::fmt::Display for Error {
             Self::Io(err) => write!(f, "io error: {}", err),
             Self::InvalidUtf8(err) => write!(f, "invalid utf-8 while parsing: {}", err),
             Self::ParsingFailure(err) => write!(f, "could not parse message: {}", err),
-            Self::InvalidCap { cap } => {
-                write!(f, "request capability '{}' was not acknowledged", cap)
+            Self::InvalidCap { caps } => write!(
+                f,
+                "requested capabilities were not acknowledged: {}",
+                caps.join(", ")
+            ),
+            Self::InvalidChannel { channel, error } => {
+                write!(f, "invalid channel '{}': {}", channel, error)
             }
             Self::AlreadyOnChannel { channel } => write!(f, "already on channel '{}'", channel),
             Self::NotOnChannel { channel } => write!(f, "not on channel '{}'", channel),
+            Self::JoinTimedOut { channel } => write!(f, "timed out joining channel '{}'", channel),
+            Self::PartTimedOut { channel } => write!(f, "timed out leaving channel '{}'", channel),
             Self::BannedFromChannel { channel } => write!(f, "banned from channel '{}'", channel),
+            Self::AuthenticationFailed { message } => {
+                write!(f, "authentication failed: {}", message)
+            }
+            Self::TokenUnavailable(err) => write!(f, "cannot get a token: {}", err),
             Self::TimedOut => write!(f, "your connection timed out"),
+            Self::ChannelSuspended { channel } => {
+                write!(f, "channel '{}' is suspended or does not exist", channel)
+            }
             Self::ShouldReconnect => write!(f, "you should reconnect. Twitch restarted the server"),
             Self::UnexpectedEof => write!(f, "reached an unexpected EOF"),
         }
@@ -62,6 +110,8 @@ impl std::error::Error for Error {
             Self::Io(err) => Some(err),
             Self::InvalidUtf8(err) => Some(err),
             Self::ParsingFailure(err) => Some(err),
+            Self::TokenUnavailable(err) => Some(err),
+            Self::InvalidChannel { error, .. } => Some(error),
             _ => None,
         }
     }
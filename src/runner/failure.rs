@@ -0,0 +1,74 @@
+/// A message that was sent, but not delivered
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SendFailure {
+    /// Twitch dropped the message because it was identical to your previous one
+    Duplicate {
+        /// The channel name
+        channel: String,
+    },
+    /// The message was dropped before it was sent
+    ///
+    /// This happens when the runner doesn't know where to send it, or a limit
+    /// such as the daily whisper recipient limit was reached.
+    Dropped,
+    /// You left (or were removed from) the channel before the message was sent
+    Parted {
+        /// The channel name
+        channel: String,
+    },
+    /// The connection was closed before the message was sent
+    Closed,
+    /// The message wasn't sent before its time-to-live expired
+    Expired {
+        /// The channel name, or `jtv` for whispers
+        channel: String,
+    },
+    /// The channel's queue was cleared before the message was sent
+    Cleared {
+        /// The channel name
+        channel: String,
+    },
+    /// Twitch didn't recognize the command, e.g. because of a typo in a raw line
+    ///
+    /// The receipt of the line that caused it fails with this.
+    UnknownCommand {
+        /// The command
+        command: String,
+        /// The line that was sent, if it was sent with a writer
+        line: Option<String>,
+    },
+}
+
+impl std::fmt::Display for SendFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Duplicate { channel } => {
+                write!(f, "duplicate message was dropped on '{}'", channel)
+            }
+            Self::Dropped => f.write_str("message was dropped before it was sent"),
+            Self::Parted { channel } => {
+                write!(f, "left channel '{}' before the message was sent", channel)
+            }
+            Self::Closed => f.write_str("connection closed before the message was sent"),
+            Self::Expired { channel } => {
+                write!(f, "message expired before it was sent to '{}'", channel)
+            }
+            Self::Cleared { channel } => {
+                write!(
+                    f,
+                    "queue for '{}' was cleared before the message was sent",
+                    channel
+                )
+            }
+            Self::UnknownCommand {
+                line: Some(line), ..
+            } => write!(f, "unknown command in '{}'", line),
+            Self::UnknownCommand { command, .. } => {
+                write!(f, "unknown command '{}'", command)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SendFailure {}
@@ -1,34 +1,161 @@
-use crate::rate_limit::{RateClass, RateLimit};
+use super::stats::QueueStats;
+use crate::{
+    rate_limit::{RateClass, RateLimit},
+    runner::SendFailure,
+    writer::{Outgoing, Priority},
+};
 use futures_lite::{AsyncWrite, AsyncWriteExt};
-use std::{collections::VecDeque, time::Duration};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// How many channels can be joined per [JOIN_WINDOW]
+///
+/// [JOIN_WINDOW]: ./constant.JOIN_WINDOW.html
+pub const JOINS_PER_WINDOW: u64 = 20;
+
+/// The window for the join rate limit
+pub const JOIN_WINDOW: Duration = Duration::from_secs(10);
+
+/// How long Twitch remembers your previous message for duplicate detection
+pub const DUPLICATE_WINDOW: Duration = Duration::from_secs(30);
+
+/// The invisible suffix appended to a duplicate message
+///
+/// This is a space followed by the `U+E0000` tag character, which is what most clients use.
+pub const DUPLICATE_SUFFIX: &str = " \u{E0000}";
+
+/// How to handle sending the same message twice in a row to a channel
+///
+/// Twitch drops a message identical to your previous one if it is sent within [DUPLICATE_WINDOW].
+///
+/// [DUPLICATE_WINDOW]: ./constant.DUPLICATE_WINDOW.html
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum DuplicateStrategy {
+    /// Send the message as is. This is the default.
+    #[default]
+    Allow,
+    /// Append an invisible suffix to the duplicate message.
+    ///
+    /// Only plain chat text gets the suffix, commands (e.g. `/ban`) are sent as is.
+    Suffix,
+    /// Hold the duplicate message (and everything queued after it) until the window expires
+    Delay,
+}
+
+/// A queue per [Priority]
+#[derive(Default)]
+pub struct Lanes {
+    lanes: [VecDeque<Outgoing>; 3],
+}
+
+impl Lanes {
+    pub fn push_back(&mut self, priority: Priority, msg: Outgoing) {
+        self.lanes[priority as usize].push_back(msg)
+    }
+
+    pub fn push_front(&mut self, priority: Priority, msg: Outgoing) {
+        self.lanes[priority as usize].push_front(msg)
+    }
+
+    /// Pop the next message with a priority of at least `max`
+    pub fn pop_front(&mut self, max: Priority) -> Option<(Priority, Outgoing)> {
+        Priority::ALL
+            .iter()
+            .take_while(|&&p| p <= max)
+            .find_map(|&p| self.lanes[p as usize].pop_front().map(|data| (p, data)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+
+    /// Fail every queued message with `failure`, returning how many there were
+    pub fn fail_all(&mut self, failure: SendFailure) -> usize {
+        let mut count = 0;
+        for msg in self.lanes.iter_mut().flat_map(|lane| lane.drain(..)) {
+            msg.failed(failure.clone());
+            count += 1;
+        }
+        count
+    }
+
+    /// Remove every message that expired before `now`
+    pub fn remove_expired(&mut self, now: Instant) -> Vec<Outgoing> {
+        let mut expired = vec![];
+        for lane in &mut self.lanes {
+            if !lane.iter().any(|msg| msg.is_expired(now)) {
+                continue;
+            }
+            let (old, keep): (VecDeque<_>, _) = lane.drain(..).partition(|msg| msg.is_expired(now));
+            expired.extend(old);
+            *lane = keep;
+        }
+        expired
+    }
+}
 
 pub struct RateLimitedEncoder {
     pub(crate) rate_limit: RateLimit,
-    pub(crate) queue: VecDeque<Box<[u8]>>,
+    pub(crate) queue: Lanes,
+    pub(crate) duplicates: DuplicateStrategy,
+    pub(crate) last: Option<(Box<[u8]>, Instant)>,
+    pub(crate) time_to_live: Option<Duration>,
+    pub(crate) stats: QueueStats,
 }
 
 impl RateLimitedEncoder {
+    /// Drain messages with a priority of at least `max`
     pub async fn drain_until_blocked<W>(
         &mut self,
         name: &str,
+        max: Priority,
         limit: &mut u64,
         sink: &mut W,
     ) -> std::io::Result<()>
     where
         W: AsyncWrite + Send + Sync + Unpin + ?Sized,
     {
-        while let Some(data) = self.queue.pop_front() {
+        while let Some((priority, mut msg)) = self.queue.pop_front(max) {
+            if self.is_duplicate(&msg.data) {
+                match self.duplicates {
+                    DuplicateStrategy::Allow => {}
+                    DuplicateStrategy::Suffix if !is_command(&msg.data) => {
+                        msg.data = with_suffix(&msg.data)
+                    }
+                    DuplicateStrategy::Suffix => {}
+                    DuplicateStrategy::Delay => {
+                        log::debug!(
+                            target: "twitchchat::rate_limit",
+                            "delaying duplicate message for '{}'",
+                            name
+                        );
+                        self.queue.push_front(priority, msg);
+                        break;
+                    }
+                }
+            }
+
             match self.rate_limit.consume(1) {
                 Ok(..) => {
                     *limit = limit.saturating_sub(1);
                     log::trace!(
                         target: "twitchchat::encoder",
                         "> {}",
-                        std::str::from_utf8(&*data).unwrap().escape_debug()
+                        std::str::from_utf8(&*msg.data).unwrap().escape_debug()
                     );
-                    sink.write_all(&*data).await?;
+                    sink.write_all(&*msg.data).await?;
+                    self.last
+                        .replace((message_body(&msg.data).into(), Instant::now()));
+                    self.stats.sent += 1;
+                    self.stats.waited += msg.queued_at.elapsed();
+                    msg.delivered();
                 }
                 Err(..) => {
+                    self.queue.push_front(priority, msg);
+                    self.stats.rate_limit_hits += 1;
                     log::warn!(
                         target: "twitchchat::rate_limit",
                         "local rate limit for '{}' hit",
@@ -45,21 +172,260 @@ impl RateLimitedEncoder {
         Ok(())
     }
 
-    pub fn enqueue(&mut self, msg: Box<[u8]>) {
-        self.queue.push_back(msg);
+    pub fn enqueue(&mut self, priority: Priority, mut msg: Outgoing) {
+        if msg.expires_at.is_none() {
+            msg.expires_at = self.time_to_live.map(|ttl| Instant::now() + ttl);
+        }
+        self.queue.push_back(priority, msg);
+    }
+
+    fn is_duplicate(&self, data: &[u8]) -> bool {
+        match &self.last {
+            Some((last, at)) => at.elapsed() < DUPLICATE_WINDOW && **last == *message_body(data),
+            None => false,
+        }
     }
 }
 
+/// Get the trailing `data` of an encoded `PRIVMSG`, ignoring its tags
+fn message_body(data: &[u8]) -> &[u8] {
+    let data = data.strip_suffix(b"\r\n").unwrap_or(data);
+
+    let data = match data.first() {
+        Some(b'@') => match data.iter().position(|&c| c == b' ') {
+            Some(pos) => &data[pos + 1..],
+            None => data,
+        },
+        _ => data,
+    };
+
+    match data.windows(2).position(|w| w == b" :") {
+        Some(pos) => &data[pos + 2..],
+        None => data,
+    }
+}
+
+/// Whether the trailing `data` of an encoded `PRIVMSG` is a chat command, e.g. `/ban` or `.ban`
+fn is_command(data: &[u8]) -> bool {
+    matches!(message_body(data).first(), Some(b'/') | Some(b'.'))
+}
+
+fn with_suffix(data: &[u8]) -> Box<[u8]> {
+    let head = data.strip_suffix(b"\r\n").unwrap_or(data);
+    let mut out = Vec::with_capacity(data.len() + DUPLICATE_SUFFIX.len() + 2);
+    out.extend_from_slice(head);
+    out.extend_from_slice(DUPLICATE_SUFFIX.as_bytes());
+    out.extend_from_slice(b"\r\n");
+    out.into_boxed_slice()
+}
+
 pub struct PreviousRate {
     pub cap: u64,
     pub period: Duration,
 }
 
-impl Default for PreviousRate {
-    fn default() -> Self {
+impl PreviousRate {
+    pub fn from_class(rate_class: RateClass) -> Self {
         Self {
-            cap: RateClass::Regular.tickets(),
+            cap: rate_class.tickets(),
             period: RateClass::period(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future::block_on;
+
+    fn encoder(duplicates: DuplicateStrategy) -> RateLimitedEncoder {
+        RateLimitedEncoder {
+            rate_limit: RateLimit::from_class(RateClass::Regular),
+            queue: Lanes::default(),
+            duplicates,
+            last: None,
+            time_to_live: None,
+            stats: QueueStats::default(),
+        }
+    }
+
+    fn drain(enc: &mut RateLimitedEncoder, msgs: &[&str]) -> String {
+        for msg in msgs {
+            enc.enqueue(Priority::Normal, Outgoing::new(msg.as_bytes().into()));
+        }
+        let mut out = vec![];
+        block_on(enc.drain_until_blocked("#museun", Priority::Bulk, &mut 100, &mut out)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn message_body() {
+        let inputs: &[(&[u8], &[u8])] = &[
+            (b"PRIVMSG #museun :hello world\r\n", b"hello world"),
+            (
+                b"@reply-parent-msg-id=abc PRIVMSG #museun :hello\r\n",
+                b"hello",
+            ),
+            (b"PRIVMSG #museun :/me :hello\r\n", b"/me :hello"),
+            (b"garbage", b"garbage"),
+        ];
+        for (input, expected) in inputs {
+            assert_eq!(super::message_body(input), *expected);
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let mut enc = RateLimitedEncoder {
+            rate_limit: RateLimit::full(2, Duration::from_secs(30)),
+            ..encoder(DuplicateStrategy::Allow)
+        };
+        let msgs = &["PRIVMSG #museun :a\r\n"; 3];
+        assert_eq!(drain(&mut enc, msgs).lines().count(), 2);
+
+        assert_eq!(enc.stats.sent, 2);
+        assert_eq!(enc.stats.rate_limit_hits, 1);
+        assert_eq!(enc.queue.len(), 1);
+    }
+
+    #[test]
+    fn allow_duplicates() {
+        let mut enc = encoder(DuplicateStrategy::Allow);
+        let msg = "PRIVMSG #museun :hello\r\n";
+        assert_eq!(drain(&mut enc, &[msg, msg]), msg.repeat(2));
+    }
+
+    #[test]
+    fn suffix_duplicates() {
+        let mut enc = encoder(DuplicateStrategy::Suffix);
+        let msg = "PRIVMSG #museun :hello\r\n";
+        let suffixed = format!("PRIVMSG #museun :hello{}\r\n", DUPLICATE_SUFFIX);
+
+        assert_eq!(
+            drain(&mut enc, &[msg, msg, msg]),
+            format!("{}{}{}", msg, suffixed, msg)
+        );
+
+        // a reply with the same text is still a duplicate
+        let reply = "@reply-parent-msg-id=abc PRIVMSG #museun :hello\r\n";
+        assert_eq!(
+            drain(&mut enc, &[reply]),
+            format!(
+                "@reply-parent-msg-id=abc PRIVMSG #museun :hello{}\r\n",
+                DUPLICATE_SUFFIX
+            )
+        );
+    }
+
+    #[test]
+    fn suffix_skips_commands() {
+        let mut enc = encoder(DuplicateStrategy::Suffix);
+        for msg in &[
+            "PRIVMSG #museun :/ban foo\r\n",
+            "PRIVMSG #museun :.ban foo\r\n",
+        ] {
+            assert_eq!(drain(&mut enc, &[msg, msg]), msg.repeat(2));
+        }
+    }
+
+    #[test]
+    fn delay_duplicates() {
+        let mut enc = encoder(DuplicateStrategy::Delay);
+        let msg = "PRIVMSG #museun :hello\r\n";
+        let other = "PRIVMSG #museun :world\r\n";
+
+        assert_eq!(drain(&mut enc, &[msg, msg, other]), msg);
+        assert_eq!(enc.queue.len(), 2);
+
+        // pretend the window has expired
+        enc.last.as_mut().unwrap().1 -= DUPLICATE_WINDOW;
+        assert_eq!(drain(&mut enc, &[]), format!("{}{}", msg, other));
+    }
+
+    #[test]
+    fn priority_order() {
+        let mut enc = encoder(DuplicateStrategy::Allow);
+        let msg = |data: &[u8]| Outgoing::new(data.into());
+        enc.enqueue(Priority::Bulk, msg(b"PRIVMSG #museun :bulk\r\n"));
+        enc.enqueue(Priority::Normal, msg(b"PRIVMSG #museun :normal\r\n"));
+        enc.enqueue(Priority::Moderation, msg(b"PRIVMSG #museun :/ban foo\r\n"));
+
+        let mut out = vec![];
+        block_on(enc.drain_until_blocked("#museun", Priority::Moderation, &mut 100, &mut out))
+            .unwrap();
+        assert_eq!(out, b"PRIVMSG #museun :/ban foo\r\n");
+        assert_eq!(enc.queue.len(), 2);
+
+        assert_eq!(
+            drain(&mut enc, &[]),
+            "PRIVMSG #museun :normal\r\nPRIVMSG #museun :bulk\r\n"
+        );
+    }
+
+    #[test]
+    fn receipts() {
+        let mut enc = encoder(DuplicateStrategy::Allow);
+        enc.rate_limit = RateLimit::full(1, RateClass::period());
+
+        let (tx, sent) = crate::writer::receipt();
+        let mut msg = Outgoing::new(b"PRIVMSG #museun :a\r\n"[..].into());
+        msg.receipt.replace(tx);
+        enc.enqueue(Priority::Normal, msg);
+
+        let (tx, parted) = crate::writer::receipt();
+        let mut msg = Outgoing::new(b"PRIVMSG #museun :b\r\n"[..].into());
+        msg.receipt.replace(tx);
+        enc.enqueue(Priority::Normal, msg);
+
+        drain(&mut enc, &[]);
+        assert!(block_on(sent).is_ok());
+
+        enc.queue.fail_all(SendFailure::Parted {
+            channel: "#museun".into(),
+        });
+        assert_eq!(
+            block_on(parted).unwrap_err(),
+            SendFailure::Parted {
+                channel: "#museun".into()
+            }
+        );
+    }
+
+    #[test]
+    fn expired() {
+        let mut enc = encoder(DuplicateStrategy::Allow);
+        enc.time_to_live = Some(Duration::from_secs(10));
+
+        let msg = |data: &[u8]| Outgoing::new(data.into());
+        let mut expiring = msg(b"PRIVMSG #museun :a\r\n");
+        expiring.expires_at = Some(Instant::now());
+        enc.enqueue(Priority::Normal, expiring);
+        enc.enqueue(Priority::Bulk, msg(b"PRIVMSG #museun :b\r\n"));
+        enc.enqueue(Priority::Bulk, msg(b"PRIVMSG #museun :c\r\n"));
+
+        let expired = enc.queue.remove_expired(Instant::now());
+        assert_eq!(expired.len(), 1);
+        assert_eq!(&*expired[0].data, b"PRIVMSG #museun :a\r\n");
+        assert_eq!(enc.queue.len(), 2);
+
+        // the channel's time-to-live applies to the others
+        let expired = enc
+            .queue
+            .remove_expired(Instant::now() + Duration::from_secs(11));
+        assert_eq!(expired.len(), 2);
+        assert_eq!(enc.queue.len(), 0);
+    }
+
+    #[test]
+    fn rate_limited_messages_are_kept() {
+        let mut enc = encoder(DuplicateStrategy::Allow);
+        enc.rate_limit = RateLimit::full(1, RateClass::period());
+
+        let out = drain(
+            &mut enc,
+            &["PRIVMSG #museun :a\r\n", "PRIVMSG #museun :b\r\n"],
+        );
+        assert_eq!(out, "PRIVMSG #museun :a\r\n");
+        assert_eq!(enc.queue.len(), 1);
+    }
+}
@@ -0,0 +1,320 @@
+use futures_lite::{AsyncRead, AsyncWrite};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+/// A snapshot of what the [AsyncRunner] has been doing
+///
+/// This is created by [AsyncRunner::stats].
+///
+/// Reconnects aren't counted here. A runner is a single connection and doesn't
+/// reconnect on its own, so count them where you call [AsyncRunner::connect].
+///
+/// [AsyncRunner]: ./struct.AsyncRunner.html
+/// [AsyncRunner::stats]: ./struct.AsyncRunner.html#method.stats
+/// [AsyncRunner::connect]: ./struct.AsyncRunner.html#method.connect
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunnerStats {
+    /// How many messages were received
+    pub messages_received: u64,
+    /// How many messages were sent from the queues
+    pub messages_sent: u64,
+    /// How many bytes were read from the connection
+    pub bytes_received: u64,
+    /// How many bytes were written to the connection
+    pub bytes_sent: u64,
+    /// How many tokens are available in the global rate limit
+    pub global_tokens: u64,
+    /// How many times a local rate limit was hit, including the global one
+    pub rate_limit_hits: u64,
+    /// How long sent messages waited in the queues, in total
+    pub time_waiting: Duration,
+    /// The round-trip time of the last PING we sent
+    pub last_ping_rtt: Option<Duration>,
+    /// Stats for each channel you're on
+    pub channels: HashMap<String, ChannelStats>,
+}
+
+/// A snapshot of what a single channel has been doing
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelStats {
+    /// How many messages were received on this channel
+    pub messages_received: u64,
+    /// How many messages were sent to this channel
+    pub messages_sent: u64,
+    /// How many messages are waiting in this channel's queue
+    pub queued: usize,
+    /// How many tokens are available in this channel's rate limit
+    pub available_tokens: u64,
+    /// How many times this channel's rate limit was hit
+    pub rate_limit_hits: u64,
+}
+
+#[cfg(feature = "metrics")]
+impl RunnerStats {
+    /// Record this snapshot with the installed [metrics] recorder
+    ///
+    /// Totals are recorded as counters and everything else as gauges. They
+    /// are all prefixed with `twitchchat_`, and per-channel metrics are
+    /// labeled with `channel`.
+    ///
+    /// [metrics]: https://docs.rs/metrics
+    pub fn record_metrics(&self) {
+        use metrics::{counter, gauge};
+
+        counter!("twitchchat_messages_received").absolute(self.messages_received);
+        counter!("twitchchat_messages_sent").absolute(self.messages_sent);
+        counter!("twitchchat_bytes_received").absolute(self.bytes_received);
+        counter!("twitchchat_bytes_sent").absolute(self.bytes_sent);
+        counter!("twitchchat_rate_limit_hits").absolute(self.rate_limit_hits);
+        gauge!("twitchchat_global_tokens").set(self.global_tokens as f64);
+        gauge!("twitchchat_time_waiting_seconds").set(self.time_waiting.as_secs_f64());
+        if let Some(rtt) = self.last_ping_rtt {
+            gauge!("twitchchat_ping_rtt_seconds").set(rtt.as_secs_f64());
+        }
+
+        for (name, channel) in &self.channels {
+            let channel_label = [("channel", name.clone())];
+            counter!("twitchchat_channel_messages_received", &channel_label)
+                .absolute(channel.messages_received);
+            counter!("twitchchat_channel_messages_sent", &channel_label)
+                .absolute(channel.messages_sent);
+            counter!("twitchchat_channel_rate_limit_hits", &channel_label)
+                .absolute(channel.rate_limit_hits);
+            gauge!("twitchchat_channel_queued", &channel_label).set(channel.queued as f64);
+            gauge!("twitchchat_channel_available_tokens", &channel_label)
+                .set(channel.available_tokens as f64);
+        }
+    }
+}
+
+/// Counters kept by the runner
+#[derive(Debug, Default)]
+pub struct Counters {
+    pub bytes_received: Arc<AtomicU64>,
+    pub bytes_sent: Arc<AtomicU64>,
+    pub messages_received: u64,
+    pub global_rate_limit_hits: u64,
+    pub ping_sent_at: Option<Instant>,
+    pub last_ping_rtt: Option<Duration>,
+}
+
+/// Counters kept by a queue of outgoing messages
+#[derive(Debug, Default, Copy, Clone)]
+pub struct QueueStats {
+    pub sent: u64,
+    pub rate_limit_hits: u64,
+    pub waited: Duration,
+}
+
+impl std::ops::AddAssign for QueueStats {
+    fn add_assign(&mut self, other: Self) {
+        self.sent += other.sent;
+        self.rate_limit_hits += other.rate_limit_hits;
+        self.waited += other.waited;
+    }
+}
+
+/// Counts the bytes that go through an `AsyncRead` or `AsyncWrite`
+pub struct Counted<T> {
+    inner: T,
+    count: Arc<AtomicU64>,
+}
+
+impl<T> Counted<T> {
+    pub fn new(inner: T, count: Arc<AtomicU64>) -> Self {
+        Self { inner, count }
+    }
+}
+
+impl<T> AsyncRead for Counted<T>
+where
+    T: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            self.count.fetch_add(n as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+}
+
+impl<T> AsyncWrite for Counted<T>
+where
+    T: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            self.count.fetch_add(n as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::{future::block_on, AsyncReadExt as _, AsyncWriteExt as _};
+
+    #[test]
+    fn counted() {
+        let count = Arc::new(AtomicU64::new(0));
+
+        let mut read = Counted::new(futures_lite::io::Cursor::new(b"hello world"), count.clone());
+        let mut buf = [0; 5];
+        block_on(read.read_exact(&mut buf)).unwrap();
+        assert_eq!(count.load(Ordering::Relaxed), 5);
+
+        let mut write = Counted::new(vec![], count.clone());
+        block_on(write.write_all(b"PING :1\r\n")).unwrap();
+        assert_eq!(count.load(Ordering::Relaxed), 5 + 9);
+    }
+
+    #[test]
+    fn queue_stats() {
+        let mut stats = QueueStats::default();
+        stats += QueueStats {
+            sent: 2,
+            rate_limit_hits: 1,
+            waited: Duration::from_secs(1),
+        };
+        stats += QueueStats {
+            sent: 3,
+            rate_limit_hits: 0,
+            waited: Duration::from_secs(2),
+        };
+        assert_eq!(stats.sent, 5);
+        assert_eq!(stats.rate_limit_hits, 1);
+        assert_eq!(stats.waited, Duration::from_secs(3));
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn record_metrics() {
+        use metrics::{
+            Counter, CounterFn, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder,
+            SharedString, Unit,
+        };
+        use std::sync::Mutex;
+
+        type Values = Arc<Mutex<HashMap<String, f64>>>;
+
+        #[derive(Default)]
+        struct TestRecorder {
+            values: Values,
+        }
+
+        struct Handle {
+            name: String,
+            values: Values,
+        }
+
+        impl Handle {
+            fn set(&self, value: f64) {
+                self.values.lock().unwrap().insert(self.name.clone(), value);
+            }
+        }
+
+        impl CounterFn for Handle {
+            // the stats are only ever set
+            fn increment(&self, _value: u64) {}
+            fn absolute(&self, value: u64) {
+                self.set(value as f64)
+            }
+        }
+
+        impl GaugeFn for Handle {
+            fn increment(&self, _value: f64) {}
+            fn decrement(&self, _value: f64) {}
+            fn set(&self, value: f64) {
+                Handle::set(self, value)
+            }
+        }
+
+        impl TestRecorder {
+            fn handle(&self, key: &Key) -> Arc<Handle> {
+                let mut name = key.name().to_string();
+                for label in key.labels() {
+                    name.push_str(&format!("{{{}={}}}", label.key(), label.value()));
+                }
+                Arc::new(Handle {
+                    name,
+                    values: self.values.clone(),
+                })
+            }
+        }
+
+        impl Recorder for TestRecorder {
+            fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+                Counter::from_arc(self.handle(key))
+            }
+            fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+                Gauge::from_arc(self.handle(key))
+            }
+            fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+                Histogram::noop()
+            }
+        }
+
+        let mut stats = RunnerStats {
+            messages_received: 3,
+            messages_sent: 2,
+            global_tokens: 18,
+            last_ping_rtt: Some(Duration::from_millis(250)),
+            ..RunnerStats::default()
+        };
+        stats.channels.insert(
+            "#museun".to_string(),
+            ChannelStats {
+                messages_sent: 2,
+                queued: 4,
+                ..ChannelStats::default()
+            },
+        );
+
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || stats.record_metrics());
+
+        let values = recorder.values.lock().unwrap();
+        for (name, value) in &[
+            ("twitchchat_messages_received", 3.0),
+            ("twitchchat_messages_sent", 2.0),
+            ("twitchchat_global_tokens", 18.0),
+            ("twitchchat_ping_rtt_seconds", 0.25),
+            ("twitchchat_channel_messages_sent{channel=#museun}", 2.0),
+            ("twitchchat_channel_queued{channel=#museun}", 4.0),
+        ] {
+            assert_eq!(values.get(*name), Some(value), "{}", name);
+        }
+    }
+}
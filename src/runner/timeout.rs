@@ -21,6 +21,16 @@ pub const WINDOW: Duration = Duration::from_secs(45);
 pub const TIMEOUT: Duration = Duration::from_secs(10);
 pub const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(30);
 
-pub async fn next_delay() {
-    futures_timer::Delay::new(WINDOW).await
+pub async fn next_delay(window: Duration) {
+    futures_timer::Delay::new(window).await
+}
+
+/// Wait until `deadline`, or forever if there isn't one
+pub async fn wait_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => {
+            futures_timer::Delay::new(deadline.saturating_duration_since(Instant::now())).await
+        }
+        None => futures_lite::future::pending().await,
+    }
 }
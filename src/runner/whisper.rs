@@ -0,0 +1,225 @@
+use super::stats::QueueStats;
+use crate::{rate_limit::RateLimit, runner::SendFailure, writer::Outgoing};
+use futures_lite::{AsyncWrite, AsyncWriteExt};
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// How many whispers can be sent per second
+pub const WHISPERS_PER_SECOND: u64 = 3;
+
+/// How many whispers can be sent per minute
+pub const WHISPERS_PER_MINUTE: u64 = 100;
+
+/// How many different users can be whispered per day
+pub const WHISPER_RECIPIENTS_PER_DAY: usize = 40;
+
+const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Get the recipient of a `PRIVMSG jtv :/w <user> <message>` whisper
+pub(crate) fn recipient<'a>(target: &str, data: &'a str) -> Option<&'a str> {
+    if target != "jtv" || !data.starts_with("/w ") {
+        return None;
+    }
+    data[3..].split(' ').next().filter(|s| !s.is_empty())
+}
+
+/// A rate limited queue for whispers
+///
+/// Whispers aren't tied to a channel, so they have their own limits.
+pub struct WhisperQueue {
+    per_second: RateLimit,
+    per_minute: RateLimit,
+    recipients: HashSet<String>,
+    recipients_since: Instant,
+    queue: VecDeque<(String, Outgoing)>,
+    pub(crate) stats: QueueStats,
+}
+
+impl Default for WhisperQueue {
+    fn default() -> Self {
+        Self {
+            per_second: RateLimit::full(WHISPERS_PER_SECOND, Duration::from_secs(1)),
+            per_minute: RateLimit::full(WHISPERS_PER_MINUTE, Duration::from_secs(60)),
+            recipients: HashSet::new(),
+            recipients_since: Instant::now(),
+            queue: VecDeque::new(),
+            stats: QueueStats::default(),
+        }
+    }
+}
+
+impl WhisperQueue {
+    pub fn enqueue(&mut self, recipient: &str, msg: Outgoing) {
+        self.queue.push_back((recipient.to_lowercase(), msg));
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Remove every whisper that expired before `now`
+    pub fn remove_expired(&mut self, now: Instant) -> Vec<Outgoing> {
+        if !self.queue.iter().any(|(_, msg)| msg.is_expired(now)) {
+            return vec![];
+        }
+        let (expired, keep): (VecDeque<_>, _) = self
+            .queue
+            .drain(..)
+            .partition(|(_, msg)| msg.is_expired(now));
+        self.queue = keep;
+        expired.into_iter().map(|(_, msg)| msg).collect()
+    }
+
+    pub async fn drain_until_blocked<W>(
+        &mut self,
+        limit: &mut u64,
+        sink: &mut W,
+    ) -> std::io::Result<()>
+    where
+        W: AsyncWrite + Send + Sync + Unpin + ?Sized,
+    {
+        if self.recipients_since.elapsed() > DAY {
+            self.recipients.clear();
+            self.recipients_since = Instant::now();
+        }
+
+        while *limit > 0 {
+            let (recipient, _) = match self.queue.front() {
+                Some(front) => front,
+                None => break,
+            };
+
+            if !self.recipients.contains(recipient)
+                && self.recipients.len() >= WHISPER_RECIPIENTS_PER_DAY
+            {
+                log::warn!(
+                    target: "twitchchat::rate_limit",
+                    "daily whisper recipient limit hit, dropping whisper to '{}'",
+                    recipient
+                );
+                if let Some((_, msg)) = self.queue.pop_front() {
+                    msg.failed(SendFailure::Dropped)
+                }
+                continue;
+            }
+
+            if !has_tokens(&mut self.per_second) || !has_tokens(&mut self.per_minute) {
+                log::warn!(target: "twitchchat::rate_limit", "local rate limit for whispers hit");
+                self.stats.rate_limit_hits += 1;
+                break;
+            }
+
+            let (recipient, msg) = self.queue.pop_front().unwrap();
+            // these cannot fail, we checked them above
+            let _ = self.per_second.consume(1);
+            let _ = self.per_minute.consume(1);
+            *limit -= 1;
+
+            log::trace!(
+                target: "twitchchat::encoder",
+                "> {}",
+                std::str::from_utf8(&*msg.data).unwrap().escape_debug()
+            );
+            sink.write_all(&*msg.data).await?;
+            self.recipients.insert(recipient);
+            self.stats.sent += 1;
+            self.stats.waited += msg.queued_at.elapsed();
+            msg.delivered();
+        }
+
+        Ok(())
+    }
+}
+
+fn has_tokens(rate: &mut RateLimit) -> bool {
+    // consuming nothing refills the bucket
+    matches!(rate.consume(0), Ok(n) if n > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future::block_on;
+
+    fn whisper(user: &str, data: &str) -> Outgoing {
+        Outgoing::new(
+            format!("PRIVMSG jtv :/w {} {}\r\n", user, data)
+                .into_bytes()
+                .into_boxed_slice(),
+        )
+    }
+
+    #[test]
+    fn recipient() {
+        assert_eq!(super::recipient("jtv", "/w museun hello"), Some("museun"));
+        assert_eq!(super::recipient("jtv", "/w museun"), Some("museun"));
+        assert_eq!(super::recipient("#museun", "/w museun hello"), None);
+        assert_eq!(super::recipient("jtv", "/me hello"), None);
+        assert_eq!(super::recipient("jtv", "/w "), None);
+    }
+
+    #[test]
+    fn per_second() {
+        let mut queue = WhisperQueue::default();
+        for i in 0..5 {
+            queue.enqueue("museun", whisper("museun", &i.to_string()));
+        }
+
+        let mut out = vec![];
+        let mut limit = 20;
+        block_on(queue.drain_until_blocked(&mut limit, &mut out)).unwrap();
+
+        assert_eq!(limit, 20 - WHISPERS_PER_SECOND);
+        assert_eq!(queue.len(), 5 - WHISPERS_PER_SECOND as usize);
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "PRIVMSG jtv :/w museun 0\r\nPRIVMSG jtv :/w museun 1\r\nPRIVMSG jtv :/w museun 2\r\n"
+        );
+    }
+
+    #[test]
+    fn global_limit() {
+        let mut queue = WhisperQueue::default();
+        for i in 0..3 {
+            queue.enqueue("museun", whisper("museun", &i.to_string()));
+        }
+
+        let mut out = vec![];
+        let mut limit = 1;
+        block_on(queue.drain_until_blocked(&mut limit, &mut out)).unwrap();
+
+        assert_eq!(limit, 0);
+        assert_eq!(queue.len(), 2);
+    }
+
+    #[test]
+    fn recipients_per_day() {
+        let mut queue = WhisperQueue {
+            per_second: RateLimit::full(1000, Duration::from_secs(1)),
+            ..WhisperQueue::default()
+        };
+
+        for i in 0..WHISPER_RECIPIENTS_PER_DAY + 1 {
+            let user = format!("user{}", i);
+            queue.enqueue(&user, whisper(&user, "hello"));
+        }
+        // already a known recipient
+        queue.enqueue("USER0", whisper("USER0", "hello again"));
+
+        let mut out = vec![];
+        let mut limit = 1000;
+        block_on(queue.drain_until_blocked(&mut limit, &mut out)).unwrap();
+
+        assert_eq!(queue.len(), 0);
+        let out = std::str::from_utf8(&out).unwrap();
+        assert_eq!(out.lines().count(), WHISPER_RECIPIENTS_PER_DAY + 1);
+        assert!(!out.contains(&format!("user{} ", WHISPER_RECIPIENTS_PER_DAY)));
+        assert!(out.ends_with("PRIVMSG jtv :/w USER0 hello again\r\n"));
+    }
+}
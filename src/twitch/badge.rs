@@ -1,3 +1,6 @@
+use crate::irc::Tags;
+use std::collections::HashMap;
+
 /// The kind of the [badges] that are associated with messages.
 ///
 /// Any unknonw (e.g. custom badges/sub events, etc) are placed into the [Unknown] variant.
@@ -30,10 +33,121 @@ pub enum BadgeKind<'a> {
     VIP,
     /// Partner badge
     Partner,
+    /// Founder badge
+    Founder,
+    /// Artist badge
+    Artist,
+    /// No audio badge
+    NoAudio,
+    /// GLHF pledge badge
+    GlhfPledge,
+    /// Predictions badge
+    Predictions,
     /// Unknown badge. Likely a custom badge
     Unknown(&'a str),
 }
 
+impl<'a> BadgeKind<'a> {
+    /// Parses the badge kind from its name
+    pub fn from_name(name: &'a str) -> Self {
+        use BadgeKind::*;
+        match name {
+            "admin" => Admin,
+            "bits" => Bits,
+            "broadcaster" => Broadcaster,
+            "global_mod" => GlobalMod,
+            "moderator" => Moderator,
+            "subscriber" => Subscriber,
+            "staff" => Staff,
+            "turbo" => Turbo,
+            "premium" => Premium,
+            "vip" => VIP,
+            "partner" => Partner,
+            "founder" => Founder,
+            "artist-badge" => Artist,
+            "no_audio" => NoAudio,
+            "glhf-pledge" => GlhfPledge,
+            "predictions" => Predictions,
+            badge => Unknown(badge),
+        }
+    }
+
+    /// Gets the name of this badge kind, as Twitch sends it
+    pub fn as_str(&self) -> &'a str {
+        use BadgeKind::*;
+        match self {
+            Admin => "admin",
+            Bits => "bits",
+            Broadcaster => "broadcaster",
+            GlobalMod => "global_mod",
+            Moderator => "moderator",
+            Subscriber => "subscriber",
+            Staff => "staff",
+            Turbo => "turbo",
+            Premium => "premium",
+            VIP => "vip",
+            Partner => "partner",
+            Founder => "founder",
+            Artist => "artist-badge",
+            NoAudio => "no_audio",
+            GlhfPledge => "glhf-pledge",
+            Predictions => "predictions",
+            Unknown(badge) => badge,
+        }
+    }
+}
+
+/// The tier of a subscription, as encoded in the `subscriber` badge version
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum SubscriberTier {
+    /// A Tier-1 (or Prime) subscription
+    Tier1,
+    /// A Tier-2 subscription
+    Tier2,
+    /// A Tier-3 subscription
+    Tier3,
+}
+
+/// Subscriber data parsed from the `subscriber` badge
+///
+/// Twitch encodes the tier into the badge version, e.g. `subscriber/3012` is
+/// the `12` month badge for a Tier-3 subscription.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct SubscriberBadge {
+    /// The tier of the subscription
+    pub tier: SubscriberTier,
+    /// The number of months.
+    ///
+    /// From a `Badge` this is the badge's month bracket. From a
+    /// [`BadgeSet`](./struct.BadgeSet.html) this is the exact number of months
+    /// found in the `badge-info` tag, if it was available.
+    pub months: u64,
+}
+
+impl SubscriberBadge {
+    fn from_version(version: u64) -> Self {
+        let (tier, months) = match version {
+            v if v >= 3000 => (SubscriberTier::Tier3, v - 3000),
+            v if v >= 2000 => (SubscriberTier::Tier2, v - 2000),
+            v => (SubscriberTier::Tier1, v),
+        };
+        Self { tier, months }
+    }
+}
+
+/// A choice from the `predictions` badge. e.g. `predictions/blue-1`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct PredictionChoice<'a> {
+    /// The color of the outcome, e.g. `blue` or `pink`
+    pub color: &'a str,
+    /// The (1-based) outcome number, if provided
+    pub outcome: Option<u64>,
+}
+
 /// Badges attached to a message
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
@@ -53,28 +167,363 @@ pub struct Badge<'a> {
 impl<'a> Badge<'a> {
     /// Tries to parse a badge from this message part
     pub fn parse(input: &'a str) -> Option<Badge<'a>> {
-        use BadgeKind::*;
-        let mut iter = input.split('/');
-        let kind = match iter.next()? {
-            "admin" => Admin,
-            "bits" => Bits,
-            "broadcaster" => Broadcaster,
-            "global_mod" => GlobalMod,
-            "moderator" => Moderator,
-            "subscriber" => Subscriber,
-            "staff" => Staff,
-            "turbo" => Turbo,
-            "premium" => Premium,
-            "vip" => VIP,
-            "partner" => Partner,
-            badge => Unknown(badge),
-        };
-
+        let mut iter = input.splitn(2, '/');
+        let kind = BadgeKind::from_name(iter.next()?);
         iter.next().map(|data| Badge { kind, data })
     }
+
+    /// The version of this badge
+    pub fn version(&self) -> &'a str {
+        self.data
+    }
+
+    /// The subscriber tier and month bracket, if this is a `subscriber` badge
+    pub fn subscriber(&self) -> Option<SubscriberBadge> {
+        if self.kind != BadgeKind::Subscriber {
+            return None;
+        }
+        self.data.parse().ok().map(SubscriberBadge::from_version)
+    }
+
+    /// The amount of bits, if this is a `bits` badge
+    pub fn bits(&self) -> Option<u64> {
+        if self.kind != BadgeKind::Bits {
+            return None;
+        }
+        self.data.parse().ok()
+    }
+
+    /// The chosen outcome, if this is a `predictions` badge
+    pub fn predictions(&self) -> Option<PredictionChoice<'a>> {
+        if self.kind != BadgeKind::Predictions || self.data.is_empty() {
+            return None;
+        }
+
+        let mut iter = self.data.rsplitn(2, '-');
+        let (tail, head) = (iter.next()?, iter.next());
+        let choice = match (head, tail.parse().ok()) {
+            (Some(color), Some(outcome)) => PredictionChoice {
+                color,
+                outcome: Some(outcome),
+            },
+            _ => PredictionChoice {
+                color: self.data,
+                outcome: None,
+            },
+        };
+        Some(choice)
+    }
 }
 
 /// Metadata to the chat badges
-pub type BadgeInfo<'a> = Badge<'a>;
+///
+/// This is found in the `badge-info` tag, e.g. `subscriber/17`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct BadgeInfo<'a> {
+    /// The kind of the Badge this metadata is for
+    pub kind: BadgeKind<'a>,
+    /// The associated metadata
+    pub data: &'a str,
+}
+
+impl<'a> BadgeInfo<'a> {
+    /// Tries to parse badge info from this message part
+    pub fn parse(input: &'a str) -> Option<BadgeInfo<'a>> {
+        let Badge { kind, data } = Badge::parse(input)?;
+        Some(BadgeInfo { kind, data })
+    }
 
-// TODO tests
+    /// The exact number of months, for `subscriber` and `founder` badges
+    pub fn months(&self) -> Option<u64> {
+        match self.kind {
+            BadgeKind::Subscriber | BadgeKind::Founder => self.data.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A set of badges, and their metadata, attached to a message.
+///
+/// This is parsed once from the `badges` and `badge-info` tags and allows for
+/// constant time lookups by [`BadgeKind`](./enum.BadgeKind.html)
+///
+/// ```
+/// # use twitchchat::twitch::{BadgeKind, BadgeSet, SubscriberTier};
+/// let set = BadgeSet::parse("moderator/1,subscriber/3012", "subscriber/17");
+///
+/// assert!(set.contains(&BadgeKind::Moderator));
+/// assert!(set.is_moderator());
+/// assert!(!set.is_broadcaster());
+///
+/// let sub = set.subscriber().unwrap();
+/// assert_eq!(sub.tier, SubscriberTier::Tier3);
+/// assert_eq!(sub.months, 17);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BadgeSet<'a> {
+    badges: HashMap<BadgeKind<'a>, Badge<'a>>,
+    info: HashMap<BadgeKind<'a>, BadgeInfo<'a>>,
+}
+
+impl<'a> BadgeSet<'a> {
+    /// Parse the set from the raw `badges` and `badge-info` tag values
+    pub fn parse(badges: &'a str, badge_info: &'a str) -> Self {
+        let badges = badges
+            .split(',')
+            .filter_map(Badge::parse)
+            .map(|badge| (badge.kind.clone(), badge))
+            .collect();
+
+        let info = badge_info
+            .split(',')
+            .filter_map(BadgeInfo::parse)
+            .map(|info| (info.kind.clone(), info))
+            .collect();
+
+        Self { badges, info }
+    }
+
+    /// Parse the set from the `badges` and `badge-info` tags
+    pub fn from_tags(tags: &Tags<'a>) -> Self {
+        Self::parse(
+            tags.get("badges").unwrap_or_default(),
+            tags.get("badge-info").unwrap_or_default(),
+        )
+    }
+
+    /// Get the badge for this kind, if it exists
+    pub fn get(&self, kind: &BadgeKind<'a>) -> Option<&Badge<'a>> {
+        self.badges.get(kind)
+    }
+
+    /// Get the badge metadata for this kind, if it exists
+    pub fn get_info(&self, kind: &BadgeKind<'a>) -> Option<&BadgeInfo<'a>> {
+        self.info.get(kind)
+    }
+
+    /// Whether this set contains this kind of badge
+    pub fn contains(&self, kind: &BadgeKind<'a>) -> bool {
+        self.badges.contains_key(kind)
+    }
+
+    /// Whether the user with these badges was a broadcaster
+    pub fn is_broadcaster(&self) -> bool {
+        self.contains(&BadgeKind::Broadcaster)
+    }
+
+    /// Whether the user with these badges was a moderator
+    pub fn is_moderator(&self) -> bool {
+        self.contains(&BadgeKind::Moderator)
+    }
+
+    /// Whether the user with these badges was a vip
+    pub fn is_vip(&self) -> bool {
+        self.contains(&BadgeKind::VIP)
+    }
+
+    /// Whether the user with these badges was a subscriber
+    pub fn is_subscriber(&self) -> bool {
+        self.contains(&BadgeKind::Subscriber)
+    }
+
+    /// Whether the user with these badges was a staff member
+    pub fn is_staff(&self) -> bool {
+        self.contains(&BadgeKind::Staff)
+    }
+
+    /// Whether the user with these badges had turbo
+    pub fn is_turbo(&self) -> bool {
+        self.contains(&BadgeKind::Turbo)
+    }
+
+    /// Whether the user with these badges was a global moderator
+    pub fn is_global_moderator(&self) -> bool {
+        self.contains(&BadgeKind::GlobalMod)
+    }
+
+    /// The subscriber tier and the exact number of months subscribed.
+    ///
+    /// If the `badge-info` tag wasn't provided, this uses the badge's month bracket instead.
+    pub fn subscriber(&self) -> Option<SubscriberBadge> {
+        let mut sub = self.get(&BadgeKind::Subscriber)?.subscriber()?;
+        if let Some(months) = self
+            .get_info(&BadgeKind::Subscriber)
+            .and_then(BadgeInfo::months)
+        {
+            sub.months = months;
+        }
+        Some(sub)
+    }
+
+    /// The amount of bits from the `bits` badge
+    pub fn bits(&self) -> Option<u64> {
+        self.get(&BadgeKind::Bits)?.bits()
+    }
+
+    /// The chosen outcome from the `predictions` badge
+    pub fn predictions(&self) -> Option<PredictionChoice<'a>> {
+        self.get(&BadgeKind::Predictions)?.predictions()
+    }
+
+    /// Get an iterator over the badges in this set
+    pub fn iter(&self) -> impl Iterator<Item = &Badge<'a>> + '_ {
+        self.badges.values()
+    }
+
+    /// Returns how many badges are in this set
+    pub fn len(&self) -> usize {
+        self.badges.len()
+    }
+
+    /// Returns whether there are any badges in this set
+    pub fn is_empty(&self) -> bool {
+        self.badges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_kinds() {
+        let inputs = &[
+            ("founder/0", BadgeKind::Founder),
+            ("moderator/1", BadgeKind::Moderator),
+            ("artist-badge/1", BadgeKind::Artist),
+            ("no_audio/1", BadgeKind::NoAudio),
+            ("glhf-pledge/1", BadgeKind::GlhfPledge),
+            ("predictions/blue-1", BadgeKind::Predictions),
+            (
+                "some-custom-thing/1",
+                BadgeKind::Unknown("some-custom-thing"),
+            ),
+        ];
+
+        for (input, kind) in inputs {
+            let badge = Badge::parse(input).unwrap();
+            assert_eq!(badge.kind, *kind);
+            assert_eq!(badge.kind.as_str(), input.split('/').next().unwrap());
+        }
+    }
+
+    #[test]
+    fn subscriber() {
+        let inputs = &[
+            ("subscriber/0", SubscriberTier::Tier1, 0),
+            ("subscriber/12", SubscriberTier::Tier1, 12),
+            ("subscriber/2006", SubscriberTier::Tier2, 6),
+            ("subscriber/3012", SubscriberTier::Tier3, 12),
+        ];
+
+        for (input, tier, months) in inputs {
+            let sub = Badge::parse(input).unwrap().subscriber().unwrap();
+            assert_eq!(sub.tier, *tier);
+            assert_eq!(sub.months, *months);
+        }
+
+        assert!(Badge::parse("bits/100").unwrap().subscriber().is_none());
+    }
+
+    #[test]
+    fn set_helpers() {
+        let set = BadgeSet::parse("broadcaster/1,vip/1,staff/1,turbo/1", "");
+        assert!(set.is_broadcaster());
+        assert!(set.is_vip());
+        assert!(set.is_staff());
+        assert!(set.is_turbo());
+        assert!(!set.is_moderator());
+        assert!(!set.is_subscriber());
+        assert!(!set.is_global_moderator());
+
+        let set = BadgeSet::parse("moderator/1,subscriber/0,global_mod/1", "");
+        assert!(set.is_moderator());
+        assert!(set.is_subscriber());
+        assert!(set.is_global_moderator());
+        assert!(!set.is_broadcaster());
+    }
+
+    #[test]
+    fn badge_info() {
+        let info = BadgeInfo::parse("subscriber/17").unwrap();
+        assert_eq!(info.months(), Some(17));
+
+        let info = BadgeInfo::parse("founder/35").unwrap();
+        assert_eq!(info.months(), Some(35));
+
+        let info = BadgeInfo::parse("predictions/Yes").unwrap();
+        assert_eq!(info.months(), None);
+    }
+
+    #[test]
+    fn predictions() {
+        let badge = Badge::parse("predictions/blue-1").unwrap();
+        assert_eq!(
+            badge.predictions().unwrap(),
+            PredictionChoice {
+                color: "blue",
+                outcome: Some(1)
+            }
+        );
+
+        let badge = Badge::parse("predictions/pink-2").unwrap();
+        assert_eq!(
+            badge.predictions().unwrap(),
+            PredictionChoice {
+                color: "pink",
+                outcome: Some(2)
+            }
+        );
+
+        let badge = Badge::parse("predictions/gray").unwrap();
+        assert_eq!(
+            badge.predictions().unwrap(),
+            PredictionChoice {
+                color: "gray",
+                outcome: None
+            }
+        );
+    }
+
+    #[test]
+    fn badge_set() {
+        let set = BadgeSet::parse(
+            "broadcaster/1,subscriber/3012,bits/1000,predictions/blue-1",
+            "subscriber/17",
+        );
+
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&BadgeKind::Broadcaster));
+        assert!(!set.contains(&BadgeKind::Moderator));
+
+        assert_eq!(
+            set.subscriber().unwrap(),
+            SubscriberBadge {
+                tier: SubscriberTier::Tier3,
+                months: 17
+            }
+        );
+        assert_eq!(set.bits(), Some(1000));
+        assert_eq!(set.predictions().unwrap().outcome, Some(1));
+    }
+
+    #[test]
+    fn badge_set_empty() {
+        let set = BadgeSet::parse("", "");
+        assert!(set.is_empty());
+        assert!(set.subscriber().is_none());
+    }
+
+    #[test]
+    fn badge_set_without_info() {
+        let set = BadgeSet::parse("subscriber/2006", "");
+        assert_eq!(
+            set.subscriber().unwrap(),
+            SubscriberBadge {
+                tier: SubscriberTier::Tier2,
+                months: 6
+            }
+        );
+    }
+}
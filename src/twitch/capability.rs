@@ -1,8 +1,23 @@
 /// Capability used to enable extra functionality with the protocol
 ///
 /// Without any of these specified, you will just able to read/write basic messages
+///
+/// Capabilities Twitch adds before this crate knows about them can be
+/// requested with [Custom][custom].
+///
+/// ```
+/// # use twitchchat::twitch::Capability;
+/// assert_eq!(Capability::from("twitch.tv/tags"), Capability::Tags);
+/// assert_eq!(
+///     Capability::from("twitch.tv/new-thing"),
+///     Capability::Custom("twitch.tv/new-thing".to_string())
+/// );
+/// assert_eq!(Capability::Commands.as_str(), "twitch.tv/commands");
+/// ```
+///
+/// [custom]: ./enum.Capability.html#variant.Custom
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Capability {
     /// Membership capability
@@ -17,24 +32,62 @@ pub enum Capability {
     ///
     /// Enables many Twitch specific commands
     Commands,
+    /// Any other capability, by its full name, e.g. `twitch.tv/foo`
+    Custom(String),
 }
 
 impl Capability {
-    /// Encode this capability as a string, to be sent to the server
-    pub fn encode_as_str(self) -> &'static str {
+    /// The name of this capability, as sent to the server
+    pub fn as_str(&self) -> &str {
         match self {
-            Self::Membership => "CAP REQ :twitch.tv/membership",
-            Self::Tags => "CAP REQ :twitch.tv/tags",
-            Self::Commands => "CAP REQ :twitch.tv/commands",
+            Self::Membership => "twitch.tv/membership",
+            Self::Tags => "twitch.tv/tags",
+            Self::Commands => "twitch.tv/commands",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for Capability {
+    fn from(name: &str) -> Self {
+        match name {
+            "twitch.tv/membership" => Self::Membership,
+            "twitch.tv/tags" => Self::Tags,
+            "twitch.tv/commands" => Self::Commands,
+            name => Self::Custom(name.to_string()),
+        }
+    }
+}
+
+impl From<String> for Capability {
+    fn from(name: String) -> Self {
+        match Self::from(&*name) {
+            Self::Custom(..) => Self::Custom(name),
+            cap => cap,
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    pub(crate) fn maybe_from_str(s: &str) -> Option<Self> {
-        match s {
-            "twitch.tv/membership" => Some(Self::Membership),
-            "twitch.tv/tags" => Some(Self::Tags),
-            "twitch.tv/commands" => Some(Self::Commands),
-            _ => None,
+    #[test]
+    fn names() {
+        for cap in &[
+            Capability::Membership,
+            Capability::Tags,
+            Capability::Commands,
+            Capability::Custom("twitch.tv/foo".into()),
+        ] {
+            assert_eq!(Capability::from(cap.as_str()), *cap);
+            assert_eq!(Capability::from(cap.to_string()), *cap);
         }
     }
 }
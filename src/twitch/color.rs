@@ -54,6 +54,28 @@ let twitch_color: TwitchColor = rgb.into();
 
 // TwitchColor can be converted into an RGB
 let rgb: RGB = twitch_color.into();
+
+// RGB can be converted into an HSL (and back)
+let hsl: HSL = rgb.into();
+assert_eq!(hsl.to_rgb(), rgb);
+```
+
+# Readability
+Chat renderers usually adjust colors so they are readable on their background,
+like Twitch's own client does.
+```
+# use twitchchat::twitch::color::*;
+let rgb = RGB(0x00, 0x00, 0xFF);
+
+// blue is hard to read on a dark background
+assert!(rgb.contrast_ratio(DARK_BACKGROUND) < MIN_READABLE_CONTRAST);
+
+// so lighten it until its readable
+let readable = rgb.readable_on_dark();
+assert!(readable.contrast_ratio(DARK_BACKGROUND) >= MIN_READABLE_CONTRAST);
+
+// it was already readable on a light background
+assert_eq!(rgb.readable_on_light(), rgb);
 ```
 */
 
@@ -67,6 +89,8 @@ pub enum ParseError {
     InvalidHexString,
     /// Unknown color name
     UnknownColor,
+    /// A custom (non-named) color requires `Turbo` or `Prime`
+    RequiresTurbo,
 }
 
 impl std::fmt::Display for ParseError {
@@ -74,12 +98,21 @@ impl std::fmt::Display for ParseError {
         match self {
             Self::InvalidHexString => f.write_str("invalid hex string"),
             Self::UnknownColor => f.write_str("unknown color"),
+            Self::RequiresTurbo => {
+                f.write_str("a custom color requires either Turbo or Prime, use a named color")
+            }
         }
     }
 }
 
 impl std::error::Error for ParseError {}
 
+impl From<std::convert::Infallible> for ParseError {
+    fn from(err: std::convert::Infallible) -> Self {
+        match err {}
+    }
+}
+
 impl FromStr for RGB {
     type Err = ParseError;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
@@ -134,6 +167,211 @@ impl RGB {
     pub const fn blue(self) -> u8 {
         self.2
     }
+
+    /// Convert this to an [HSL](./struct.HSL.html) triplet
+    pub fn to_hsl(self) -> HSL {
+        let Self(r, g, b) = self;
+        let (r, g, b) = (
+            f64::from(r) / 255.0,
+            f64::from(g) / 255.0,
+            f64::from(b) / 255.0,
+        );
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+
+        let delta = max - min;
+        if delta == 0.0 {
+            return HSL(0.0, 0.0, lightness);
+        }
+
+        let saturation = if lightness > 0.5 {
+            delta / (2.0 - max - min)
+        } else {
+            delta / (max + min)
+        };
+
+        let hue = if (max - r).abs() < f64::EPSILON {
+            (g - b) / delta + if g < b { 6.0 } else { 0.0 }
+        } else if (max - g).abs() < f64::EPSILON {
+            (b - r) / delta + 2.0
+        } else {
+            (r - g) / delta + 4.0
+        };
+
+        HSL(hue * 60.0, saturation, lightness)
+    }
+
+    /// The relative luminance of this color, from `0.0` (black) to `1.0` (white)
+    ///
+    /// This is the [WCAG](https://www.w3.org/TR/WCAG20/#relativeluminancedef) definition.
+    pub fn luminance(self) -> f64 {
+        fn channel(c: u8) -> f64 {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.039_28 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        let Self(r, g, b) = self;
+        0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+    }
+
+    /// The contrast ratio between this color and another color
+    ///
+    /// This ranges from `1.0` (no contrast) to `21.0` (black on white)
+    pub fn contrast_ratio(self, other: Self) -> f64 {
+        let (left, right) = (self.luminance(), other.luminance());
+        let (light, dark) = if left > right {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        (light + 0.05) / (dark + 0.05)
+    }
+
+    /// Adjust the lightness of this color until it has atleast `min_contrast` against the `background`.
+    ///
+    /// Colors are lightened on dark backgrounds and darkened on light backgrounds.
+    /// The hue and saturation are kept, so the color stays recognizable.
+    ///
+    /// If the color is already readable, it is returned unchanged. If it can't
+    /// be made readable, the closest color (e.g. white or black) is returned.
+    pub fn readable_on(self, background: Self, min_contrast: f64) -> Self {
+        if self.contrast_ratio(background) >= min_contrast {
+            return self;
+        }
+
+        let HSL(hue, saturation, lightness) = self.to_hsl();
+        let lighten = background.luminance() < 0.5;
+
+        // the most extreme lightness in the direction we're going
+        let (mut readable, mut unreadable) = if lighten {
+            (1.0, lightness)
+        } else {
+            (0.0, lightness)
+        };
+
+        // binary search for the smallest change that is still readable
+        for _ in 0..16 {
+            let mid = (readable + unreadable) / 2.0;
+            if HSL(hue, saturation, mid)
+                .to_rgb()
+                .contrast_ratio(background)
+                >= min_contrast
+            {
+                readable = mid
+            } else {
+                unreadable = mid
+            }
+        }
+
+        HSL(hue, saturation, readable).to_rgb()
+    }
+
+    /// Adjust this color so its readable on a dark background. See [`DARK_BACKGROUND`](./constant.DARK_BACKGROUND.html)
+    pub fn readable_on_dark(self) -> Self {
+        self.readable_on(DARK_BACKGROUND, MIN_READABLE_CONTRAST)
+    }
+
+    /// Adjust this color so its readable on a light background. See [`LIGHT_BACKGROUND`](./constant.LIGHT_BACKGROUND.html)
+    pub fn readable_on_light(self) -> Self {
+        self.readable_on(LIGHT_BACKGROUND, MIN_READABLE_CONTRAST)
+    }
+}
+
+/// The background color of Twitch's dark theme
+pub const DARK_BACKGROUND: RGB = RGB(0x18, 0x18, 0x1B);
+
+/// The background color of Twitch's light theme
+pub const LIGHT_BACKGROUND: RGB = RGB(0xFF, 0xFF, 0xFF);
+
+/// The minimum contrast ratio for text to be considered readable.
+///
+/// This is the [WCAG AA](https://www.w3.org/TR/WCAG20/#visual-audio-contrast-contrast) level for normal text.
+pub const MIN_READABLE_CONTRAST: f64 = 4.5;
+
+/// A HSL (hue, saturation, lightness) triplet
+///
+/// * hue is in degrees, from `0.0` to `360.0`
+/// * saturation is from `0.0` to `1.0`
+/// * lightness is from `0.0` to `1.0`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct HSL(pub f64, pub f64, pub f64);
+
+impl HSL {
+    /// The hue field
+    pub const fn hue(self) -> f64 {
+        self.0
+    }
+    /// The saturation field
+    pub const fn saturation(self) -> f64 {
+        self.1
+    }
+    /// The lightness field
+    pub const fn lightness(self) -> f64 {
+        self.2
+    }
+
+    /// Convert this to an [RGB](./struct.RGB.html) triplet
+    pub fn to_rgb(self) -> RGB {
+        fn hue_to_channel(p: f64, q: f64, mut t: f64) -> f64 {
+            if t < 0.0 {
+                t += 1.0
+            }
+            if t > 1.0 {
+                t -= 1.0
+            }
+            match t {
+                t if t < 1.0 / 6.0 => p + (q - p) * 6.0 * t,
+                t if t < 1.0 / 2.0 => q,
+                t if t < 2.0 / 3.0 => p + (q - p) * (2.0 / 3.0 - t) * 6.0,
+                _ => p,
+            }
+        }
+
+        fn to_u8(c: f64) -> u8 {
+            (c * 255.0).round().clamp(0.0, 255.0) as u8
+        }
+
+        let hue = self.hue().rem_euclid(360.0) / 360.0;
+        let saturation = self.saturation().clamp(0.0, 1.0);
+        let lightness = self.lightness().clamp(0.0, 1.0);
+
+        if saturation == 0.0 {
+            let c = to_u8(lightness);
+            return RGB(c, c, c);
+        }
+
+        let q = if lightness < 0.5 {
+            lightness * (1.0 + saturation)
+        } else {
+            lightness + saturation - lightness * saturation
+        };
+        let p = 2.0 * lightness - q;
+
+        RGB(
+            to_u8(hue_to_channel(p, q, hue + 1.0 / 3.0)),
+            to_u8(hue_to_channel(p, q, hue)),
+            to_u8(hue_to_channel(p, q, hue - 1.0 / 3.0)),
+        )
+    }
+}
+
+impl From<RGB> for HSL {
+    fn from(rgb: RGB) -> Self {
+        rgb.to_hsl()
+    }
+}
+
+impl From<HSL> for RGB {
+    fn from(hsl: HSL) -> Self {
+        hsl.to_rgb()
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -198,6 +436,9 @@ These can be [parsed] from their **name** in
 - `"snake_case"`
 - `"lower case"`
 
+Or from a hex string (e.g. `#1E90FF`). If the hex string matches one of the
+named colors, that named color is used, otherwise it'll be a `Turbo` color.
+
 [parsed]: https://doc.rust-lang.org/std/str/trait.FromStr.html
 */
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Ord, Eq, Hash)]
@@ -213,45 +454,43 @@ impl FromStr for Color {
     type Err = ParseError;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         use TwitchColor::*;
-        let find = |color| {
-            let colors = twitch_colors();
-            colors[colors.iter().position(|(d, _)| *d == color).unwrap()]
-        };
 
-        let mut s = input.replace(' ', "_");
-        if !s.contains('_') {
-            if let Some(pos) = s
-                .chars()
-                .skip(1)
-                .position(|d| d.is_ascii_uppercase())
-                .map(|d| d + 1)
-            {
-                s.insert(pos, '_');
+        let name = input
+            .trim()
+            .chars()
+            .filter(|&c| c != ' ' && c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect::<String>();
+
+        let kind = match name.as_str() {
+            "blue" => Blue,
+            "blueviolet" => BlueViolet,
+            "cadetblue" => CadetBlue,
+            "chocolate" => Chocolate,
+            "coral" => Coral,
+            "dodgerblue" => DodgerBlue,
+            "firebrick" => Firebrick,
+            "goldenrod" => GoldenRod,
+            "green" => Green,
+            "hotpink" => HotPink,
+            "orangered" => OrangeRed,
+            "red" => Red,
+            "seagreen" => SeaGreen,
+            "springgreen" => SpringGreen,
+            "yellowgreen" => YellowGreen,
+            _ => {
+                let rgb: RGB = input.parse()?;
+                return Ok(Self {
+                    kind: rgb.into(),
+                    rgb,
+                });
             }
-        }
-
-        // TODO this isn't correct, its always parsing stuff as 'Turbo'
-        let s = s.to_ascii_lowercase();
-        let (kind, rgb) = match s.as_str() {
-            "blue" => find(Blue),
-            "blue_violet" => find(BlueViolet),
-            "cadet_blue" => find(CadetBlue),
-            "chocolate" => find(Chocolate),
-            "coral" => find(Coral),
-            "dodger_blue" => find(DodgerBlue),
-            "firebrick" => find(Firebrick),
-            "golden_rod" => find(GoldenRod),
-            "green" => find(Green),
-            "hot_pink" => find(HotPink),
-            "orange_red" => find(OrangeRed),
-            "red" => find(Red),
-            "sea_green" => find(SeaGreen),
-            "spring_green" => find(SpringGreen),
-            "yellow_green" => find(YellowGreen),
-            _ => (Turbo, input.parse()?),
         };
 
-        Ok(Self { kind, rgb })
+        Ok(Self {
+            kind,
+            rgb: kind.into(),
+        })
     }
 }
 
@@ -477,6 +716,87 @@ mod tests {
         assert_eq!(color.to_string(), "#FAFAFA");
     }
 
+    #[test]
+    fn parse_color_case_insensitive() {
+        use TwitchColor::*;
+        let inputs = &[
+            ("HOTPINK", HotPink),
+            ("hotpink", HotPink),
+            ("HOT PINK", HotPink),
+            ("  Dodger Blue  ", DodgerBlue),
+            ("goldenRod", GoldenRod),
+        ];
+        for (input, kind) in inputs {
+            let color: Color = input.parse().unwrap();
+            assert_eq!(color.kind, *kind, "{}", input);
+        }
+
+        assert!(matches!(
+            "not a color".parse::<Color>(),
+            Err(ParseError::InvalidHexString)
+        ));
+    }
+
+    #[test]
+    fn parse_hex_named_color() {
+        for (kind, rgb) in &twitch_colors() {
+            let color: Color = rgb.to_string().parse().unwrap();
+            assert_eq!(color.kind, *kind);
+            assert_eq!(color.rgb, *rgb);
+        }
+
+        let color: Color = "#ff69b4".parse().unwrap();
+        assert_eq!(color.kind, TwitchColor::HotPink);
+        assert_eq!(color.to_string(), "HotPink");
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        let inputs = &[
+            (RGB(0xFF, 0x00, 0x00), HSL(0.0, 1.0, 0.5)),
+            (RGB(0x00, 0xFF, 0x00), HSL(120.0, 1.0, 0.5)),
+            (RGB(0x00, 0x00, 0xFF), HSL(240.0, 1.0, 0.5)),
+            (RGB(0xFF, 0xFF, 0xFF), HSL(0.0, 0.0, 1.0)),
+            (RGB(0x00, 0x00, 0x00), HSL(0.0, 0.0, 0.0)),
+        ];
+
+        for (rgb, hsl) in inputs {
+            assert_eq!(rgb.to_hsl(), *hsl);
+            assert_eq!(hsl.to_rgb(), *rgb);
+        }
+
+        for (_, rgb) in &twitch_colors() {
+            assert_eq!(HSL::from(*rgb).to_rgb(), *rgb);
+        }
+        assert_eq!(HSL::from(RGB(0x27, 255, 82)).to_rgb(), RGB(0x27, 255, 82));
+    }
+
+    #[test]
+    fn contrast_ratio() {
+        let black = RGB(0, 0, 0);
+        let white = RGB(0xFF, 0xFF, 0xFF);
+        assert!((black.contrast_ratio(white) - 21.0).abs() < 1e-9);
+        assert!((white.contrast_ratio(black) - 21.0).abs() < 1e-9);
+        assert!((white.contrast_ratio(white) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn readable_colors() {
+        for (_, rgb) in &twitch_colors() {
+            let dark = rgb.readable_on_dark();
+            assert!(dark.contrast_ratio(DARK_BACKGROUND) >= MIN_READABLE_CONTRAST);
+            assert!(dark.luminance() >= rgb.luminance());
+
+            let light = rgb.readable_on_light();
+            assert!(light.contrast_ratio(LIGHT_BACKGROUND) >= MIN_READABLE_CONTRAST);
+            assert!(light.luminance() <= rgb.luminance());
+        }
+
+        // already readable, so it shouldn't change
+        let white = RGB(0xFF, 0xFF, 0xFF);
+        assert_eq!(white.readable_on_dark(), white);
+    }
+
     #[test]
     fn fields() {
         let rgb = RGB(0x27, 255, 82);
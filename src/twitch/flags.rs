@@ -0,0 +1,150 @@
+use std::ops::Range;
+
+/**
+Flags are AutoMod classifications attached to parts of a message
+
+They are presented (to the irc connection) in a `start-end:category.level/category.level,..` form which marks the character position that was classified.
+
+The positions count characters (Unicode scalar values), not bytes, so use `str::chars` rather than slicing the message with them.
+
+# example:
+`"0-5:A.3/P.6"` would be the range `0-5` classified as `Aggressive` at level `3` and `Profanity` at level `6`
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Flags {
+    /// The [Range] of characters in the message that was classified
+    ///
+    /// [Range]: https://doc.rust-lang.org/std/ops/struct.Range.html
+    pub range: Range<u16>,
+    /// The classifications for this range
+    pub categories: Vec<FlagCategory>,
+}
+
+/// A single AutoMod classification, e.g. `P.6`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct FlagCategory {
+    /// The kind of the classification
+    pub kind: FlagKind,
+    /// The level of the classification, higher is more severe
+    pub level: u8,
+}
+
+/// The kind of an AutoMod classification
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum FlagKind {
+    /// `A`: aggressive content
+    Aggressive,
+    /// `I`: identity-based hate
+    Identity,
+    /// `S`: sexual content
+    Sexual,
+    /// `P`: profanity
+    Profanity,
+    /// An unknown category
+    Unknown(char),
+}
+
+impl FlagKind {
+    fn from_char(ch: char) -> Self {
+        match ch {
+            'A' => Self::Aggressive,
+            'I' => Self::Identity,
+            'S' => Self::Sexual,
+            'P' => Self::Profanity,
+            ch => Self::Unknown(ch),
+        }
+    }
+}
+
+impl Flags {
+    /// Parse flags from a string, returning an iterator over each flagged range
+    pub fn parse(input: &str) -> impl Iterator<Item = Self> + '_ {
+        input.split_terminator(',').filter_map(|s| {
+            let mut parts = s.splitn(2, ':');
+            let (range, tail) = (parts.next()?, parts.next()?);
+
+            let mut range = range.splitn(2, '-');
+            let (start, end) = (range.next()?.parse().ok()?, range.next()?.parse().ok()?);
+
+            let flags = Self {
+                range: Range { start, end },
+                categories: tail
+                    .split_terminator('/')
+                    .filter_map(parse_category)
+                    .collect(),
+            };
+            flags.into()
+        })
+    }
+}
+
+#[inline]
+fn parse_category(input: &str) -> Option<FlagCategory> {
+    let mut parts = input.splitn(2, '.');
+    let mut kind = parts.next()?.chars();
+    let kind = match (kind.next()?, kind.next()) {
+        (ch, None) => FlagKind::from_char(ch),
+        _ => return None,
+    };
+    let level = parts.next()?.parse().ok()?;
+    FlagCategory { kind, level }.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        macro_rules! flags {
+            ($range:expr, $(($kind:ident, $level:expr)),* $(,)?) => {
+                Flags {
+                    range: $range,
+                    categories: vec![$(FlagCategory { kind: FlagKind::$kind, level: $level }),*]
+                }
+            };
+        }
+
+        let inputs = &[
+            ("", vec![]),
+            (
+                "0-5:A.3/P.6",
+                vec![flags!((0..5), (Aggressive, 3), (Profanity, 6))],
+            ),
+            (
+                "0-5:P.5,14-20:I.7",
+                vec![
+                    flags!((0..5), (Profanity, 5)),
+                    flags!((14..20), (Identity, 7)),
+                ],
+            ),
+            (
+                "3-9:S.6/X.1",
+                vec![Flags {
+                    range: 3..9,
+                    categories: vec![
+                        FlagCategory {
+                            kind: FlagKind::Sexual,
+                            level: 6,
+                        },
+                        FlagCategory {
+                            kind: FlagKind::Unknown('X'),
+                            level: 1,
+                        },
+                    ],
+                }],
+            ),
+            ("0-5:", vec![flags!((0..5),)]),
+            ("garbage", vec![]),
+        ];
+
+        for (input, expect) in inputs {
+            let flags = Flags::parse(input).collect::<Vec<_>>();
+            assert_eq!(flags, *expect, "{}", input);
+        }
+    }
+}
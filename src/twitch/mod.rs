@@ -6,11 +6,22 @@ pub use capability::Capability;
 mod userconfig;
 pub use userconfig::{UserConfig, UserConfigBuilder, UserConfigError};
 
+mod name;
+pub use name::{ChannelName, Login, NameError};
+
+mod token;
+pub use token::{EnvToken, FileToken, StaticToken, TokenError, TokenProvider};
+
 mod emotes;
 pub use emotes::Emotes;
 
+mod flags;
+pub use flags::{FlagCategory, FlagKind, Flags};
+
 mod badge;
-pub use badge::{Badge, BadgeInfo, BadgeKind};
+pub use badge::{
+    Badge, BadgeInfo, BadgeKind, BadgeSet, PredictionChoice, SubscriberBadge, SubscriberTier,
+};
 
 pub mod color;
 #[doc(inline)]
@@ -20,15 +31,23 @@ pub(crate) fn parse_emotes(input: &str) -> Vec<Emotes> {
     Emotes::parse(input).collect()
 }
 
+pub(crate) fn parse_flags(input: &str) -> Vec<Flags> {
+    Flags::parse(input).collect()
+}
+
+pub(crate) fn parse_badges_iter(input: &str) -> impl Iterator<Item = Badge<'_>> + '_ {
+    input.split(',').filter_map(Badge::parse)
+}
+
 pub(crate) fn parse_badges(input: &str) -> Vec<Badge<'_>> {
-    input.split(',').filter_map(Badge::parse).collect()
+    parse_badges_iter(input).collect()
+}
+
+pub(crate) fn parse_badge_info(input: &str) -> Vec<BadgeInfo<'_>> {
+    input.split(',').filter_map(BadgeInfo::parse).collect()
 }
 
 #[allow(dead_code)]
 pub(crate) fn parse_emotes_iter(input: &str) -> impl Iterator<Item = Emotes> + '_ {
     Emotes::parse(input)
 }
-
-pub(crate) fn parse_badges_iter(input: &str) -> impl Iterator<Item = Badge<'_>> + '_ {
-    input.split(',').filter_map(Badge::parse)
-}
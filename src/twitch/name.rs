@@ -0,0 +1,402 @@
+use crate::{IntoOwned, MaybeOwned};
+use std::convert::TryFrom;
+
+/// The longest login Twitch allows
+const MAX_LENGTH: usize = 25;
+
+/// An error returned when a [ChannelName] or [Login] is invalid
+///
+/// [ChannelName]: ./struct.ChannelName.html
+/// [Login]: ./struct.Login.html
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NameError {
+    /// The name was empty
+    Empty,
+    /// The name was longer than 25 characters
+    TooLong {
+        /// How long the name was
+        length: usize,
+    },
+    /// The name had a character other than an ASCII letter, digit or underscore
+    InvalidCharacter {
+        /// The invalid character
+        character: char,
+    },
+}
+
+impl std::fmt::Display for NameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the name is empty"),
+            Self::TooLong { length } => write!(
+                f,
+                "the name is {} characters long, the limit is {}",
+                length, MAX_LENGTH
+            ),
+            Self::InvalidCharacter { character } => write!(
+                f,
+                "the name contains {:?}. only letters, digits and underscores are allowed",
+                character
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NameError {}
+
+fn validate(login: &str) -> Result<(), NameError> {
+    if login.is_empty() {
+        return Err(NameError::Empty);
+    }
+    if let Some(character) = login
+        .chars()
+        .find(|&c| !(c.is_ascii_alphanumeric() || c == '_'))
+    {
+        return Err(NameError::InvalidCharacter { character });
+    }
+    if login.len() > MAX_LENGTH {
+        return Err(NameError::TooLong {
+            length: login.len(),
+        });
+    }
+    Ok(())
+}
+
+fn has_uppercase(login: &str) -> bool {
+    login.bytes().any(|c| c.is_ascii_uppercase())
+}
+
+macro_rules! name_type {
+    ($ty:ident) => {
+        impl<'a> $ty<'a> {
+            /// Get the name as a `&str`
+            pub fn as_str(&self) -> &str {
+                &*self.0
+            }
+
+            /// Consumes the name, returning it as a `String`
+            pub fn into_string(self) -> String {
+                self.0.to_string()
+            }
+        }
+
+        impl<'a> std::fmt::Display for $ty<'a> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl<'a> std::fmt::Debug for $ty<'a> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple(stringify!($ty))
+                    .field(&self.as_str())
+                    .finish()
+            }
+        }
+
+        impl<'a> std::ops::Deref for $ty<'a> {
+            type Target = str;
+            fn deref(&self) -> &Self::Target {
+                self.as_str()
+            }
+        }
+
+        impl<'a> AsRef<str> for $ty<'a> {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl<'a> std::borrow::Borrow<str> for $ty<'a> {
+            fn borrow(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl<'a, 'b> PartialEq<$ty<'b>> for $ty<'a> {
+            fn eq(&self, other: &$ty<'b>) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        impl<'a> Eq for $ty<'a> {}
+
+        impl<'a> PartialEq<str> for $ty<'a> {
+            fn eq(&self, other: &str) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl<'a, 'b> PartialEq<&'b str> for $ty<'a> {
+            fn eq(&self, other: &&'b str) -> bool {
+                self.as_str() == *other
+            }
+        }
+
+        impl<'a> PartialEq<$ty<'a>> for str {
+            fn eq(&self, other: &$ty<'a>) -> bool {
+                self == other.as_str()
+            }
+        }
+
+        impl<'a, 'b> PartialEq<$ty<'a>> for &'b str {
+            fn eq(&self, other: &$ty<'a>) -> bool {
+                *self == other.as_str()
+            }
+        }
+
+        impl<'a> PartialOrd for $ty<'a> {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<'a> Ord for $ty<'a> {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.as_str().cmp(other.as_str())
+            }
+        }
+
+        impl<'a> std::hash::Hash for $ty<'a> {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state)
+            }
+        }
+
+        impl<'a> TryFrom<&'a str> for $ty<'a> {
+            type Error = NameError;
+            fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+                Self::new(name)
+            }
+        }
+
+        impl TryFrom<String> for $ty<'static> {
+            type Error = NameError;
+            fn try_from(name: String) -> Result<Self, Self::Error> {
+                Self::new(name)
+            }
+        }
+
+        impl std::str::FromStr for $ty<'static> {
+            type Err = NameError;
+            fn from_str(name: &str) -> Result<Self, Self::Err> {
+                $ty::new(name).map(IntoOwned::into_owned)
+            }
+        }
+
+        impl<'a> IntoOwned<'a> for $ty<'a> {
+            type Output = $ty<'static>;
+            fn into_owned(self) -> Self::Output {
+                $ty(self.0.into_owned())
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'a> ::serde::Serialize for $ty<'a> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de, 'a> ::serde::Deserialize<'de> for $ty<'a> {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                let name = <String as ::serde::Deserialize>::deserialize(deserializer)?;
+                $ty::new(name).map_err(::serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+/// A channel name, normalized to be lowercase and start with a `#`
+///
+/// This can be used anywhere a `&str` channel is expected, e.g. with the
+/// [commands] or the runner.
+///
+/// # Example
+/// ```
+/// # use twitchchat::twitch::ChannelName;
+/// let channel = ChannelName::new("Museun").unwrap();
+/// assert_eq!(channel, "#museun");
+/// assert_eq!(channel.login(), "museun");
+///
+/// // these are all the same channel
+/// assert_eq!(ChannelName::new("#museun").unwrap(), channel);
+/// assert_eq!(ChannelName::new("#MUSEUN").unwrap(), channel);
+///
+/// assert!(ChannelName::new("#not a channel").is_err());
+/// ```
+///
+/// [commands]: ../commands/index.html
+pub struct ChannelName<'a>(MaybeOwned<'a>);
+
+impl<'a> ChannelName<'a> {
+    /// Create a channel name, with or without the leading `#`
+    ///
+    /// This only allocates if the name has to be normalized.
+    pub fn new(name: impl Into<MaybeOwned<'a>>) -> Result<Self, NameError> {
+        let name = name.into();
+        let login = name.strip_prefix('#').unwrap_or(&*name);
+        validate(login)?;
+        Ok(Self::normalize(name))
+    }
+
+    /// Create a channel name from one Twitch sent us, without validating it
+    pub(crate) fn from_server(name: impl Into<MaybeOwned<'a>>) -> Self {
+        Self::normalize(name.into())
+    }
+
+    fn normalize(name: MaybeOwned<'a>) -> Self {
+        match name.strip_prefix('#') {
+            Some(login) if !has_uppercase(login) => Self(name),
+            Some(login) => Self(format!("#{}", login.to_ascii_lowercase()).into()),
+            None => Self(format!("#{}", name.to_ascii_lowercase()).into()),
+        }
+    }
+
+    /// The login of the channel's owner, the name without the leading `#`
+    pub fn login(&self) -> Login<'_> {
+        Login(MaybeOwned::Borrowed(&self.as_str()[1..]))
+    }
+}
+
+impl<'a> Clone for ChannelName<'a> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+name_type!(ChannelName);
+
+/// A user's login name, normalized to be lowercase
+///
+/// This can be used anywhere a `&str` username is expected, e.g. with the
+/// [commands].
+///
+/// # Example
+/// ```
+/// # use twitchchat::twitch::Login;
+/// let login = Login::new("Museun").unwrap();
+/// assert_eq!(login, "museun");
+/// assert_eq!(login.channel(), "#museun");
+///
+/// assert!(Login::new("#museun").is_err());
+/// ```
+///
+/// [commands]: ../commands/index.html
+pub struct Login<'a>(MaybeOwned<'a>);
+
+impl<'a> Login<'a> {
+    /// Create a login
+    ///
+    /// This only allocates if the name has to be normalized.
+    pub fn new(name: impl Into<MaybeOwned<'a>>) -> Result<Self, NameError> {
+        let name = name.into();
+        validate(&name)?;
+        Ok(Self::normalize(name))
+    }
+
+    /// Create a login from one Twitch sent us, without validating it
+    pub(crate) fn from_server(name: impl Into<MaybeOwned<'a>>) -> Self {
+        Self::normalize(name.into())
+    }
+
+    fn normalize(name: MaybeOwned<'a>) -> Self {
+        if has_uppercase(&name) {
+            return Self(name.to_ascii_lowercase().into());
+        }
+        Self(name)
+    }
+
+    /// The channel owned by this user
+    pub fn channel(&self) -> ChannelName<'static> {
+        ChannelName(format!("#{}", self.as_str()).into())
+    }
+}
+
+impl<'a> Clone for Login<'a> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+name_type!(Login);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_name() {
+        for input in &["museun", "#museun", "Museun", "#MUSEUN"] {
+            let channel = ChannelName::new(*input).unwrap();
+            assert_eq!(channel, "#museun");
+            assert_eq!(channel.login(), "museun");
+        }
+
+        // already normalized names are borrowed
+        assert!(ChannelName::new("#museun").unwrap().0.is_borrowed());
+        assert!(ChannelName::new("museun").unwrap().0.is_owned());
+
+        assert_eq!(ChannelName::new("#").unwrap_err(), NameError::Empty);
+        assert_eq!(
+            ChannelName::new("#foo bar").unwrap_err(),
+            NameError::InvalidCharacter { character: ' ' }
+        );
+        assert_eq!(
+            ChannelName::new("##foo").unwrap_err(),
+            NameError::InvalidCharacter { character: '#' }
+        );
+        assert_eq!(
+            ChannelName::new("a".repeat(26)).unwrap_err(),
+            NameError::TooLong { length: 26 }
+        );
+    }
+
+    #[test]
+    fn login() {
+        assert_eq!(Login::new("Museun_123").unwrap(), "museun_123");
+        assert!(Login::new("museun").unwrap().0.is_borrowed());
+        assert_eq!(Login::new("museun").unwrap().channel(), "#museun");
+
+        assert_eq!(Login::new("").unwrap_err(), NameError::Empty);
+        assert_eq!(
+            Login::new(":museun").unwrap_err(),
+            NameError::InvalidCharacter { character: ':' }
+        );
+    }
+
+    #[test]
+    fn from_server() {
+        assert_eq!(ChannelName::from_server("#Foo"), "#foo");
+        assert_eq!(ChannelName::from_server("foo"), "#foo");
+        assert_eq!(Login::from_server("Foo"), "foo");
+    }
+
+    #[test]
+    fn lookups() {
+        let mut set = std::collections::HashSet::new();
+        set.insert(ChannelName::new("#museun").unwrap().into_owned());
+        assert!(set.contains("#museun"));
+        assert!(set.contains(&ChannelName::new("Museun").unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let channel = ChannelName::new("museun").unwrap();
+        let json = serde_json::to_string(&channel).unwrap();
+        assert_eq!(json, r##""#museun""##);
+        let out: ChannelName<'static> = serde_json::from_str(&json).unwrap();
+        assert_eq!(out, channel);
+
+        assert!(serde_json::from_str::<Login<'static>>(r#""not a login""#).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+use super::UserConfigError;
+use crate::BoxedFuture;
+
+use std::{env::VarError, path::PathBuf};
+
+/**
+A provider for OAuth tokens
+
+The [AsyncRunner] asks this for a token each time it (re)connects via [AsyncRunner::connect_with_token_provider], so a token can be rotated without rebuilding your [UserConfig].
+
+The token returned must be a valid `oauth:` token. It'll be validated before it is sent to Twitch.
+
+# example
+```
+# use twitchchat::twitch::{TokenProvider, StaticToken};
+# let token = format!("oauth:{}", "a".repeat(30));
+let provider = StaticToken::new(token.clone());
+let fresh = futures_lite::future::block_on(provider.token()).unwrap();
+assert_eq!(fresh, token);
+```
+
+[AsyncRunner]: ../runner/struct.AsyncRunner.html
+[AsyncRunner::connect_with_token_provider]: ../runner/struct.AsyncRunner.html#method.connect_with_token_provider
+[UserConfig]: ./struct.UserConfig.html
+*/
+pub trait TokenProvider: Send + Sync {
+    /// Get a token to use for the next connection
+    fn token(&self) -> BoxedFuture<Result<String, TokenError>>;
+}
+
+/// An error returned by a [TokenProvider]
+///
+/// [TokenProvider]: ./trait.TokenProvider.html
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum TokenError {
+    /// The environment variable could not be read
+    Env {
+        /// The name of the variable
+        var: String,
+        /// Why it could not be read
+        source: VarError,
+    },
+    /// The token file could not be read
+    Io {
+        /// The path of the file
+        path: PathBuf,
+        /// Why it could not be read
+        source: std::io::Error,
+    },
+    /// The token that was provided is invalid
+    InvalidToken(UserConfigError),
+    /// A custom provider could not produce a token
+    Custom(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Env { var, source } => {
+                write!(f, "cannot read token from variable '{}': {}", var, source)
+            }
+            Self::Io { path, source } => {
+                write!(f, "cannot read token from '{}': {}", path.display(), source)
+            }
+            Self::InvalidToken(err) => write!(f, "the provided token is invalid: {}", err),
+            Self::Custom(err) => write!(f, "cannot get a token: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Env { source, .. } => Some(source),
+            Self::Io { source, .. } => Some(source),
+            Self::InvalidToken(err) => Some(err),
+            Self::Custom(err) => Some(&**err),
+        }
+    }
+}
+
+impl From<UserConfigError> for TokenError {
+    fn from(err: UserConfigError) -> Self {
+        Self::InvalidToken(err)
+    }
+}
+
+impl<T> TokenProvider for &T
+where
+    T: TokenProvider + ?Sized,
+{
+    fn token(&self) -> BoxedFuture<Result<String, TokenError>> {
+        (**self).token()
+    }
+}
+
+impl<T> TokenProvider for Box<T>
+where
+    T: TokenProvider + ?Sized,
+{
+    fn token(&self) -> BoxedFuture<Result<String, TokenError>> {
+        (**self).token()
+    }
+}
+
+impl<T> TokenProvider for std::sync::Arc<T>
+where
+    T: TokenProvider + ?Sized,
+{
+    fn token(&self) -> BoxedFuture<Result<String, TokenError>> {
+        (**self).token()
+    }
+}
+
+/// A token provider that always returns the same token
+#[derive(Clone, PartialEq)]
+pub struct StaticToken {
+    token: String,
+}
+
+impl StaticToken {
+    /// Create a provider for this token
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+impl std::fmt::Debug for StaticToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticToken").finish()
+    }
+}
+
+impl TokenProvider for StaticToken {
+    fn token(&self) -> BoxedFuture<Result<String, TokenError>> {
+        let token = check_token(&self.token)
+            .map(ToString::to_string)
+            .map_err(Into::into);
+        Box::pin(async move { token })
+    }
+}
+
+/// A token provider that reads the token from an environment variable
+///
+/// The variable is read each time a token is requested.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvToken {
+    var: String,
+}
+
+impl EnvToken {
+    /// Create a provider that reads the environment variable `var`
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl TokenProvider for EnvToken {
+    fn token(&self) -> BoxedFuture<Result<String, TokenError>> {
+        let token = env_token(&self.var, std::env::var(&self.var));
+        Box::pin(async move { token })
+    }
+}
+
+fn env_token(var: &str, value: Result<String, VarError>) -> Result<String, TokenError> {
+    let token = value.map_err(|source| TokenError::Env {
+        var: var.to_string(),
+        source,
+    })?;
+    Ok(check_token(token.trim())?.to_string())
+}
+
+/// A token provider that reads the token from a file
+///
+/// The file is read each time a token is requested. Surrounding whitespace is trimmed.
+///
+/// The file is read on its own thread, so it won't block the executor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileToken {
+    path: PathBuf,
+}
+
+impl FileToken {
+    /// Create a provider that reads the file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl TokenProvider for FileToken {
+    fn token(&self) -> BoxedFuture<Result<String, TokenError>> {
+        let path = self.path.clone();
+        Box::pin(async move {
+            let (tx, rx) = crate::channel::bounded(1);
+            let file = path.clone();
+            let spawned = std::thread::Builder::new()
+                .name("twitchchat-token".into())
+                .spawn(move || {
+                    let _ = tx.try_send(std::fs::read_to_string(&file));
+                });
+
+            let token = match spawned {
+                Ok(..) => rx.recv().await.unwrap_or_else(|| {
+                    Err(std::io::Error::other(
+                        "the reading thread quit unexpectedly",
+                    ))
+                }),
+                Err(err) => Err(err),
+            };
+            let token = token.map_err(|source| TokenError::Io { path, source })?;
+            Ok(check_token(token.trim())?.to_string())
+        })
+    }
+}
+
+pub(super) fn check_token(token: &str) -> Result<&str, UserConfigError> {
+    if token == crate::JUSTINFAN1234 {
+        return Ok(token);
+    }
+    if !token.starts_with("oauth:") {
+        return Err(UserConfigError::MissingOAuthPrefix);
+    }
+    if token.len() != 36 {
+        return Err(UserConfigError::InvalidToken);
+    }
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future::block_on;
+
+    fn valid() -> String {
+        format!("oauth:{}", "a".repeat(30))
+    }
+
+    #[test]
+    fn static_token() {
+        let token = block_on(StaticToken::new(valid()).token()).unwrap();
+        assert_eq!(token, valid());
+
+        let err = block_on(StaticToken::new("a".repeat(36)).token()).unwrap_err();
+        assert!(matches!(
+            err,
+            TokenError::InvalidToken(UserConfigError::MissingOAuthPrefix)
+        ));
+
+        let err = block_on(StaticToken::new("oauth:foo").token()).unwrap_err();
+        assert!(matches!(
+            err,
+            TokenError::InvalidToken(UserConfigError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn env_token() {
+        // this variable is never set, so nothing here changes the environment
+        let var = "TWITCHCHAT_TEST_ENV_TOKEN_UNSET";
+        match block_on(EnvToken::new(var).token()).unwrap_err() {
+            TokenError::Env { var: name, source } => {
+                assert_eq!(name, var);
+                assert_eq!(source, VarError::NotPresent);
+            }
+            err => panic!("expected an env error, got: {:?}", err),
+        }
+
+        let token = super::env_token(var, Ok(format!("{}\n", valid())));
+        assert_eq!(token.unwrap(), valid());
+
+        let err = super::env_token(var, Ok("a".repeat(30))).unwrap_err();
+        assert!(matches!(
+            err,
+            TokenError::InvalidToken(UserConfigError::MissingOAuthPrefix)
+        ));
+    }
+
+    #[test]
+    fn file_token() {
+        let path =
+            std::env::temp_dir().join(format!("twitchchat_test_file_token_{}", std::process::id()));
+        let provider = FileToken::new(&path);
+
+        let _ = std::fs::remove_file(&path);
+        match block_on(provider.token()).unwrap_err() {
+            TokenError::Io {
+                path: err_path,
+                source,
+            } => {
+                assert_eq!(err_path, path);
+                assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+            }
+            err => panic!("expected an io error, got: {:?}", err),
+        }
+
+        std::fs::write(&path, format!("  {}\r\n", valid())).unwrap();
+        assert_eq!(block_on(provider.token()).unwrap(), valid());
+
+        let rotated = format!("oauth:{}", "b".repeat(30));
+        std::fs::write(&path, &rotated).unwrap();
+        assert_eq!(block_on(provider.token()).unwrap(), rotated);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn boxed_provider() {
+        fn get(provider: impl TokenProvider) -> Result<String, TokenError> {
+            block_on(provider.token())
+        }
+
+        let provider: Box<dyn TokenProvider> = Box::new(StaticToken::new(valid()));
+        assert_eq!(get(&provider).unwrap(), valid());
+        assert_eq!(get(std::sync::Arc::new(provider)).unwrap(), valid());
+    }
+}
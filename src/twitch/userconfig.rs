@@ -8,6 +8,10 @@ This is used to *register* the connection with the server
 
 It contains your user name, your OAuth token and the capabilities you want to request.
 
+Twitch has to acknowledge all of the `capabilities`, otherwise connecting fails. The
+`optional_capabilities` are requested separately and may be denied, see [Capabilities]
+for which ones were granted.
+
 # example using a builder
 ```
 # use twitchchat::twitch::{Capability, UserConfig};
@@ -22,9 +26,12 @@ let config = UserConfig::builder()
     .name(name)
     .token(token)
     .capabilities(&[Capability::Tags])
+    .optional_capabilities(&[Capability::Custom("twitch.tv/new-thing".into())])
     .build()
     .unwrap();
 ```
+
+[Capabilities]: ../runner/struct.Capabilities.html
 */
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -35,6 +42,9 @@ pub struct UserConfig {
     pub token: String,
     /// Capabilities to be requested from the server
     pub capabilities: Vec<Capability>,
+    /// Capabilities to be requested from the server, which it may deny
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub optional_capabilities: Vec<Capability>,
 }
 
 impl UserConfig {
@@ -61,6 +71,8 @@ pub enum UserConfigError {
     InvalidName,
     /// An invalid token was provided.
     InvalidToken,
+    /// The token provided did not start with `oauth:`
+    MissingOAuthPrefix,
     /// Anonymous login was requested with a user-provided name or token
     PartialAnonymous,
 }
@@ -72,6 +84,7 @@ impl std::fmt::Display for UserConfigError {
             Self::InvalidToken => {
                 f.write_str("invalid token. token must start with oauth: and be 36 characters")
             }
+            Self::MissingOAuthPrefix => f.write_str("invalid token. token must start with oauth:"),
             Self::PartialAnonymous => f.write_str(
                 "user provided name or token provided when an anonymous login was requested",
             ),
@@ -88,6 +101,8 @@ impl std::error::Error for UserConfigError {}
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserConfigBuilder {
     capabilities: BTreeSet<Capability>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    optional_capabilities: BTreeSet<Capability>,
     name: Option<String>,
     token: Option<String>,
 }
@@ -121,7 +136,17 @@ impl UserConfigBuilder {
     /// Capabilities to enable
     ///
     pub fn capabilities(mut self, caps: &[Capability]) -> Self {
-        self.capabilities.extend(caps);
+        self.capabilities.extend(caps.iter().cloned());
+        self
+    }
+
+    /// Capabilities to enable, if the server supports them
+    ///
+    /// Unlike [capabilities][caps], the server denying one of these doesn't fail the connection.
+    ///
+    /// [caps]: ./struct.UserConfigBuilder.html#method.capabilities
+    pub fn optional_capabilities(mut self, caps: &[Capability]) -> Self {
+        self.optional_capabilities.extend(caps.iter().cloned());
         self
     }
 
@@ -151,10 +176,8 @@ impl UserConfigBuilder {
             .filter(|s| validate_name(s))
             .ok_or_else(|| UserConfigError::InvalidName)?;
 
-        let token = self
-            .token
-            .filter(|s| validate_token(s))
-            .ok_or_else(|| UserConfigError::InvalidToken)?;
+        let token = self.token.ok_or(UserConfigError::InvalidToken)?;
+        super::token::check_token(&token)?;
 
         match (name.as_str(), token.as_str()) {
             (crate::JUSTINFAN1234, crate::JUSTINFAN1234) => {
@@ -166,10 +189,17 @@ impl UserConfigBuilder {
             _ => {}
         }
 
+        let optional_capabilities = self
+            .optional_capabilities
+            .difference(&self.capabilities)
+            .cloned()
+            .collect();
+
         Ok(UserConfig {
             name,
             token,
             capabilities: self.capabilities.into_iter().collect(),
+            optional_capabilities,
         })
     }
 }
@@ -179,14 +209,6 @@ const fn validate_name(s: &str) -> bool {
     !s.is_empty()
 }
 
-#[inline]
-fn validate_token(s: &str) -> bool {
-    if s == crate::JUSTINFAN1234 {
-        return true;
-    }
-    !s.is_empty() && s.len() == 36 && &s[..6] == "oauth:"
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,6 +227,7 @@ mod tests {
                 name: "foo".to_string(),
                 token: format!("oauth:{}", "a".repeat(30)),
                 capabilities: vec![],
+                optional_capabilities: vec![],
             }
         )
     }
@@ -225,10 +248,26 @@ mod tests {
                 name: "foo".to_string(),
                 token: format!("oauth:{}", "a".repeat(30)),
                 capabilities: vec![Capability::Membership, Capability::Tags,],
+                optional_capabilities: vec![],
             }
         )
     }
 
+    #[test]
+    fn valid_user_config_optional_caps() {
+        let custom = Capability::Custom("twitch.tv/foo".into());
+        let config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[Capability::Tags])
+            .optional_capabilities(&[Capability::Tags, custom.clone()])
+            .build()
+            .unwrap();
+
+        // a required capability isn't also optional
+        assert_eq!(config.capabilities, vec![Capability::Tags]);
+        assert_eq!(config.optional_capabilities, vec![custom]);
+    }
+
     #[test]
     fn valid_user_config_anonymous() {
         let config = UserConfig::builder().anonymous().build().unwrap();
@@ -239,6 +278,7 @@ mod tests {
                 name: crate::JUSTINFAN1234.to_string(),
                 token: crate::JUSTINFAN1234.to_string(),
                 capabilities: vec![],
+                optional_capabilities: vec![],
             }
         );
 
@@ -304,6 +344,6 @@ mod tests {
             .token("a".repeat(36))
             .build()
             .unwrap_err();
-        matches!(err, UserConfigError::InvalidToken);
+        assert!(matches!(err, UserConfigError::MissingOAuthPrefix));
     }
 }
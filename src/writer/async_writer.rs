@@ -1,15 +1,21 @@
 use crate::channel::Sender;
 use crate::encoder::{AsyncEncoder, Encodable};
+use crate::writer::{receipt, Outgoing, Priority, Receipt};
 
 use futures_lite::AsyncWrite;
 use io::Write;
 use std::io::{self};
+use std::time::Instant;
+
+type LanesSender = Sender<(Priority, Outgoing)>;
 
 /// An asynchronous writer.
 #[derive(Clone)]
 pub struct AsyncWriter<W> {
     inner: AsyncEncoder<W>,
     activity_tx: Sender<()>,
+    // every message is queued here when provided
+    lanes_tx: Option<LanesSender>,
 }
 
 impl<W> std::fmt::Debug for AsyncWriter<W> {
@@ -23,10 +29,22 @@ where
     W: Write + Send + Sync,
 {
     /// If the wrapped writer is synchronous, you can use this method to encode the message to it.
+    ///
+    /// If this writer came from a runner with a bounded `writer_capacity`, this
+    /// returns an error of kind `WouldBlock` while its queue is full.
     pub fn encode_sync<M>(&mut self, msg: M) -> io::Result<()>
     where
         M: Encodable + Send + Sync,
     {
+        if let Some((lanes_tx, priority)) = self.lane_for(&msg) {
+            let outgoing = Self::outgoing(&msg)?;
+            return lanes_tx
+                .try_send((priority, outgoing))
+                .map_err(|err| match err {
+                    crate::channel::TrySendError::Full(..) => full(),
+                    crate::channel::TrySendError::Closed(..) => closed(),
+                });
+        }
         self.inner.encode_sync(msg)
     }
 }
@@ -52,24 +70,83 @@ where
         Self {
             inner: AsyncEncoder::new(inner),
             activity_tx,
+            lanes_tx: None,
+        }
+    }
+
+    pub(crate) fn with_lanes(self, lanes_tx: LanesSender) -> Self {
+        Self {
+            lanes_tx: Some(lanes_tx),
+            ..self
         }
     }
 
     /// Encode this `Encodable` message to the writer.
+    ///
+    /// If this writer came from a runner, the message is queued according to its [priority].
+    /// This waits for room in the queue when the runner has a bounded `writer_capacity`.
+    ///
+    /// [priority]: ../trait.Encodable.html#method.priority
     pub async fn encode<M>(&mut self, msg: M) -> io::Result<()>
     where
         M: Encodable + Send + Sync,
     {
-        self.inner.encode(msg).await?;
+        match self.lane_for(&msg) {
+            Some((lanes_tx, priority)) => {
+                let outgoing = Self::outgoing(&msg)?;
+                lanes_tx
+                    .send((priority, outgoing))
+                    .await
+                    .map_err(|_| closed())?
+            }
+            None => self.inner.encode(msg).await?,
+        }
+
         if self.activity_tx.send(()).await.is_err() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::UnexpectedEof,
-                "Runner has closed its receiver",
-            ));
+            return Err(closed());
         }
         Ok(())
     }
 
+    /// Encode this `Encodable` message to the writer, returning a [Receipt] for it.
+    ///
+    /// If this writer came from a runner, the receipt resolves when the
+    /// message is written to the connection, after any rate limiting. Otherwise
+    /// it resolves once the message is written to the inner writer.
+    ///
+    /// A runner resolves the lines that aren't a `PRIVMSG` a short while after
+    /// they're written, unless Twitch answers with a `421`. Then it fails with
+    /// [SendFailure::UnknownCommand].
+    ///
+    /// [Receipt]: ./struct.Receipt.html
+    /// [SendFailure::UnknownCommand]: ../runner/enum.SendFailure.html#variant.UnknownCommand
+    pub async fn encode_with_receipt<M>(&mut self, msg: M) -> io::Result<Receipt>
+    where
+        M: Encodable + Send + Sync,
+    {
+        let (tx, receipt) = receipt();
+
+        match &self.lanes_tx {
+            Some(lanes_tx) => {
+                let mut outgoing = Self::outgoing(&msg)?;
+                outgoing.receipt.replace(tx);
+                lanes_tx
+                    .send((msg.priority(), outgoing))
+                    .await
+                    .map_err(|_| closed())?
+            }
+            None => {
+                self.inner.encode(msg).await?;
+                tx.delivered();
+            }
+        }
+
+        if self.activity_tx.send(()).await.is_err() {
+            return Err(closed());
+        }
+        Ok(receipt)
+    }
+
     /// Encode a slice of `Encodable` messages to the writer.
     pub async fn encode_many<'a, I, M>(&mut self, msgs: I) -> io::Result<()>
     where
@@ -84,9 +161,41 @@ where
     }
 }
 
+impl<W> AsyncWriter<W> {
+    fn lane_for<M>(&self, msg: &M) -> Option<(&LanesSender, Priority)>
+    where
+        M: Encodable,
+    {
+        self.lanes_tx.as_ref().map(|tx| (tx, msg.priority()))
+    }
+
+    fn outgoing<M>(msg: &M) -> io::Result<Outgoing>
+    where
+        M: Encodable,
+    {
+        let mut data = vec![];
+        msg.encode(&mut data)?;
+        let mut outgoing = Outgoing::new(data.into_boxed_slice());
+        outgoing.expires_at = msg.time_to_live().map(|ttl| Instant::now() + ttl);
+        Ok(outgoing)
+    }
+}
+
+pub(crate) fn full() -> io::Error {
+    io::Error::new(io::ErrorKind::WouldBlock, "Runner's writer queue is full")
+}
+
+fn closed() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "Runner has closed its receiver",
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::SendFailure;
     #[test]
     fn is_that_right() {
         let (tx, rx) = crate::channel::bounded(10);
@@ -110,4 +219,119 @@ mod tests {
             eprintln!("{}", std::str::from_utf8(&*t).unwrap().escape_debug());
         }
     }
+
+    #[test]
+    fn priority_lanes() {
+        let (tx, rx) = crate::channel::unbounded();
+        let (lanes_tx, lanes_rx) = crate::channel::unbounded();
+        let (activity_tx, _activity_rx) = crate::channel::unbounded();
+
+        let w = crate::writer::MpscWriter::new(tx);
+        let mut w = AsyncWriter::new(w, activity_tx).with_lanes(lanes_tx);
+
+        use crate::commands::*;
+        let fut = async move {
+            w.encode(privmsg("museun", "hello")).await.unwrap();
+            w.encode(ban("museun", "someone", None)).await.unwrap();
+            w.encode(Priority::Bulk.with(privmsg("museun", "later")))
+                .await
+                .unwrap();
+            w.encode_sync(timeout("museun", "someone", None, None))
+                .unwrap();
+        };
+        futures_lite::future::block_on(fut);
+
+        assert!(rx.try_recv().is_none());
+
+        let expected: &[(_, &[u8])] = &[
+            (Priority::Normal, b"PRIVMSG #museun :hello\r\n"),
+            (Priority::Moderation, b"PRIVMSG #museun :/ban someone\r\n"),
+            (Priority::Bulk, b"PRIVMSG #museun :later\r\n"),
+            (
+                Priority::Moderation,
+                b"PRIVMSG #museun :/timeout someone\r\n",
+            ),
+        ];
+        for (priority, data) in expected {
+            let (p, d) = lanes_rx.try_recv().unwrap();
+            assert_eq!(p, *priority);
+            assert_eq!(&*d.data, *data);
+        }
+    }
+
+    #[test]
+    fn full_lanes() {
+        let (tx, _rx) = crate::channel::unbounded();
+        let (lanes_tx, lanes_rx) = crate::channel::bounded(1);
+        let (activity_tx, _activity_rx) = crate::channel::unbounded();
+
+        let w = crate::writer::MpscWriter::new(tx);
+        let mut w = AsyncWriter::new(w, activity_tx).with_lanes(lanes_tx);
+
+        use crate::commands::*;
+        use futures_lite::future::{block_on, poll_once};
+
+        block_on(w.encode(privmsg("museun", "hello"))).unwrap();
+
+        // the sync paths can't wait for room
+        let err = w.encode_sync(privmsg("museun", "world")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+        // but the async ones do
+        let mut fut = Box::pin(w.encode(privmsg("museun", "world")));
+        assert!(block_on(poll_once(&mut fut)).is_none());
+        drop(lanes_rx.try_recv().unwrap());
+        block_on(fut).unwrap();
+
+        let (_, outgoing) = lanes_rx.try_recv().unwrap();
+        assert_eq!(&*outgoing.data, b"PRIVMSG #museun :world\r\n");
+    }
+
+    #[test]
+    fn receipts() {
+        let (tx, rx) = crate::channel::unbounded();
+        let (lanes_tx, lanes_rx) = crate::channel::unbounded();
+        let (activity_tx, _activity_rx) = crate::channel::unbounded();
+
+        let w = crate::writer::MpscWriter::new(tx);
+        let mut w = AsyncWriter::new(w, activity_tx.clone()).with_lanes(lanes_tx);
+
+        use crate::commands::*;
+        let fut = async move {
+            let receipt = w
+                .encode_with_receipt(privmsg("museun", "hello"))
+                .await
+                .unwrap();
+
+            // a runner would send the message and resolve the receipt
+            let (priority, outgoing) = lanes_rx.try_recv().unwrap();
+            assert_eq!(priority, Priority::Normal);
+            assert_eq!(&*outgoing.data, b"PRIVMSG #museun :hello\r\n");
+            outgoing.delivered();
+            assert!(receipt.await.is_ok());
+
+            // the runner went away before sending it
+            let receipt = w
+                .encode_with_receipt(privmsg("museun", "hello"))
+                .await
+                .unwrap();
+            drop(lanes_rx.try_recv().unwrap());
+            assert_eq!(receipt.await.unwrap_err(), SendFailure::Closed);
+        };
+        futures_lite::future::block_on(fut);
+        assert!(rx.try_recv().is_none());
+
+        // without a runner, its written directly
+        let (tx, rx) = crate::channel::unbounded();
+        let mut w = AsyncWriter::new(crate::writer::MpscWriter::new(tx), activity_tx);
+        let fut = async move {
+            let receipt = w
+                .encode_with_receipt(privmsg("museun", "hello"))
+                .await
+                .unwrap();
+            assert!(receipt.await.is_ok());
+        };
+        futures_lite::future::block_on(fut);
+        assert_eq!(&*rx.try_recv().unwrap(), b"PRIVMSG #museun :hello\r\n");
+    }
 }
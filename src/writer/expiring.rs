@@ -0,0 +1,83 @@
+use crate::{writer::Priority, Encodable};
+use std::{
+    io::{Result as IoResult, Write},
+    time::Duration,
+};
+
+/// A message that is dropped if it hasn't been sent within a time-to-live
+///
+/// This only applies to messages queued by a runner.
+///
+/// # example
+/// ```
+/// use twitchchat::{commands, writer::Expiring, Encodable as _};
+/// use std::time::Duration;
+///
+/// let msg = Expiring::new(Duration::from_secs(10), commands::privmsg("museun", "pong"));
+/// assert_eq!(msg.time_to_live(), Some(Duration::from_secs(10)));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Expiring<M> {
+    ttl: Duration,
+    msg: M,
+}
+
+impl<M> Expiring<M>
+where
+    M: Encodable,
+{
+    /// Drop `msg` if it hasn't been sent within `ttl`
+    pub fn new(ttl: Duration, msg: M) -> Self {
+        Self { ttl, msg }
+    }
+
+    /// Get the inner message out
+    pub fn into_inner(self) -> M {
+        self.msg
+    }
+}
+
+impl<M> Encodable for Expiring<M>
+where
+    M: Encodable,
+{
+    fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+    where
+        W: Write + ?Sized,
+    {
+        self.msg.encode(buf)
+    }
+
+    fn priority(&self) -> Priority {
+        self.msg.priority()
+    }
+
+    fn time_to_live(&self) -> Option<Duration> {
+        Some(self.ttl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands;
+
+    #[test]
+    fn expiring() {
+        let ttl = Duration::from_secs(3);
+        let msg = Expiring::new(
+            ttl,
+            Priority::Bulk.with(commands::privmsg("museun", "hello")),
+        );
+        assert_eq!(msg.time_to_live(), Some(ttl));
+        assert_eq!(msg.priority(), Priority::Bulk);
+
+        let msg = Priority::Bulk.with(Expiring::new(ttl, commands::privmsg("museun", "hello")));
+        assert_eq!(msg.time_to_live(), Some(ttl));
+        assert_eq!(msg.priority(), Priority::Bulk);
+
+        let mut out = vec![];
+        msg.encode(&mut out).unwrap();
+        assert_eq!(out, b"PRIVMSG #museun :hello\r\n");
+    }
+}
@@ -5,3 +5,13 @@ pub use async_writer::AsyncWriter;
 
 mod mpsc_writer;
 pub use mpsc_writer::MpscWriter;
+
+mod priority;
+pub use priority::{Prioritized, Priority};
+
+mod expiring;
+pub use expiring::Expiring;
+
+mod receipt;
+pub use receipt::Receipt;
+pub(crate) use receipt::{receipt, Outgoing};
@@ -1,4 +1,5 @@
 use crate::encoder::Encodable;
+use crate::writer::{Outgoing, Priority};
 
 use futures_lite::AsyncWrite;
 use std::{
@@ -12,7 +13,14 @@ use std::{
 /// This can be used both a `std::io::Write` instance and a `AsyncWrite` instance.
 pub struct MpscWriter {
     buf: Vec<u8>,
-    channel: crate::Sender<Box<[u8]>>,
+    channel: Channel,
+}
+
+#[derive(Clone)]
+enum Channel {
+    Bytes(crate::Sender<Box<[u8]>>),
+    // a runner's queue, lines are sent with a `Priority::Normal`
+    Lanes(crate::Sender<(Priority, Outgoing)>),
 }
 
 impl std::fmt::Debug for MpscWriter {
@@ -35,7 +43,14 @@ impl MpscWriter {
     pub const fn new(channel: crate::Sender<Box<[u8]>>) -> Self {
         Self {
             buf: Vec::new(),
-            channel,
+            channel: Channel::Bytes(channel),
+        }
+    }
+
+    pub(crate) const fn lanes(channel: crate::Sender<(Priority, Outgoing)>) -> Self {
+        Self {
+            buf: Vec::new(),
+            channel: Channel::Lanes(channel),
         }
     }
 
@@ -60,7 +75,7 @@ impl MpscWriter {
         Some(tail.into_boxed_slice())
     }
 
-    fn inner_flush(&mut self) -> std::io::Result<()> {
+    fn inner_flush(&mut self) -> io::Result<()> {
         use crate::channel::TrySendError;
 
         let tail = match self.split_buf() {
@@ -71,14 +86,26 @@ impl MpscWriter {
             }
         };
 
-        match self.channel.try_send(tail) {
-            Ok(..) => Ok(()),
-            Err(TrySendError::Closed(..)) => Err(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                "writer was closed",
-            )),
-            Err(TrySendError::Full(..)) => unreachable!(),
+        let full = match &self.channel {
+            Channel::Bytes(channel) => match channel.try_send(tail) {
+                Ok(..) => return Ok(()),
+                Err(err) => matches!(err, TrySendError::Full(..)),
+            },
+            Channel::Lanes(channel) => {
+                match channel.try_send((Priority::Normal, Outgoing::new(tail))) {
+                    Ok(..) => return Ok(()),
+                    Err(err) => matches!(err, TrySendError::Full(..)),
+                }
+            }
+        };
+
+        if full {
+            return Err(super::async_writer::full());
         }
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "writer was closed",
+        ))
     }
 }
 
@@ -141,4 +168,34 @@ mod tests {
         assert!(m.flush().is_ok());
         assert_eq!(&*rx.try_recv().unwrap(), b"\r\n");
     }
+
+    #[test]
+    fn mpsc_full() {
+        let (tx, rx) = crate::channel::bounded(1);
+        let mut m = MpscWriter::new(tx);
+
+        m.write_all(b"PING :1\r\n").unwrap();
+        assert!(m.flush().is_ok());
+
+        m.write_all(b"PING :2\r\n").unwrap();
+        assert_eq!(m.flush().unwrap_err().kind(), io::ErrorKind::WouldBlock);
+
+        assert_eq!(&*rx.try_recv().unwrap(), b"PING :1\r\n");
+    }
+
+    #[test]
+    fn mpsc_lanes() {
+        let (tx, rx) = crate::channel::bounded(1);
+        let mut m = MpscWriter::lanes(tx);
+
+        m.write_all(b"PING :1\r\n").unwrap();
+        assert!(m.flush().is_ok());
+
+        m.write_all(b"PING :2\r\n").unwrap();
+        assert_eq!(m.flush().unwrap_err().kind(), io::ErrorKind::WouldBlock);
+
+        let (priority, outgoing) = rx.try_recv().unwrap();
+        assert_eq!(priority, Priority::Normal);
+        assert_eq!(&*outgoing.data, b"PING :1\r\n");
+    }
 }
@@ -0,0 +1,114 @@
+use crate::Encodable;
+use std::io::{Result as IoResult, Write};
+
+/// The priority of an outgoing message
+///
+/// The runner sends queued messages in priority order, still within the
+/// per-channel and global rate limits.
+///
+/// # example
+/// ```
+/// use twitchchat::{commands, writer::Priority, Encodable as _};
+///
+/// // moderation commands have a `Moderation` priority by default
+/// assert_eq!(commands::ban("museun", "someone", None).priority(), Priority::Moderation);
+/// assert_eq!(commands::privmsg("museun", "hello").priority(), Priority::Normal);
+///
+/// // but you can change the priority of any message
+/// let msg = Priority::Bulk.with(commands::privmsg("museun", "a periodic announcement"));
+/// assert_eq!(msg.priority(), Priority::Bulk);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    /// Sent before anything else. e.g. bans and timeouts
+    Moderation,
+    /// The default priority
+    #[default]
+    Normal,
+    /// Sent after everything else. e.g. announcements
+    Bulk,
+}
+
+impl Priority {
+    /// All of the priorities, from highest to lowest
+    pub const ALL: [Self; 3] = [Self::Moderation, Self::Normal, Self::Bulk];
+
+    /// Send `msg` with this priority
+    pub fn with<M>(self, msg: M) -> Prioritized<M>
+    where
+        M: Encodable,
+    {
+        Prioritized {
+            priority: self,
+            msg,
+        }
+    }
+}
+
+/// A message with an explicit [Priority]
+///
+/// This is created with [Priority::with].
+///
+/// [Priority]: ./enum.Priority.html
+/// [Priority::with]: ./enum.Priority.html#method.with
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Prioritized<M> {
+    priority: Priority,
+    msg: M,
+}
+
+impl<M> Prioritized<M> {
+    /// Get the inner message out
+    pub fn into_inner(self) -> M {
+        self.msg
+    }
+}
+
+impl<M> Encodable for Prioritized<M>
+where
+    M: Encodable,
+{
+    fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+    where
+        W: Write + ?Sized,
+    {
+        self.msg.encode(buf)
+    }
+
+    fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    fn time_to_live(&self) -> Option<std::time::Duration> {
+        self.msg.time_to_live()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands;
+
+    #[test]
+    fn ordering() {
+        assert!(Priority::Moderation < Priority::Normal);
+        assert!(Priority::Normal < Priority::Bulk);
+        assert_eq!(Priority::default(), Priority::Normal);
+    }
+
+    #[test]
+    fn prioritized() {
+        let msg = Priority::Bulk.with(commands::privmsg("museun", "hello"));
+        assert_eq!(msg.priority(), Priority::Bulk);
+        // references keep the priority
+        let by_ref = &msg;
+        assert_eq!(Encodable::priority(&by_ref), Priority::Bulk);
+
+        let mut out = vec![];
+        msg.encode(&mut out).unwrap();
+        assert_eq!(out, b"PRIVMSG #museun :hello\r\n");
+
+        let msg = Priority::Normal.with(commands::timeout("museun", "someone", None, None));
+        assert_eq!(msg.priority(), Priority::Normal);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    channel::{Receiver, Sender},
+    runner::SendFailure,
+};
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Instant, SystemTime},
+};
+
+pin_project_lite::pin_project! {
+    /// A receipt for a message sent with [AsyncWriter::encode_with_receipt]
+    ///
+    /// This resolves to the time the message was written to the connection, or why it wasn't.
+    ///
+    /// [AsyncWriter::encode_with_receipt]: ./struct.AsyncWriter.html#method.encode_with_receipt
+    #[derive(Debug)]
+    pub struct Receipt {
+        #[pin]
+        rx: Receiver<Result<SystemTime, SendFailure>>,
+    }
+}
+
+impl Future for Receipt {
+    type Output = Result<SystemTime, SendFailure>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        use futures_lite::Stream as _;
+        match futures_lite::ready!(self.project().rx.poll_next(cx)) {
+            Some(result) => Poll::Ready(result),
+            // the message was dropped along with the runner
+            None => Poll::Ready(Err(SendFailure::Closed)),
+        }
+    }
+}
+
+/// The sending half of a [Receipt]
+#[derive(Debug)]
+pub(crate) struct ReceiptSender {
+    tx: Sender<Result<SystemTime, SendFailure>>,
+}
+
+impl ReceiptSender {
+    pub(crate) fn delivered(self) {
+        let _ = self.tx.try_send(Ok(SystemTime::now()));
+    }
+
+    pub(crate) fn failed(self, failure: SendFailure) {
+        let _ = self.tx.try_send(Err(failure));
+    }
+}
+
+pub(crate) fn receipt() -> (ReceiptSender, Receipt) {
+    let (tx, rx) = crate::channel::bounded(1);
+    (ReceiptSender { tx }, Receipt { rx })
+}
+
+/// An encoded message, waiting to be sent
+#[derive(Debug)]
+pub(crate) struct Outgoing {
+    pub(crate) data: Box<[u8]>,
+    pub(crate) receipt: Option<ReceiptSender>,
+    pub(crate) expires_at: Option<Instant>,
+    pub(crate) queued_at: Instant,
+}
+
+impl Outgoing {
+    pub(crate) fn new(data: Box<[u8]>) -> Self {
+        Self {
+            data,
+            receipt: None,
+            expires_at: None,
+            queued_at: Instant::now(),
+        }
+    }
+
+    pub(crate) fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.map(|at| at <= now).unwrap_or(false)
+    }
+
+    pub(crate) fn delivered(self) {
+        if let Some(receipt) = self.receipt {
+            receipt.delivered()
+        }
+    }
+
+    pub(crate) fn failed(self, failure: SendFailure) {
+        if let Some(receipt) = self.receipt {
+            receipt.failed(failure)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future::block_on;
+
+    #[test]
+    fn delivered() {
+        let (tx, rx) = receipt();
+        tx.delivered();
+        assert!(block_on(rx).is_ok());
+    }
+
+    #[test]
+    fn failed() {
+        let (tx, rx) = receipt();
+        tx.failed(SendFailure::Dropped);
+        assert_eq!(block_on(rx).unwrap_err(), SendFailure::Dropped);
+    }
+
+    #[test]
+    fn closed() {
+        let (tx, rx) = receipt();
+        let mut msg = Outgoing::new(Box::new([]));
+        msg.receipt.replace(tx);
+        drop(msg);
+        assert_eq!(block_on(rx).unwrap_err(), SendFailure::Closed);
+    }
+}